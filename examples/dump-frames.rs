@@ -20,13 +20,21 @@ fn main() -> Result<(), ffmpeg::Error> {
 
         let mut decoder = input.codec().decoder().video()?;
 
+        let (width, height) = (decoder.width(), decoder.height());
+
+        #[cfg(feature = "nonzero")]
+        let (width, height) = (
+            width.expect("unknown width").get(),
+            height.expect("unknown height").get(),
+        );
+
         let mut scaler = Context::get(
             decoder.format(),
-            decoder.width(),
-            decoder.height(),
+            width,
+            height,
             Pixel::RGB24,
-            decoder.width(),
-            decoder.height(),
+            width,
+            height,
             Flags::BILINEAR,
         )?;
 
@@ -59,7 +67,9 @@ fn main() -> Result<(), ffmpeg::Error> {
 
 fn save_file(frame: &Video, index: usize) -> std::result::Result<(), std::io::Error> {
     let mut file = File::create(format!("frame{}.ppm", index))?;
-    file.write_all(format!("P6\n{} {}\n255\n", frame.width(), frame.height()).as_bytes())?;
+    // The size of the only plane of RGB24, known whatever type `width()` returns.
+    let (width, height) = (frame.plane_width(0), frame.plane_height(0));
+    file.write_all(format!("P6\n{} {}\n255\n", width, height).as_bytes())?;
     file.write_all(frame.data(0))?;
     Ok(())
 }
@@ -40,7 +40,7 @@ fn main() {
                 println!("\tframes: {}", stream.frames());
                 println!("\tdisposition: {:?}", stream.disposition());
                 println!("\tdiscard: {:?}", stream.discard());
-                println!("\trate: {}", stream.rate());
+                println!("\trate: {:?}", stream.rate());
 
                 let codec = stream.codec();
                 println!("\tmedium: {:?}", codec.medium());
@@ -51,8 +51,8 @@ fn main() {
                         println!("\tbit_rate: {}", video.bit_rate());
                         println!("\tmax_rate: {}", video.max_bit_rate());
                         println!("\tdelay: {}", video.delay());
-                        println!("\tvideo.width: {}", video.width());
-                        println!("\tvideo.height: {}", video.height());
+                        println!("\tvideo.width: {:?}", video.width());
+                        println!("\tvideo.height: {:?}", video.height());
                         println!("\tvideo.format: {:?}", video.format());
                         println!("\tvideo.has_b_frames: {}", video.has_b_frames());
                         println!("\tvideo.aspect_ratio: {}", video.aspect_ratio());
@@ -72,7 +72,7 @@ fn main() {
                         println!("\tbit_rate: {}", audio.bit_rate());
                         println!("\tmax_rate: {}", audio.max_bit_rate());
                         println!("\tdelay: {}", audio.delay());
-                        println!("\taudio.rate: {}", audio.rate());
+                        println!("\taudio.rate: {:?}", audio.rate());
                         println!("\taudio.channels: {}", audio.channels());
                         println!("\taudio.format: {:?}", audio.format());
                         println!("\taudio.frames: {}", audio.frames());
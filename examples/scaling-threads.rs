@@ -0,0 +1,90 @@
+extern crate ffmpeg_next as ffmpeg;
+
+use std::env;
+use std::time::Instant;
+
+use ffmpeg::format::Pixel;
+use ffmpeg::frame;
+use ffmpeg::software::scaling::{Context, Flags, Parallel};
+
+// Compare single-threaded scaling with `Parallel` on a 4K to 1080p
+// conversion, or the given sizes: scaling-threads [threads] [WxH] [WxH]
+fn main() {
+    ffmpeg::init().unwrap();
+
+    let mut args = env::args().skip(1);
+    let threads = args.next().and_then(|v| v.parse().ok()).unwrap_or(4);
+    let (src_w, src_h) = size(args.next(), (3840, 2160));
+    let (dst_w, dst_h) = size(args.next(), (1920, 1080));
+    let iterations = 50;
+
+    let mut input = frame::Video::new(Pixel::YUV420P, src_w, src_h);
+
+    for index in 0..input.planes() {
+        for (i, byte) in input.data_mut(index).iter_mut().enumerate() {
+            *byte = (i * 7 + index * 31) as u8;
+        }
+    }
+
+    let mut single = Context::get(
+        Pixel::YUV420P,
+        src_w,
+        src_h,
+        Pixel::RGB24,
+        dst_w,
+        dst_h,
+        Flags::BICUBIC,
+    )
+    .unwrap();
+
+    let mut parallel = Parallel::get(
+        Pixel::YUV420P,
+        src_w,
+        src_h,
+        Pixel::RGB24,
+        dst_w,
+        dst_h,
+        Flags::BICUBIC,
+        threads,
+    )
+    .unwrap();
+
+    let mut output = frame::Video::empty();
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        single.run(&input, &mut output).unwrap();
+    }
+    let single_time = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        parallel.run(&input, &mut output).unwrap();
+    }
+    let parallel_time = start.elapsed();
+
+    println!(
+        "{}x{} -> {}x{}, {} frames",
+        src_w, src_h, dst_w, dst_h, iterations
+    );
+    println!("single: {:?} per frame", single_time / iterations);
+    println!(
+        "parallel ({} bands): {:?} per frame, {:.2}x",
+        parallel.threads(),
+        parallel_time / iterations,
+        single_time.as_secs_f64() / parallel_time.as_secs_f64()
+    );
+}
+
+fn size(value: Option<String>, default: (u32, u32)) -> (u32, u32) {
+    value
+        .and_then(|v| {
+            let mut parts = v.split('x').map(|p| p.parse().ok());
+
+            match (parts.next(), parts.next()) {
+                (Some(Some(w)), Some(Some(h))) => Some((w, h)),
+                _ => None,
+            }
+        })
+        .unwrap_or(default)
+}
@@ -13,10 +13,16 @@ fn filter(
 ) -> Result<filter::Graph, ffmpeg::Error> {
     let mut filter = filter::Graph::new();
 
+    let (input_rate, output_rate) = (decoder.rate(), encoder.rate());
+
+    // Both are known once the decoder is opened and the encoder set up.
+    #[cfg(feature = "nonzero")]
+    let (input_rate, output_rate) = (input_rate.unwrap().get(), output_rate.unwrap().get());
+
     let args = format!(
         "time_base={}:sample_rate={}:sample_fmt={}:channel_layout=0x{:x}",
         decoder.time_base(),
-        decoder.rate(),
+        input_rate,
         decoder.format().name(),
         decoder.channel_layout().bits()
     );
@@ -29,7 +35,7 @@ fn filter(
 
         out.set_sample_format(encoder.format());
         out.set_channel_layout(encoder.channel_layout());
-        out.set_sample_rate(encoder.rate());
+        out.set_sample_rate(output_rate);
     }
 
     filter.output("in", 0)?.input("out", 0)?.parse(spec)?;
@@ -95,7 +101,12 @@ fn transcoder<P: AsRef<Path>>(
         encoder.set_flags(ffmpeg::codec::flag::Flags::GLOBAL_HEADER);
     }
 
-    encoder.set_rate(decoder.rate() as i32);
+    let rate = decoder.rate();
+
+    #[cfg(feature = "nonzero")]
+    let rate = rate.expect("unknown sample rate").get();
+
+    encoder.set_rate(rate as i32);
     encoder.set_channel_layout(channel_layout);
     encoder.set_channels(channel_layout.channels());
     encoder.set_format(
@@ -108,8 +119,8 @@ fn transcoder<P: AsRef<Path>>(
     encoder.set_bit_rate(decoder.bit_rate());
     encoder.set_max_bit_rate(decoder.max_bit_rate());
 
-    encoder.set_time_base((1, decoder.rate() as i32));
-    output.set_time_base((1, decoder.rate() as i32));
+    encoder.set_time_base((1, rate as i32));
+    output.set_time_base((1, rate as i32));
 
     let encoder = encoder.open_as(codec)?;
     output.set_parameters(&encoder);
@@ -50,8 +50,16 @@ impl Transcoder {
         let decoder = ist.codec().decoder().video()?;
         let mut ost = octx.add_stream(encoder::find(codec::Id::H264))?;
         let mut encoder = ost.codec().encoder().video()?;
-        encoder.set_height(decoder.height());
-        encoder.set_width(decoder.width());
+        let (width, height) = (decoder.width(), decoder.height());
+
+        #[cfg(feature = "nonzero")]
+        let (width, height) = (
+            width.expect("unknown width").get(),
+            height.expect("unknown height").get(),
+        );
+
+        encoder.set_height(height);
+        encoder.set_width(width);
         encoder.set_aspect_ratio(decoder.aspect_ratio());
         encoder.set_format(decoder.format());
         encoder.set_frame_rate(decoder.frame_rate());
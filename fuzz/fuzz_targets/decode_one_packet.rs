@@ -0,0 +1,44 @@
+//! Run with `cargo fuzz run decode_one_packet` from the repository root.
+//!
+//! The first byte of the input picks the decoder, the next two give the
+//! length of the extradata that follows, and the rest is the packet.
+
+#![no_main]
+
+use ffmpeg_next::codec::Id;
+use ffmpeg_next::decoder::fuzz::{decode_one_packet, Limits};
+use libfuzzer_sys::fuzz_target;
+
+const DECODERS: &[Id] = &[
+    Id::H264,
+    Id::HEVC,
+    Id::VP8,
+    Id::VP9,
+    Id::AV1,
+    Id::MPEG2VIDEO,
+    Id::MJPEG,
+    Id::PNG,
+    Id::AAC,
+    Id::MP3,
+    Id::OPUS,
+    Id::VORBIS,
+    Id::FLAC,
+];
+
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 3 {
+        return;
+    }
+
+    let id = DECODERS[data[0] as usize % DECODERS.len()];
+    let length = u16::from_le_bytes([data[1], data[2]]) as usize;
+    let data = &data[3..];
+
+    let (extradata, packet) = if length > 0 && length <= data.len() {
+        (Some(&data[..length]), &data[length..])
+    } else {
+        (None, data)
+    };
+
+    let _ = decode_one_packet(id, extradata, packet, &Limits::default());
+});
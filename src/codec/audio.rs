@@ -47,6 +47,80 @@ impl Audio {
             }
         }
     }
+
+    /// Whether the codec takes `rate`, codecs listing no rates being assumed
+    /// to take any.
+    pub fn supports_rate(&self, rate: i32) -> bool {
+        self.rates()
+            .map_or(true, |mut rates| rates.any(|r| r == rate))
+    }
+
+    pub fn supports_format(&self, format: format::Sample) -> bool {
+        self.formats()
+            .map_or(true, |mut formats| formats.any(|f| f == format))
+    }
+
+    pub fn supports_channel_layout(&self, layout: ChannelLayout) -> bool {
+        self.channel_layouts()
+            .map_or(true, |mut layouts| layouts.any(|l| l == layout))
+    }
+
+    /// `rate` if supported, else the lowest supported rate above it, else
+    /// the highest one, so that resampling never loses bandwidth when it
+    /// can be avoided.
+    pub fn pick_best_rate(&self, rate: i32) -> i32 {
+        let rates = match self.rates() {
+            Some(rates) => rates.collect::<Vec<_>>(),
+            None => return rate,
+        };
+
+        if rates.contains(&rate) {
+            return rate;
+        }
+
+        rates
+            .iter()
+            .filter(|&&r| r > rate)
+            .min()
+            .or_else(|| rates.iter().max())
+            .cloned()
+            .unwrap_or(rate)
+    }
+
+    /// `format` if supported, else its packed or planar counterpart, else
+    /// the first supported format with samples at least as large, else the
+    /// first supported one.
+    pub fn pick_best_format(&self, format: format::Sample) -> format::Sample {
+        let formats = match self.formats() {
+            Some(formats) => formats.collect::<Vec<_>>(),
+            None => return format,
+        };
+
+        let counterpart = if format.is_planar() {
+            format.packed()
+        } else {
+            format.planar()
+        };
+
+        formats
+            .iter()
+            .find(|&&f| f == format)
+            .or_else(|| formats.iter().find(|&&f| f == counterpart))
+            .or_else(|| formats.iter().find(|f| f.bytes() >= format.bytes()))
+            .or_else(|| formats.first())
+            .cloned()
+            .unwrap_or(format)
+    }
+
+    /// `layout` if supported, else the supported layout with the most
+    /// channels not exceeding its count.
+    pub fn pick_best_channel_layout(&self, layout: ChannelLayout) -> ChannelLayout {
+        match self.channel_layouts() {
+            Some(_) if self.supports_channel_layout(layout) => layout,
+            Some(layouts) => layouts.best(layout.channels()),
+            None => layout,
+        }
+    }
 }
 
 impl Deref for Audio {
@@ -0,0 +1,150 @@
+use std::ptr;
+
+use super::Filter;
+use codec::Parameters;
+use ffi::*;
+use packet::Mut;
+use {Dictionary, Error, Packet, Rational};
+
+/// An instance of a bitstream filter, such as `h264_mp4toannexb` to remux
+/// H.264 from MP4 into MPEG-TS.
+pub struct Context {
+    ptr: *mut AVBSFContext,
+}
+
+unsafe impl Send for Context {}
+
+impl Context {
+    pub unsafe fn wrap(ptr: *mut AVBSFContext) -> Self {
+        Context { ptr }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVBSFContext {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVBSFContext {
+        self.ptr
+    }
+}
+
+impl Context {
+    /// Set up `filter` for packets described by `parameters` and timestamped
+    /// in `time_base`, typically those of the input stream.
+    pub fn new(
+        filter: &Filter,
+        parameters: &Parameters,
+        time_base: Rational,
+    ) -> Result<Self, Error> {
+        Context::new_with(filter, parameters, time_base, Dictionary::new())
+    }
+
+    /// Like `new`, with filter private options such as `remove` for
+    /// `extract_extradata`.
+    pub fn new_with(
+        filter: &Filter,
+        parameters: &Parameters,
+        time_base: Rational,
+        options: Dictionary,
+    ) -> Result<Self, Error> {
+        unsafe {
+            let mut ptr = ptr::null_mut();
+
+            match av_bsf_alloc(filter.as_ptr(), &mut ptr) {
+                0 => (),
+                e => return Err(Error::from(e)),
+            }
+
+            let mut context = Context::wrap(ptr);
+
+            match avcodec_parameters_copy((*ptr).par_in, parameters.as_ptr()) {
+                e if e < 0 => return Err(Error::from(e)),
+                _ => (),
+            }
+
+            (*ptr).time_base_in = time_base.into();
+
+            if !(*ptr).priv_data.is_null() {
+                let mut opts = options.disown();
+                let res = av_opt_set_dict((*ptr).priv_data, &mut opts);
+
+                Dictionary::own(opts);
+
+                if res < 0 {
+                    return Err(Error::from(res));
+                }
+            }
+
+            match av_bsf_init(context.as_mut_ptr()) {
+                0 => Ok(context),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    pub fn filter(&self) -> Filter {
+        unsafe { Filter::wrap((*self.as_ptr()).filter) }
+    }
+
+    /// Parameters of the filtered packets, to give to the output stream.
+    pub fn parameters(&self) -> Parameters {
+        unsafe {
+            let mut parameters = Parameters::new();
+            avcodec_parameters_copy(parameters.as_mut_ptr(), (*self.as_ptr()).par_out);
+
+            parameters
+        }
+    }
+
+    /// Time base of the filtered packets.
+    pub fn time_base(&self) -> Rational {
+        unsafe { Rational::from((*self.as_ptr()).time_base_out) }
+    }
+
+    /// Submit a packet for filtering, taking its data; `packet` is left empty.
+    pub fn send(&mut self, packet: &mut Packet) -> Result<(), Error> {
+        unsafe {
+            match av_bsf_send_packet(self.as_mut_ptr(), packet.as_mut_ptr()) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Signal the end of the stream, so the remaining packets can be
+    /// received.
+    pub fn send_eof(&mut self) -> Result<(), Error> {
+        unsafe {
+            match av_bsf_send_packet(self.as_mut_ptr(), ptr::null_mut()) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Retrieve a filtered packet, failing with `EAGAIN` when more input is
+    /// needed and `Eof` once drained.
+    pub fn receive(&mut self, packet: &mut Packet) -> Result<(), Error> {
+        unsafe {
+            match av_bsf_receive_packet(self.as_mut_ptr(), packet.as_mut_ptr()) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Drop buffered packets, as after a seek.
+    pub fn flush(&mut self) {
+        unsafe {
+            av_bsf_flush(self.as_mut_ptr());
+        }
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        unsafe {
+            av_bsf_free(&mut self.ptr);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::ffi::CStr;
+use std::str::from_utf8_unchecked;
+
+use codec::Id;
+use ffi::AVCodecID::AV_CODEC_ID_NONE;
+use ffi::*;
+
+#[derive(Eq, PartialEq, Clone, Copy)]
+pub struct Filter {
+    ptr: *const AVBitStreamFilter,
+}
+
+unsafe impl Send for Filter {}
+unsafe impl Sync for Filter {}
+
+impl Filter {
+    pub unsafe fn wrap(ptr: *const AVBitStreamFilter) -> Self {
+        Filter { ptr }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVBitStreamFilter {
+        self.ptr
+    }
+}
+
+impl Filter {
+    pub fn name(&self) -> &str {
+        unsafe { from_utf8_unchecked(CStr::from_ptr((*self.as_ptr()).name).to_bytes()) }
+    }
+
+    /// Codecs the filter works on, `None` if it accepts any.
+    pub fn codecs(&self) -> Option<Vec<Id>> {
+        unsafe {
+            let mut ptr = (*self.as_ptr()).codec_ids;
+
+            if ptr.is_null() {
+                return None;
+            }
+
+            let mut codecs = Vec::new();
+
+            while *ptr != AV_CODEC_ID_NONE {
+                codecs.push(Id::from(*ptr));
+                ptr = ptr.offset(1);
+            }
+
+            Some(codecs)
+        }
+    }
+}
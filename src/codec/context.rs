@@ -1,25 +1,55 @@
 use std::any::Any;
 use std::ptr;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::decoder::Decoder;
 use super::encoder::Encoder;
-use super::{threading, Compliance, Debug, Flags, Id, Parameters};
+use super::{threading, Compliance, Debug, Flags, Flags2, Id, Parameters};
 use ffi::*;
-use libc::c_int;
+use libc::{c_int, c_uint, c_void};
 use media;
 use {Codec, Error};
 
+/// A codec context, and through it every decoder and encoder.
+///
+/// Contexts are `Send` but not `Sync`: libavcodec forbids concurrent calls on
+/// the same context, but a context may be moved to and used from any single
+/// thread. A context borrowed from a format stream keeps its format context
+/// alive through an atomic reference count; to hand decoding or encoding to
+/// worker threads without sharing anything with the format context, build
+/// the context with `Context::new()` and `set_parameters()` instead.
 pub struct Context {
     ptr: *mut AVCodecContext,
-    owner: Option<Rc<dyn Any>>,
+    owner: Option<Arc<dyn Any + Send + Sync>>,
+    state: State,
+}
+
+/// Where a decoder or encoder is in the end of stream protocol.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum State {
+    /// Taking input.
+    Running,
+
+    /// End of stream was sent, buffered output is still coming.
+    Draining,
+
+    /// Every output was received, the codec takes no more input until it is
+    /// flushed.
+    Finished,
 }
 
 unsafe impl Send for Context {}
 
 impl Context {
-    pub unsafe fn wrap(ptr: *mut AVCodecContext, owner: Option<Rc<dyn Any>>) -> Self {
-        Context { ptr, owner }
+    pub unsafe fn wrap(
+        ptr: *mut AVCodecContext,
+        owner: Option<Arc<dyn Any + Send + Sync>>,
+    ) -> Self {
+        Context {
+            ptr,
+            owner,
+            state: State::Running,
+        }
     }
 
     pub unsafe fn as_ptr(&self) -> *const AVCodecContext {
@@ -37,10 +67,43 @@ impl Context {
             Context {
                 ptr: avcodec_alloc_context3(ptr::null()),
                 owner: None,
+                state: State::Running,
             }
         }
     }
 
+    /// Where the context is in the end of stream protocol, as tracked by
+    /// `send_eof`, receiving and `flush`.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Record a transition of the end of stream protocol, for the decoders
+    /// and encoders built on the context.
+    #[doc(hidden)]
+    pub unsafe fn set_state(&mut self, value: State) {
+        self.state = value;
+    }
+
+    // The fields behind the width, height and rate getters of decoders and
+    // encoders, 0 when unset, whatever type the `nonzero` feature makes the
+    // getters return.
+
+    #[doc(hidden)]
+    pub fn raw_width(&self) -> u32 {
+        unsafe { (*self.as_ptr()).width as u32 }
+    }
+
+    #[doc(hidden)]
+    pub fn raw_height(&self) -> u32 {
+        unsafe { (*self.as_ptr()).height as u32 }
+    }
+
+    #[doc(hidden)]
+    pub fn raw_rate(&self) -> u32 {
+        unsafe { (*self.as_ptr()).sample_rate as u32 }
+    }
+
     pub fn decoder(self) -> Decoder {
         Decoder(self)
     }
@@ -63,12 +126,22 @@ impl Context {
         unsafe { media::Type::from((*self.as_ptr()).codec_type) }
     }
 
+    pub fn flags(&self) -> Flags {
+        unsafe { Flags::from_bits_truncate((*self.as_ptr()).flags as c_uint) }
+    }
+
     pub fn set_flags(&mut self, value: Flags) {
         unsafe {
             (*self.as_mut_ptr()).flags = value.bits() as c_int;
         }
     }
 
+    pub fn set_flags2(&mut self, value: Flags2) {
+        unsafe {
+            (*self.as_mut_ptr()).flags2 = value.bits();
+        }
+    }
+
     pub fn id(&self) -> Id {
         unsafe { Id::from((*self.as_ptr()).codec_id) }
     }
@@ -85,6 +158,10 @@ impl Context {
         }
     }
 
+    /// Set how the codec is to be threaded once opened, a count of 0
+    /// letting FFmpeg pick one thread per core. `threading::Type::None`
+    /// disables threading whatever the count; frame threading adds a frame
+    /// of delay per thread, slice threading does not.
     pub fn set_threading(&mut self, config: threading::Config) {
         unsafe {
             (*self.as_mut_ptr()).thread_type = config.kind.into();
@@ -93,6 +170,8 @@ impl Context {
         }
     }
 
+    /// The threading in use, its kind being `threading::Type::None` until
+    /// the codec is opened.
     pub fn threading(&self) -> threading::Config {
         unsafe {
             threading::Config {
@@ -103,6 +182,20 @@ impl Context {
         }
     }
 
+    /// Out of band codec data, such as the `avcC` record of H.264 holding
+    /// its SPS and PPS.
+    pub fn extradata(&self) -> Option<&[u8]> {
+        unsafe { super::extradata((*self.as_ptr()).extradata, (*self.as_ptr()).extradata_size) }
+    }
+
+    /// Replace the extradata with a padded copy of `value`, before opening.
+    pub fn set_extradata(&mut self, value: Option<&[u8]>) -> Result<(), Error> {
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            super::set_extradata(&mut (*ptr).extradata, &mut (*ptr).extradata_size, value)
+        }
+    }
+
     pub fn set_parameters<P: Into<Parameters>>(&mut self, parameters: P) -> Result<(), Error> {
         let parameters = parameters.into();
 
@@ -125,6 +218,8 @@ impl Drop for Context {
     fn drop(&mut self) {
         unsafe {
             if self.owner.is_none() {
+                // Owned by the user as far as libavcodec is concerned.
+                av_freep(&mut (*self.as_mut_ptr()).stats_in as *mut _ as *mut c_void);
                 avcodec_free_context(&mut self.as_mut_ptr());
             }
         }
@@ -142,6 +237,55 @@ impl Clone for Context {
     fn clone_from(&mut self, source: &Self) {
         unsafe {
             avcodec_copy_context(self.as_mut_ptr(), source.as_ptr());
+
+            let stats_in = (*self.as_ptr()).stats_in;
+
+            if !stats_in.is_null() {
+                (*self.as_mut_ptr()).stats_in = av_strdup(stats_in);
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use {decoder, encoder};
+
+    fn assert_send<T: Send>() {}
+
+    #[test]
+    fn test_send() {
+        assert_send::<Context>();
+        assert_send::<Parameters>();
+
+        assert_send::<decoder::Opened>();
+        assert_send::<decoder::Video>();
+        assert_send::<decoder::Audio>();
+        assert_send::<decoder::Subtitle>();
+
+        assert_send::<encoder::Encoder>();
+        assert_send::<encoder::Video>();
+        assert_send::<encoder::Audio>();
+        assert_send::<encoder::Subtitle>();
+    }
+
+    #[test]
+    fn test_eof() {
+        let codec = encoder::find(Id::PCM_S16LE).unwrap();
+        let mut audio = Context::new().encoder().audio().unwrap();
+
+        audio.set_rate(48000);
+        audio.set_format(::format::Sample::I16(::format::sample::Type::Packed));
+        audio.set_channel_layout(::ChannelLayout::STEREO);
+
+        let mut encoder = audio.open_as(codec).unwrap();
+
+        assert_eq!(encoder.state(), State::Running);
+        assert!(encoder.send_eof().is_ok());
+        assert_eq!(encoder.state(), State::Draining);
+        assert!(encoder.send_eof().is_ok());
+        assert_eq!(encoder.finish().count(), 0);
+        assert_eq!(encoder.state(), State::Finished);
+    }
+}
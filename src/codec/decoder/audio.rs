@@ -1,3 +1,5 @@
+#[cfg(feature = "nonzero")]
+use std::num::NonZeroU32;
 use std::ops::{Deref, DerefMut};
 
 use ffi::*;
@@ -37,8 +39,15 @@ impl Audio {
         }
     }
 
+    #[cfg(not(feature = "nonzero"))]
     pub fn rate(&self) -> u32 {
-        unsafe { (*self.as_ptr()).sample_rate as u32 }
+        self.raw_rate()
+    }
+
+    /// `None` until the decoder knows it.
+    #[cfg(feature = "nonzero")]
+    pub fn rate(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_rate())
     }
 
     pub fn channels(&self) -> u16 {
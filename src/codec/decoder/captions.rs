@@ -0,0 +1,85 @@
+use super::{find, Subtitle};
+use codec::{packet, Context, Id};
+use ffi::*;
+use rescale::TIME_BASE;
+use util::frame::side_data;
+use {frame, Error, Packet, Rational, Rescale};
+
+/// Wrap the ATSC A/53 closed caption side data of `frame` into an `EIA_608`
+/// packet, with the frame timestamps in the same time base.
+///
+/// The packet can be fed to `Captions` or written as is to a subtitle stream
+/// with the `EIA_608` codec id, which muxers such as `scc` or `mcc` accept.
+pub fn extract(frame: &frame::Video) -> Option<Packet> {
+    let data = frame.side_data(side_data::Type::A53CC)?;
+
+    if data.data().is_empty() {
+        return None;
+    }
+
+    let mut packet = Packet::copy(data.data());
+    packet.set_pts(frame.timestamp());
+    packet.set_dts(frame.timestamp());
+    packet.set_flags(packet::Flags::KEY);
+
+    Some(packet)
+}
+
+/// Decode closed captions carried by video frames into subtitle cues.
+///
+/// Frames must be pushed in presentation order, as output by a decoder.
+pub struct Captions {
+    decoder: Subtitle,
+    time_base: Rational,
+}
+
+impl Captions {
+    /// Create a caption decoder for frames timestamped in `time_base`.
+    pub fn new(time_base: Rational) -> Result<Self, Error> {
+        let codec = find(Id::EIA_608).ok_or(Error::DecoderNotFound)?;
+        let mut context = Context::new();
+
+        unsafe {
+            (*context.as_mut_ptr()).time_base = TIME_BASE.into();
+            (*context.as_mut_ptr()).pkt_timebase = TIME_BASE.into();
+        }
+
+        Ok(Captions {
+            decoder: context.decoder().open_as(codec)?.subtitle()?,
+            time_base,
+        })
+    }
+
+    /// Feed the captions of `frame`, returning a cue when one is complete.
+    ///
+    /// Cue timestamps are in `AV_TIME_BASE` units.
+    pub fn push(&mut self, frame: &frame::Video) -> Result<Option<::Subtitle>, Error> {
+        match extract(frame) {
+            Some(packet) => self.decode(packet),
+            None => Ok(None),
+        }
+    }
+
+    /// Feed a packet produced by `extract`, or read from an `EIA_608` stream
+    /// in the time base given at creation.
+    pub fn decode(&mut self, mut packet: Packet) -> Result<Option<::Subtitle>, Error> {
+        packet.set_pts(
+            packet
+                .pts()
+                .map(|pts| pts.rescale(self.time_base, TIME_BASE)),
+        );
+        packet.set_dts(
+            packet
+                .dts()
+                .map(|dts| dts.rescale(self.time_base, TIME_BASE)),
+        );
+
+        let mut subtitle = ::Subtitle::new();
+
+        if self.decoder.decode(&packet, &mut subtitle)? {
+            Ok(Some(subtitle))
+        } else {
+            Ok(None)
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use std::ops::{Deref, DerefMut};
 use std::ptr;
 
-use super::{Audio, Check, Conceal, Opened, Subtitle, Video};
+use super::{Audio, Check, Conceal, Idct, Opened, Properties, Subtitle, Video, Workaround};
 use codec::{traits, Context};
 use ffi::*;
-use {Dictionary, Discard, Error, Rational};
+use libc::ENOMEM;
+use {hwcontext, Dictionary, Discard, Error, Rational};
 
 pub struct Decoder(pub Context);
 
@@ -89,6 +90,19 @@ impl Decoder {
         }
     }
 
+    /// Enable workarounds for bugs in files produced by broken encoders.
+    pub fn workaround(&mut self, value: Workaround) {
+        unsafe {
+            (*self.as_mut_ptr()).workaround_bugs = value.bits();
+        }
+    }
+
+    pub fn idct(&mut self, value: Idct) {
+        unsafe {
+            (*self.as_mut_ptr()).idct_algo = value.into();
+        }
+    }
+
     pub fn skip_loop_filter(&mut self, value: Discard) {
         unsafe {
             (*self.as_mut_ptr()).skip_loop_filter = value.into();
@@ -107,9 +121,64 @@ impl Decoder {
         }
     }
 
+    /// Refuse frames larger than `value` pixels, as a guard against
+    /// decompression bombs. Frames over the limit fail to decode with
+    /// `Error::Limit(Limit::Pixels)`.
+    ///
+    /// FFmpeg reports the limit as `EINVAL`, which is reported so when the
+    /// dimensions of the decoder go over the limit, and passed through
+    /// otherwise.
+    pub fn set_max_pixels(&mut self, value: i64) {
+        unsafe {
+            (*self.as_mut_ptr()).max_pixels = value;
+        }
+    }
+
+    pub fn max_pixels(&self) -> i64 {
+        unsafe { (*self.as_ptr()).max_pixels }
+    }
+
+    /// Refuse audio frames with more than `value` samples over all channels,
+    /// failing with `Error::Limit(Limit::Samples)`, as with `set_max_pixels`.
+    #[cfg(feature = "ffmpeg_4_3")]
+    pub fn set_max_samples(&mut self, value: i64) {
+        unsafe {
+            (*self.as_mut_ptr()).max_samples = value;
+        }
+    }
+
+    #[cfg(feature = "ffmpeg_4_3")]
+    pub fn max_samples(&self) -> i64 {
+        unsafe { (*self.as_ptr()).max_samples }
+    }
+
+    /// Decode on `device`. Must be called before opening; the decoder then
+    /// picks the matching hardware pixel format and outputs frames backed by
+    /// surfaces, to be downloaded with `Frame::transfer_data_from`.
+    pub fn set_hw_device_ctx(&mut self, device: &hwcontext::Device) -> Result<(), Error> {
+        unsafe {
+            let ptr = self.as_mut_ptr();
+
+            av_buffer_unref(&mut (*ptr).hw_device_ctx);
+            (*ptr).hw_device_ctx = av_buffer_ref(device.as_ptr() as *mut _);
+
+            if (*ptr).hw_device_ctx.is_null() {
+                Err(Error::Other { errno: ENOMEM })
+            } else {
+                Ok(())
+            }
+        }
+    }
+
     pub fn time_base(&self) -> Rational {
         unsafe { Rational::from((*self.as_ptr()).time_base) }
     }
+
+    /// Properties of the stream discovered while decoding, such as closed
+    /// captions showing up in the first frames that carry them.
+    pub fn properties(&self) -> Properties {
+        unsafe { Properties::from_bits_truncate((*self.as_ptr()).properties) }
+    }
 }
 
 impl Deref for Decoder {
@@ -0,0 +1,117 @@
+//! A narrow entry point for fuzzing decoders through this crate.
+//!
+//! `decode_one_packet` sets up a decoder from scratch, feeds it one packet,
+//! drains it and tears everything down, so each fuzzer input is isolated.
+//! Every failure is returned rather than skipped, and decoded frames are
+//! accounted against `Limits` to catch inputs blowing up memory use. See
+//! the `fuzz` directory of the repository for a `cargo fuzz` harness.
+
+use super::find;
+use codec::{threading, Context, Id};
+use ffi::*;
+use libc::{c_int, ENOMEM};
+use {Error, Frame, Packet};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Limits {
+    /// Largest picture in pixels the decoder may allocate.
+    pub max_pixels: u32,
+
+    /// Decoded frames beyond this count fail the input.
+    pub max_frames: usize,
+
+    /// Total size of the buffers of all decoded frames beyond which the
+    /// input fails with `ENOMEM`.
+    pub max_memory: usize,
+
+    /// Slice threads of the decoder, 1 keeping runs deterministic.
+    pub threads: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_pixels: 4096 * 4096,
+            max_frames: 64,
+            max_memory: 256 * 1024 * 1024,
+            threads: 1,
+        }
+    }
+}
+
+/// What decoding one packet produced.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct Report {
+    pub frames: usize,
+
+    /// Total size of the buffers of the decoded frames.
+    pub memory: usize,
+}
+
+/// Decode `data` as a single packet of codec `id`, with optional codec
+/// `extradata`, then drain the decoder.
+///
+/// Returns `Error::DecoderNotFound` when the linked FFmpeg lacks a decoder
+/// for `id`, and the first error of the decoder otherwise, except for the
+/// end of stream that drain ends with.
+pub fn decode_one_packet(
+    id: Id,
+    extradata: Option<&[u8]>,
+    data: &[u8],
+    limits: &Limits,
+) -> Result<Report, Error> {
+    let codec = find(id).ok_or(Error::DecoderNotFound)?;
+    let mut context = Context::new();
+
+    context
+        .set_threading(threading::Config::count(limits.threads).with_kind(threading::Type::Slice));
+
+    unsafe {
+        let ptr = context.as_mut_ptr();
+
+        (*ptr).max_pixels = i64::from(limits.max_pixels);
+        (*ptr).err_recognition |= AV_EF_EXPLODE as c_int;
+    }
+
+    context.set_extradata(extradata)?;
+
+    let mut decoder = context.decoder().open_as(codec)?;
+    let mut report = Report::default();
+
+    decoder.send_packet(&Packet::copy(data))?;
+    decoder.send_eof()?;
+
+    loop {
+        let mut frame = unsafe { Frame::empty() };
+
+        match decoder.receive_frame(&mut frame) {
+            Ok(()) => (),
+            Err(Error::Eof) => return Ok(report),
+            Err(e) => return Err(e),
+        }
+
+        report.frames += 1;
+        report.memory += size(&frame);
+
+        if report.frames > limits.max_frames || report.memory > limits.max_memory {
+            return Err(Error::Other { errno: ENOMEM });
+        }
+    }
+}
+
+fn size(frame: &Frame) -> usize {
+    unsafe {
+        let frame = &*frame.as_ptr();
+        let mut size = 0;
+
+        for buf in frame.buf.iter().filter(|buf| !buf.is_null()) {
+            size += (**buf).size as usize;
+        }
+
+        for index in 0..frame.nb_extended_buf as isize {
+            size += (**frame.extended_buf.offset(index)).size as usize;
+        }
+
+        size
+    }
+}
@@ -0,0 +1,64 @@
+use ffi::*;
+use libc::c_int;
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Idct {
+    Auto,
+    Int,
+    Simple,
+    SimpleMMX,
+    ARM,
+    AltiVec,
+    SimpleARM,
+    XviD,
+    SimpleARMv5TE,
+    SimpleARMv6,
+    FAAN,
+    SimpleNEON,
+    None,
+    SimpleAuto,
+}
+
+impl From<c_int> for Idct {
+    fn from(value: c_int) -> Idct {
+        match value {
+            FF_IDCT_AUTO => Idct::Auto,
+            FF_IDCT_INT => Idct::Int,
+            FF_IDCT_SIMPLE => Idct::Simple,
+            FF_IDCT_SIMPLEMMX => Idct::SimpleMMX,
+            FF_IDCT_ARM => Idct::ARM,
+            FF_IDCT_ALTIVEC => Idct::AltiVec,
+            FF_IDCT_SIMPLEARM => Idct::SimpleARM,
+            FF_IDCT_XVID => Idct::XviD,
+            FF_IDCT_SIMPLEARMV5TE => Idct::SimpleARMv5TE,
+            FF_IDCT_SIMPLEARMV6 => Idct::SimpleARMv6,
+            FF_IDCT_FAAN => Idct::FAAN,
+            FF_IDCT_SIMPLENEON => Idct::SimpleNEON,
+            FF_IDCT_NONE => Idct::None,
+            FF_IDCT_SIMPLEAUTO => Idct::SimpleAuto,
+
+            _ => Idct::Auto,
+        }
+    }
+}
+
+impl From<Idct> for c_int {
+    fn from(value: Idct) -> c_int {
+        match value {
+            Idct::Auto => FF_IDCT_AUTO,
+            Idct::Int => FF_IDCT_INT,
+            Idct::Simple => FF_IDCT_SIMPLE,
+            Idct::SimpleMMX => FF_IDCT_SIMPLEMMX,
+            Idct::ARM => FF_IDCT_ARM,
+            Idct::AltiVec => FF_IDCT_ALTIVEC,
+            Idct::SimpleARM => FF_IDCT_SIMPLEARM,
+            Idct::XviD => FF_IDCT_XVID,
+            Idct::SimpleARMv5TE => FF_IDCT_SIMPLEARMV5TE,
+            Idct::SimpleARMv6 => FF_IDCT_SIMPLEARMV6,
+            Idct::FAAN => FF_IDCT_FAAN,
+            Idct::SimpleNEON => FF_IDCT_SIMPLENEON,
+            Idct::None => FF_IDCT_NONE,
+            Idct::SimpleAuto => FF_IDCT_SIMPLEAUTO,
+        }
+    }
+}
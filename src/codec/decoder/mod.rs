@@ -10,6 +10,9 @@ pub use self::audio::Audio;
 pub mod subtitle;
 pub use self::subtitle::Subtitle;
 
+pub mod captions;
+pub use self::captions::Captions;
+
 pub mod slice;
 
 pub mod conceal;
@@ -18,8 +21,20 @@ pub use self::conceal::Conceal;
 pub mod check;
 pub use self::check::Check;
 
+pub mod workaround;
+pub use self::workaround::Workaround;
+
+pub mod idct;
+pub use self::idct::Idct;
+
+pub mod properties;
+pub use self::properties::Properties;
+
 pub mod opened;
-pub use self::opened::Opened;
+pub use self::opened::{Drain, Opened};
+
+pub mod fuzz;
+pub use self::fuzz::decode_one_packet;
 
 use std::ffi::CString;
 
@@ -2,8 +2,10 @@ use std::ops::{Deref, DerefMut};
 use std::ptr;
 
 use super::{Audio, Decoder, Subtitle, Video};
-use codec::{Context, Profile};
+use codec::{Context, Profile, State};
 use ffi::*;
+use libc::EINVAL;
+use util::error::Limit;
 use {media, packet, Error, Frame, Rational};
 
 pub struct Opened(pub Decoder);
@@ -36,32 +38,90 @@ impl Opened {
     pub fn send_packet<P: packet::Ref>(&mut self, packet: &P) -> Result<(), Error> {
         unsafe {
             match avcodec_send_packet(self.as_mut_ptr(), packet.as_ptr()) {
-                e if e < 0 => Err(Error::from(e)),
+                e if e < 0 => Err(self.limit(Error::from(e))),
                 _ => Ok(()),
             }
         }
     }
 
     /// Sends a NULL packet to the decoder to signal end of stream and enter
-    /// draining mode.
+    /// draining mode. Does nothing when already draining or drained, up to
+    /// the next `flush`.
     pub fn send_eof(&mut self) -> Result<(), Error> {
+        if self.state() != State::Running {
+            return Ok(());
+        }
+
         unsafe {
             match avcodec_send_packet(self.as_mut_ptr(), ptr::null()) {
                 e if e < 0 => Err(Error::from(e)),
-                _ => Ok(()),
+                _ => {
+                    self.set_state(State::Draining);
+                    Ok(())
+                }
             }
         }
     }
 
+    /// Receive the next decoded frame, failing with `Error::Other { errno:
+    /// EAGAIN }` when more packets are needed, and with `Error::Eof` once
+    /// drained.
     pub fn receive_frame(&mut self, frame: &mut Frame) -> Result<(), Error> {
         unsafe {
             match avcodec_receive_frame(self.as_mut_ptr(), frame.as_mut_ptr()) {
-                e if e < 0 => Err(Error::from(e)),
+                e if e < 0 => match Error::from(e) {
+                    Error::Eof => {
+                        self.set_state(State::Finished);
+                        Err(Error::Eof)
+                    }
+
+                    error => Err(self.limit(error)),
+                },
                 _ => Ok(()),
             }
         }
     }
 
+    // FFmpeg reports frames over `max_pixels` or `max_samples` with a bare
+    // `EINVAL`, which is only put down to the limit when the size the
+    // context was set to actually goes over it.
+    fn limit(&self, error: Error) -> Error {
+        if error != (Error::Other { errno: EINVAL }) {
+            return error;
+        }
+
+        unsafe {
+            let ptr = self.as_ptr();
+
+            match self.medium() {
+                media::Type::Video
+                    if i64::from((*ptr).width) * i64::from((*ptr).height) > self.max_pixels() =>
+                {
+                    Error::Limit(Limit::Pixels)
+                }
+
+                #[cfg(feature = "ffmpeg_4_3")]
+                media::Type::Audio
+                    if i64::from((*ptr).frame_size) * i64::from((*ptr).channels)
+                        > self.max_samples() =>
+                {
+                    Error::Limit(Limit::Samples)
+                }
+
+                _ => error,
+            }
+        }
+    }
+
+    /// Signal end of stream unless done already, and go over the frames
+    /// still buffered in the decoder.
+    pub fn drain(&mut self) -> Drain {
+        Drain {
+            decoder: self,
+            done: false,
+        }
+    }
+
     pub fn bit_rate(&self) -> usize {
         unsafe { (*self.as_ptr()).bit_rate as usize }
     }
@@ -86,9 +146,49 @@ impl Opened {
         }
     }
 
+    /// Drop buffered frames and state, ready for packets following a seek,
+    /// or a new stream after draining.
     pub fn flush(&mut self) {
         unsafe {
             avcodec_flush_buffers(self.as_mut_ptr());
+            self.set_state(State::Running);
+        }
+    }
+}
+
+/// Frames left in a decoder after the end of stream, see `Opened::drain`.
+pub struct Drain<'a> {
+    decoder: &'a mut Opened,
+    done: bool,
+}
+
+impl<'a> Iterator for Drain<'a> {
+    type Item = Result<Frame, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if let Err(error) = self.decoder.send_eof() {
+            self.done = true;
+            return Some(Err(error));
+        }
+
+        let mut frame = unsafe { Frame::empty() };
+
+        match self.decoder.receive_frame(&mut frame) {
+            Ok(()) => Some(Ok(frame)),
+
+            Err(Error::Eof) => {
+                self.done = true;
+                None
+            }
+
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
         }
     }
 }
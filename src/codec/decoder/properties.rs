@@ -0,0 +1,9 @@
+use ffi::*;
+use libc::c_int;
+
+bitflags! {
+    pub struct Properties: c_int {
+        const LOSSLESS        = FF_CODEC_PROPERTY_LOSSLESS;
+        const CLOSED_CAPTIONS = FF_CODEC_PROPERTY_CLOSED_CAPTIONS;
+    }
+}
@@ -18,6 +18,9 @@ impl Subtitle {
         unsafe {
             let mut got: c_int = 0;
 
+            // The decoder overwrites `out` without releasing what it holds.
+            avsubtitle_free(out.as_mut_ptr());
+
             match avcodec_decode_subtitle2(
                 self.as_mut_ptr(),
                 out.as_mut_ptr(),
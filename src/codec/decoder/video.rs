@@ -1,3 +1,5 @@
+#[cfg(feature = "nonzero")]
+use std::num::NonZeroU32;
 use std::ops::{Deref, DerefMut};
 
 use ffi::*;
@@ -39,12 +41,26 @@ impl Video {
         }
     }
 
+    #[cfg(not(feature = "nonzero"))]
     pub fn width(&self) -> u32 {
-        unsafe { (*self.as_ptr()).width as u32 }
+        self.raw_width()
     }
 
+    /// `None` until the decoder knows it.
+    #[cfg(feature = "nonzero")]
+    pub fn width(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_width())
+    }
+
+    #[cfg(not(feature = "nonzero"))]
     pub fn height(&self) -> u32 {
-        unsafe { (*self.as_ptr()).height as u32 }
+        self.raw_height()
+    }
+
+    /// `None` until the decoder knows it.
+    #[cfg(feature = "nonzero")]
+    pub fn height(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_height())
     }
 
     pub fn format(&self) -> format::Pixel {
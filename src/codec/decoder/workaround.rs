@@ -0,0 +1,22 @@
+use ffi::*;
+use libc::c_int;
+
+bitflags! {
+    pub struct Workaround: c_int {
+        const AUTODETECT       = FF_BUG_AUTODETECT;
+        const XVID_ILACE       = FF_BUG_XVID_ILACE;
+        const UMP4             = FF_BUG_UMP4;
+        const NO_PADDING       = FF_BUG_NO_PADDING;
+        const AMV              = FF_BUG_AMV;
+        const QPEL_CHROMA      = FF_BUG_QPEL_CHROMA;
+        const STD_QPEL         = FF_BUG_STD_QPEL;
+        const QPEL_CHROMA2     = FF_BUG_QPEL_CHROMA2;
+        const DIRECT_BLOCKSIZE = FF_BUG_DIRECT_BLOCKSIZE;
+        const EDGE             = FF_BUG_EDGE;
+        const HPEL_CHROMA      = FF_BUG_HPEL_CHROMA;
+        const DC_CLIP          = FF_BUG_DC_CLIP;
+        const MS               = FF_BUG_MS;
+        const TRUNCATED        = FF_BUG_TRUNCATED;
+        const IEDGE            = FF_BUG_IEDGE;
+    }
+}
@@ -0,0 +1,177 @@
+use std::ffi::{CStr, CString};
+use std::ptr;
+use std::str::from_utf8_unchecked;
+
+use super::codec::ProfileIter;
+use super::Id;
+use ffi::*;
+use libc::c_int;
+use media;
+
+bitflags! {
+    pub struct Properties: c_int {
+        const INTRA_ONLY = AV_CODEC_PROP_INTRA_ONLY;
+        const LOSSY      = AV_CODEC_PROP_LOSSY;
+        const LOSSLESS   = AV_CODEC_PROP_LOSSLESS;
+        const REORDER    = AV_CODEC_PROP_REORDER;
+        const BITMAP_SUB = AV_CODEC_PROP_BITMAP_SUB;
+        const TEXT_SUB   = AV_CODEC_PROP_TEXT_SUB;
+    }
+}
+
+/// Static properties of a codec id, whether or not FFmpeg was built with an
+/// encoder or decoder for it.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub struct Descriptor {
+    ptr: *const AVCodecDescriptor,
+}
+
+unsafe impl Send for Descriptor {}
+unsafe impl Sync for Descriptor {}
+
+impl Descriptor {
+    pub unsafe fn wrap(ptr: *const AVCodecDescriptor) -> Self {
+        Descriptor { ptr }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVCodecDescriptor {
+        self.ptr
+    }
+}
+
+impl Descriptor {
+    pub fn id(&self) -> Id {
+        unsafe { Id::from((*self.as_ptr()).id) }
+    }
+
+    pub fn medium(&self) -> media::Type {
+        unsafe { media::Type::from((*self.as_ptr()).type_) }
+    }
+
+    pub fn name(&self) -> &'static str {
+        unsafe { from_utf8_unchecked(CStr::from_ptr((*self.as_ptr()).name).to_bytes()) }
+    }
+
+    pub fn description(&self) -> &'static str {
+        unsafe {
+            let long_name = (*self.as_ptr()).long_name;
+
+            if long_name.is_null() {
+                ""
+            } else {
+                from_utf8_unchecked(CStr::from_ptr(long_name).to_bytes())
+            }
+        }
+    }
+
+    pub fn properties(&self) -> Properties {
+        unsafe { Properties::from_bits_truncate((*self.as_ptr()).props) }
+    }
+
+    pub fn is_intra_only(&self) -> bool {
+        self.properties().contains(Properties::INTRA_ONLY)
+    }
+
+    pub fn is_lossy(&self) -> bool {
+        self.properties().contains(Properties::LOSSY)
+    }
+
+    pub fn is_lossless(&self) -> bool {
+        self.properties().contains(Properties::LOSSLESS)
+    }
+
+    /// MIME types of the codec, mostly set for image and subtitle formats.
+    pub fn mime_types(&self) -> Vec<&'static str> {
+        let mut types = Vec::new();
+
+        unsafe {
+            let mut ptr = (*self.as_ptr()).mime_types;
+
+            if ptr.is_null() {
+                return types;
+            }
+
+            while !(*ptr).is_null() {
+                types.push(from_utf8_unchecked(CStr::from_ptr(*ptr).to_bytes()));
+                ptr = ptr.offset(1);
+            }
+        }
+
+        types
+    }
+
+    /// Every profile the codec defines, not only those an implementation
+    /// supports.
+    pub fn profiles(&self) -> Option<ProfileIter> {
+        unsafe {
+            if (*self.as_ptr()).profiles.is_null() {
+                None
+            } else {
+                Some(ProfileIter::new(self.id(), (*self.as_ptr()).profiles))
+            }
+        }
+    }
+}
+
+pub fn find(id: Id) -> Option<Descriptor> {
+    unsafe {
+        let ptr = avcodec_descriptor_get(id.into());
+
+        if ptr.is_null() {
+            None
+        } else {
+            Some(Descriptor::wrap(ptr))
+        }
+    }
+}
+
+pub fn find_by_name(name: &str) -> Option<Descriptor> {
+    unsafe {
+        let name = CString::new(name).unwrap();
+        let ptr = avcodec_descriptor_get_by_name(name.as_ptr());
+
+        if ptr.is_null() {
+            None
+        } else {
+            Some(Descriptor::wrap(ptr))
+        }
+    }
+}
+
+/// Every codec descriptor known to libavcodec, ordered by id.
+pub fn list() -> DescriptorIter {
+    DescriptorIter::new()
+}
+
+pub struct DescriptorIter {
+    ptr: *const AVCodecDescriptor,
+}
+
+impl DescriptorIter {
+    pub fn new() -> Self {
+        DescriptorIter { ptr: ptr::null() }
+    }
+}
+
+impl Default for DescriptorIter {
+    fn default() -> Self {
+        DescriptorIter::new()
+    }
+}
+
+impl Iterator for DescriptorIter {
+    type Item = Descriptor;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            let ptr = avcodec_descriptor_next(self.ptr);
+
+            if ptr.is_null() {
+                None
+            } else {
+                self.ptr = ptr;
+                Some(Descriptor::wrap(ptr))
+            }
+        }
+    }
+}
@@ -1,11 +1,15 @@
+#[cfg(feature = "nonzero")]
+use std::num::NonZeroU32;
 use std::ops::{Deref, DerefMut};
 use std::ptr;
 
 use ffi::*;
 use libc::c_int;
 
+use super::private::Private;
+pub use super::private::{AacCoder, AacOptions};
 use super::Encoder as Super;
-use codec::{traits, Context};
+use codec::{traits, Capabilities, Context};
 use util::format;
 use {frame, packet, ChannelLayout, Dictionary, Error};
 
@@ -70,14 +74,27 @@ impl Audio {
         }
     }
 
+    /// Open the encoder the typed `options` belong to, with them, failing
+    /// with `Error::EncoderNotFound` when FFmpeg was built without it.
+    pub fn open_private<P: Private>(self, options: &P) -> Result<Encoder, Error> {
+        self.open_as_with(options.encoder(), options.to_dictionary())
+    }
+
     pub fn set_rate(&mut self, rate: i32) {
         unsafe {
             (*self.as_mut_ptr()).sample_rate = rate;
         }
     }
 
+    #[cfg(not(feature = "nonzero"))]
     pub fn rate(&self) -> u32 {
-        unsafe { (*self.as_ptr()).sample_rate as u32 }
+        self.raw_rate()
+    }
+
+    /// `None` until set.
+    #[cfg(feature = "nonzero")]
+    pub fn rate(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_rate())
     }
 
     pub fn set_format(&mut self, value: format::Sample) {
@@ -137,6 +154,22 @@ impl AsMut<Context> for Audio {
     }
 }
 
+/// What to do with a frame shorter than the encoder frame size, which is
+/// normally the last one of a stream.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum LastFrame {
+    /// Pad with silence up to the frame size, lengthening the stream by the
+    /// padding.
+    Pad,
+
+    /// Drop the frame, truncating the stream.
+    Drop,
+
+    /// Send the frame as is, keeping the exact duration. Encoders without
+    /// `SMALL_LAST_FRAME` have it padded by FFmpeg, as the final frame.
+    PassThrough,
+}
+
 pub struct Encoder(pub Audio);
 
 impl Encoder {
@@ -193,6 +226,67 @@ impl Encoder {
     pub fn frame_size(&self) -> u32 {
         unsafe { (*self.as_ptr()).frame_size as u32 }
     }
+
+    /// Send a frame, applying `policy` if it is shorter than the frame size
+    /// of an encoder without `VARIABLE_FRAME_SIZE`.
+    pub fn send_frame_with(
+        &mut self,
+        frame: &frame::Audio,
+        policy: LastFrame,
+    ) -> Result<(), Error> {
+        let size = self.frame_size() as usize;
+        let capabilities = self
+            .codec()
+            .map_or(Capabilities::empty(), |codec| codec.capabilities());
+
+        if size == 0
+            || frame.samples() >= size
+            || capabilities.contains(Capabilities::VARIABLE_FRAME_SIZE)
+        {
+            return self.send_frame(frame);
+        }
+
+        match policy {
+            LastFrame::Pad => self.send_frame(&pad(frame, size)?),
+            LastFrame::Drop => Ok(()),
+            LastFrame::PassThrough => self.send_frame(frame),
+        }
+    }
+}
+
+fn pad(frame: &frame::Audio, size: usize) -> Result<frame::Audio, Error> {
+    unsafe {
+        let mut padded = frame::Audio::empty();
+        padded.set_channels(frame.channels());
+        padded.alloc(frame.format(), size, frame.channel_layout());
+
+        match av_frame_copy_props(padded.as_mut_ptr(), frame.as_ptr()) {
+            e if e < 0 => return Err(Error::from(e)),
+            _ => (),
+        }
+
+        let channels = c_int::from(frame.channels());
+
+        av_samples_copy(
+            (*padded.as_mut_ptr()).extended_data,
+            (*frame.as_ptr()).extended_data as *const _,
+            0,
+            0,
+            frame.samples() as c_int,
+            channels,
+            frame.format().into(),
+        );
+
+        av_samples_set_silence(
+            (*padded.as_mut_ptr()).extended_data,
+            frame.samples() as c_int,
+            (size - frame.samples()) as c_int,
+            channels,
+            frame.format().into(),
+        );
+
+        Ok(padded)
+    }
 }
 
 impl Deref for Encoder {
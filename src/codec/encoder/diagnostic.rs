@@ -0,0 +1,156 @@
+use std::fmt;
+
+use super::Encoder;
+use codec::{Flags, Id};
+use ffi::*;
+use format::context::Output;
+use {format, media, Rational};
+
+/// A configuration problem found by `Encoder::validate_against()`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Diagnostic {
+    /// The time base is unset or not positive.
+    TimeBase(Rational),
+
+    /// The frame rate is unset, rate control and some muxers depend on it.
+    FrameRate,
+
+    /// The time base cannot represent every frame of the frame rate.
+    TimeBaseTooCoarse {
+        time_base: Rational,
+        frame_rate: Rational,
+    },
+
+    /// The sample aspect ratio is negative or has a zero denominator.
+    AspectRatio(Rational),
+
+    /// Width or height is zero, or not a multiple of the chroma subsampling.
+    Dimensions { width: u32, height: u32 },
+
+    /// The muxer wants global headers but `Flags::GLOBAL_HEADER` is not set.
+    GlobalHeader,
+
+    /// The muxer cannot store the codec.
+    Unsupported(Id),
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Diagnostic::TimeBase(value) => {
+                write!(f, "invalid time base {}, call set_time_base()", value)
+            }
+
+            Diagnostic::FrameRate => f.write_str("frame rate not set, call set_frame_rate()"),
+
+            Diagnostic::TimeBaseTooCoarse {
+                time_base,
+                frame_rate,
+            } => write!(
+                f,
+                "time base {} is coarser than a frame at {} fps, use 1/{} or finer",
+                time_base,
+                frame_rate,
+                frame_rate.numerator()
+            ),
+
+            Diagnostic::AspectRatio(value) => write!(
+                f,
+                "invalid sample aspect ratio {}, use 0/1 when unknown",
+                value
+            ),
+
+            Diagnostic::Dimensions { width, height } => write!(
+                f,
+                "dimensions {}x{} are empty or not aligned to the chroma subsampling",
+                width, height
+            ),
+
+            Diagnostic::GlobalHeader => {
+                f.write_str("muxer needs global headers, set codec::Flags::GLOBAL_HEADER")
+            }
+
+            Diagnostic::Unsupported(id) => write!(f, "muxer cannot store {:?}", id),
+        }
+    }
+}
+
+impl Encoder {
+    /// Cross-check the configuration against the muxer of `output` before
+    /// opening the encoder, returning every problem found.
+    pub fn validate_against(&self, output: &Output) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let format = output.format();
+
+        let time_base = self.time_base();
+
+        if time_base.numerator() <= 0 || time_base.denominator() <= 0 {
+            diagnostics.push(Diagnostic::TimeBase(time_base));
+        }
+
+        if format.flags().contains(format::Flags::GLOBAL_HEADER)
+            && !self.flags().contains(Flags::GLOBAL_HEADER)
+        {
+            diagnostics.push(Diagnostic::GlobalHeader);
+        }
+
+        if self.id() != Id::None {
+            unsafe {
+                let compliance = (*self.as_ptr()).strict_std_compliance;
+
+                if avformat_query_codec(format.as_ptr(), self.id().into(), compliance) == 0 {
+                    diagnostics.push(Diagnostic::Unsupported(self.id()));
+                }
+            }
+        }
+
+        if self.medium() == media::Type::Video {
+            self.validate_video(time_base, &mut diagnostics);
+        }
+
+        diagnostics
+    }
+
+    fn validate_video(&self, time_base: Rational, diagnostics: &mut Vec<Diagnostic>) {
+        match self.frame_rate() {
+            None => diagnostics.push(Diagnostic::FrameRate),
+
+            Some(frame_rate) if time_base.numerator() > 0 && time_base.denominator() > 0 => {
+                if i64::from(time_base.numerator()) * i64::from(frame_rate.numerator())
+                    > i64::from(time_base.denominator()) * i64::from(frame_rate.denominator())
+                {
+                    diagnostics.push(Diagnostic::TimeBaseTooCoarse {
+                        time_base,
+                        frame_rate,
+                    });
+                }
+            }
+
+            Some(_) => (),
+        }
+
+        unsafe {
+            let ptr = self.as_ptr();
+            let aspect_ratio = Rational::from((*ptr).sample_aspect_ratio);
+
+            if aspect_ratio.numerator() < 0
+                || (aspect_ratio.numerator() != 0 && aspect_ratio.denominator() <= 0)
+            {
+                diagnostics.push(Diagnostic::AspectRatio(aspect_ratio));
+            }
+
+            let (width, height) = ((*ptr).width as u32, (*ptr).height as u32);
+            let (w, h) = match format::Pixel::from((*ptr).pix_fmt).descriptor() {
+                Some(descriptor) => (
+                    (1 << descriptor.log2_chroma_w()) - 1,
+                    (1 << descriptor.log2_chroma_h()) - 1,
+                ),
+                None => (0, 0),
+            };
+
+            if width == 0 || height == 0 || width & w != 0 || height & h != 0 {
+                diagnostics.push(Diagnostic::Dimensions { width, height });
+            }
+        }
+    }
+}
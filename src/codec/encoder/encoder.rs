@@ -2,11 +2,11 @@ use std::ops::{Deref, DerefMut};
 use std::ptr;
 
 use ffi::*;
-use libc::c_int;
+use libc::{c_int, ENOMEM};
 
 use super::{audio, subtitle, video};
-use codec::Context;
-use {media, packet, Error, Frame, Rational};
+use codec::{Context, State};
+use {hwcontext, media, packet, Error, Frame, Packet, Rational};
 
 pub struct Encoder(pub Context);
 
@@ -69,20 +69,47 @@ impl Encoder {
     }
 
     /// Sends a NULL packet to the encoder to signal end of stream and enter
-    /// draining mode.
+    /// draining mode. Does nothing when already draining or drained.
     pub fn send_eof(&mut self) -> Result<(), Error> {
-        unsafe { self.send_frame(&Frame::wrap(ptr::null_mut())) }
+        if self.state() != State::Running {
+            return Ok(());
+        }
+
+        unsafe {
+            self.send_frame(&Frame::wrap(ptr::null_mut()))?;
+            self.set_state(State::Draining);
+        }
+
+        Ok(())
     }
 
+    /// Receive the next packet, failing with `Error::Other { errno: EAGAIN }`
+    /// when more frames are needed, and with `Error::Eof` once drained.
     pub fn receive_packet<P: packet::Mut>(&mut self, packet: &mut P) -> Result<(), Error> {
         unsafe {
             match avcodec_receive_packet(self.as_mut_ptr(), packet.as_mut_ptr()) {
-                e if e < 0 => Err(Error::from(e)),
+                e if e < 0 => match Error::from(e) {
+                    Error::Eof => {
+                        self.set_state(State::Finished);
+                        Err(Error::Eof)
+                    }
+
+                    error => Err(error),
+                },
                 _ => Ok(()),
             }
         }
     }
 
+    /// Signal end of stream unless done already, and go over the packets
+    /// still buffered in the encoder.
+    pub fn finish(&mut self) -> Finish {
+        Finish {
+            encoder: self,
+            done: false,
+        }
+    }
+
     pub fn set_bit_rate(&mut self, value: usize) {
         unsafe {
             (*self.as_mut_ptr()).bit_rate = value as i64;
@@ -123,6 +150,10 @@ impl Encoder {
         }
     }
 
+    pub fn time_base(&self) -> Rational {
+        unsafe { Rational::from((*self.as_ptr()).time_base) }
+    }
+
     pub fn set_frame_rate<R: Into<Rational>>(&mut self, value: Option<R>) {
         unsafe {
             if let Some(value) = value {
@@ -133,6 +164,73 @@ impl Encoder {
             }
         }
     }
+
+    pub fn frame_rate(&self) -> Option<Rational> {
+        unsafe {
+            let value = (*self.as_ptr()).framerate;
+
+            if value.num == 0 {
+                None
+            } else {
+                Some(Rational::from(value))
+            }
+        }
+    }
+
+    /// Encode from the surfaces of `frames`, which must match the pixel
+    /// format and dimensions of the encoder. Must be called before opening.
+    pub fn set_hw_frames_ctx(&mut self, frames: &hwcontext::Frames) -> Result<(), Error> {
+        unsafe {
+            let ptr = self.as_mut_ptr();
+
+            av_buffer_unref(&mut (*ptr).hw_frames_ctx);
+            (*ptr).hw_frames_ctx = av_buffer_ref(frames.as_ptr() as *mut _);
+
+            if (*ptr).hw_frames_ctx.is_null() {
+                Err(Error::Other { errno: ENOMEM })
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Packets left in an encoder after the end of stream, see
+/// `Encoder::finish`.
+pub struct Finish<'a> {
+    encoder: &'a mut Encoder,
+    done: bool,
+}
+
+impl<'a> Iterator for Finish<'a> {
+    type Item = Result<Packet, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if let Err(error) = self.encoder.send_eof() {
+            self.done = true;
+            return Some(Err(error));
+        }
+
+        let mut packet = Packet::empty();
+
+        match self.encoder.receive_packet(&mut packet) {
+            Ok(()) => Some(Ok(packet)),
+
+            Err(Error::Eof) => {
+                self.done = true;
+                None
+            }
+
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
+        }
+    }
 }
 
 impl Deref for Encoder {
@@ -0,0 +1,156 @@
+use super::Encoder;
+use packet;
+use picture;
+use {frame, Error};
+
+/// What to drop once the encoder falls behind.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Policy {
+    /// Drop frames no other frame depends on: B-frames when the source
+    /// reports picture types, as decoded frames do, or every other frame
+    /// otherwise.
+    NonReference,
+
+    /// Drop everything up to the encoder catching up, then restart with a
+    /// forced keyframe, so no decoded picture refers to a dropped one.
+    GopTail,
+}
+
+/// Counters kept by a `Governor`.
+#[derive(Eq, PartialEq, Clone, Copy, Default, Debug)]
+pub struct Statistics {
+    /// Frames handed to the governor.
+    pub frames: u64,
+
+    /// Frames dropped instead of being sent to the encoder.
+    pub dropped: u64,
+
+    /// Packets received from the encoder.
+    pub packets: u64,
+
+    /// Highest number of frames the encoder held at once.
+    pub peak: usize,
+}
+
+/// Drops video frames ahead of an encoder whose queue grows past a limit,
+/// keeping a live encode realtime on hardware that cannot sustain it.
+///
+/// The queue depth is the number of frames sent that have not come back as
+/// packets yet, so every packet must be received through the governor.
+/// Encoders buffering by design, for lookahead or B-frame reordering, need a
+/// limit above their own delay.
+pub struct Governor {
+    policy: Policy,
+    limit: usize,
+
+    pending: usize,
+    skipping: bool,
+    toggle: bool,
+
+    statistics: Statistics,
+}
+
+impl Governor {
+    pub fn new(policy: Policy, limit: usize) -> Self {
+        Governor {
+            policy,
+            limit: limit.max(1),
+
+            pending: 0,
+            skipping: false,
+            toggle: false,
+
+            statistics: Statistics::default(),
+        }
+    }
+
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        self.statistics
+    }
+
+    /// Send `frame` to `encoder` unless the queue is over the limit and the
+    /// policy lets it go, returning whether it was sent.
+    ///
+    /// Under `Policy::GopTail` the first frame sent after dropping is turned
+    /// into an I-frame.
+    pub fn send_frame(
+        &mut self,
+        encoder: &mut Encoder,
+        frame: &mut frame::Video,
+    ) -> Result<bool, Error> {
+        self.statistics.frames += 1;
+
+        if self.skip(frame) {
+            self.statistics.dropped += 1;
+            return Ok(false);
+        }
+
+        encoder.send_frame(frame)?;
+
+        self.pending += 1;
+        self.statistics.peak = self.statistics.peak.max(self.pending);
+
+        Ok(true)
+    }
+
+    pub fn receive_packet<P: packet::Mut>(
+        &mut self,
+        encoder: &mut Encoder,
+        packet: &mut P,
+    ) -> Result<(), Error> {
+        encoder.receive_packet(packet)?;
+
+        self.pending = self.pending.saturating_sub(1);
+        self.statistics.packets += 1;
+
+        Ok(())
+    }
+
+    fn skip(&mut self, frame: &mut frame::Video) -> bool {
+        let behind = self.pending >= self.limit;
+
+        match self.policy {
+            Policy::NonReference => {
+                if !behind {
+                    self.toggle = false;
+                    return false;
+                }
+
+                match frame.kind() {
+                    picture::Type::B | picture::Type::BI => true,
+                    picture::Type::None => {
+                        self.toggle = !self.toggle;
+                        self.toggle
+                    }
+                    _ => false,
+                }
+            }
+
+            Policy::GopTail => {
+                if behind {
+                    self.skipping = true;
+                    return true;
+                }
+
+                if self.skipping {
+                    self.skipping = false;
+                    frame.set_kind(picture::Type::I);
+                }
+
+                false
+            }
+        }
+    }
+}
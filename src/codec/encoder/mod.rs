@@ -1,11 +1,11 @@
 pub mod encoder;
-pub use self::encoder::Encoder;
+pub use self::encoder::{Encoder, Finish};
 
 pub mod video;
 pub use self::video::Encoder as Video;
 
 pub mod audio;
-pub use self::audio::Encoder as Audio;
+pub use self::audio::{Encoder as Audio, LastFrame};
 
 pub mod subtitle;
 pub use self::subtitle::Encoder as Subtitle;
@@ -22,6 +22,23 @@ pub use self::comparison::Comparison;
 pub mod decision;
 pub use self::decision::Decision;
 
+pub mod rate_control;
+pub use self::rate_control::Pass;
+
+pub mod private;
+pub use self::private::Private;
+
+pub mod quality;
+pub use self::quality::QpHistogram;
+
+pub mod governor;
+pub use self::governor::Governor;
+
+#[cfg(feature = "format")]
+pub mod diagnostic;
+#[cfg(feature = "format")]
+pub use self::diagnostic::Diagnostic;
+
 use std::ffi::CString;
 
 use codec::Context;
@@ -0,0 +1,416 @@
+//! Typed private options of the most used encoders, for
+//! `video::Video::open_private` and `audio::Audio::open_private`.
+//!
+//! Only the fields that are set end up in the options, leaving the rest to
+//! the encoder defaults.
+
+use Dictionary;
+
+/// Private options of a specific encoder.
+pub trait Private {
+    /// Name of the encoder the options belong to, as in
+    /// `encoder::find_by_name`.
+    fn encoder(&self) -> &'static str;
+
+    fn to_dictionary(&self) -> Dictionary<'static>;
+}
+
+/// Speed presets shared by x264 and x265, from fastest to smallest output.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Preset {
+    UltraFast,
+    SuperFast,
+    VeryFast,
+    Faster,
+    Fast,
+    Medium,
+    Slow,
+    Slower,
+    VerySlow,
+    Placebo,
+}
+
+impl Preset {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Preset::UltraFast => "ultrafast",
+            Preset::SuperFast => "superfast",
+            Preset::VeryFast => "veryfast",
+            Preset::Faster => "faster",
+            Preset::Fast => "fast",
+            Preset::Medium => "medium",
+            Preset::Slow => "slow",
+            Preset::Slower => "slower",
+            Preset::VerySlow => "veryslow",
+            Preset::Placebo => "placebo",
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum X264Tune {
+    Film,
+    Animation,
+    Grain,
+    StillImage,
+    Psnr,
+    Ssim,
+    FastDecode,
+    ZeroLatency,
+}
+
+impl X264Tune {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            X264Tune::Film => "film",
+            X264Tune::Animation => "animation",
+            X264Tune::Grain => "grain",
+            X264Tune::StillImage => "stillimage",
+            X264Tune::Psnr => "psnr",
+            X264Tune::Ssim => "ssim",
+            X264Tune::FastDecode => "fastdecode",
+            X264Tune::ZeroLatency => "zerolatency",
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum X264Profile {
+    Baseline,
+    Main,
+    High,
+    High10,
+    High422,
+    High444,
+}
+
+impl X264Profile {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            X264Profile::Baseline => "baseline",
+            X264Profile::Main => "main",
+            X264Profile::High => "high",
+            X264Profile::High10 => "high10",
+            X264Profile::High422 => "high422",
+            X264Profile::High444 => "high444",
+        }
+    }
+}
+
+/// Options of `libx264`.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct X264Options {
+    pub preset: Option<Preset>,
+    pub tune: Option<X264Tune>,
+    pub profile: Option<X264Profile>,
+
+    /// Constant rate factor, lower meaning better quality, 23 by default.
+    pub crf: Option<f32>,
+
+    /// Raw `key=value:key=value` parameters passed on to x264.
+    pub params: Option<String>,
+}
+
+impl Private for X264Options {
+    fn encoder(&self) -> &'static str {
+        "libx264"
+    }
+
+    fn to_dictionary(&self) -> Dictionary<'static> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(preset) = self.preset {
+            dictionary.set("preset", preset.name());
+        }
+
+        if let Some(tune) = self.tune {
+            dictionary.set("tune", tune.name());
+        }
+
+        if let Some(profile) = self.profile {
+            dictionary.set("profile", profile.name());
+        }
+
+        if let Some(crf) = self.crf {
+            dictionary.set("crf", &crf.to_string());
+        }
+
+        if let Some(ref params) = self.params {
+            dictionary.set("x264-params", params);
+        }
+
+        dictionary
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum X265Tune {
+    Psnr,
+    Ssim,
+    Grain,
+    ZeroLatency,
+    FastDecode,
+    Animation,
+}
+
+impl X265Tune {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            X265Tune::Psnr => "psnr",
+            X265Tune::Ssim => "ssim",
+            X265Tune::Grain => "grain",
+            X265Tune::ZeroLatency => "zerolatency",
+            X265Tune::FastDecode => "fastdecode",
+            X265Tune::Animation => "animation",
+        }
+    }
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum X265Profile {
+    Main,
+    Main10,
+    MainStillPicture,
+    Main422_10,
+    Main444_8,
+    Main444_10,
+}
+
+impl X265Profile {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            X265Profile::Main => "main",
+            X265Profile::Main10 => "main10",
+            X265Profile::MainStillPicture => "mainstillpicture",
+            X265Profile::Main422_10 => "main422-10",
+            X265Profile::Main444_8 => "main444-8",
+            X265Profile::Main444_10 => "main444-10",
+        }
+    }
+}
+
+/// Options of `libx265`.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct X265Options {
+    pub preset: Option<Preset>,
+    pub tune: Option<X265Tune>,
+    pub profile: Option<X265Profile>,
+
+    /// Constant rate factor, lower meaning better quality, 28 by default.
+    pub crf: Option<f32>,
+
+    /// Raw `key=value:key=value` parameters passed on to x265.
+    pub params: Option<String>,
+}
+
+impl Private for X265Options {
+    fn encoder(&self) -> &'static str {
+        "libx265"
+    }
+
+    fn to_dictionary(&self) -> Dictionary<'static> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(preset) = self.preset {
+            dictionary.set("preset", preset.name());
+        }
+
+        if let Some(tune) = self.tune {
+            dictionary.set("tune", tune.name());
+        }
+
+        if let Some(profile) = self.profile {
+            dictionary.set("profile", profile.name());
+        }
+
+        if let Some(crf) = self.crf {
+            dictionary.set("crf", &crf.to_string());
+        }
+
+        if let Some(ref params) = self.params {
+            dictionary.set("x265-params", params);
+        }
+
+        dictionary
+    }
+}
+
+/// How much time libvpx may spend per frame.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Deadline {
+    Best,
+    Good,
+    Realtime,
+}
+
+impl Deadline {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Deadline::Best => "best",
+            Deadline::Good => "good",
+            Deadline::Realtime => "realtime",
+        }
+    }
+}
+
+/// Options of `libvpx-vp9`.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct Vp9Options {
+    pub deadline: Option<Deadline>,
+
+    /// Speed against quality within the deadline, from -8 to 8, higher
+    /// being faster.
+    pub cpu_used: Option<i32>,
+
+    /// Constant quality from 0 to 63, lower meaning better quality. Needs
+    /// the bit rate to be 0 for constant quality, or sets a cap otherwise.
+    pub crf: Option<u32>,
+
+    /// Log2 of the number of tile columns, to encode and decode them in
+    /// parallel.
+    pub tile_columns: Option<u32>,
+
+    /// Multithreading within tile columns.
+    pub row_mt: Option<bool>,
+
+    /// Frames to look ahead, 0 for the lowest latency.
+    pub lag_in_frames: Option<u32>,
+}
+
+impl Private for Vp9Options {
+    fn encoder(&self) -> &'static str {
+        "libvpx-vp9"
+    }
+
+    fn to_dictionary(&self) -> Dictionary<'static> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(deadline) = self.deadline {
+            dictionary.set("deadline", deadline.name());
+        }
+
+        if let Some(cpu_used) = self.cpu_used {
+            dictionary.set("cpu-used", &cpu_used.to_string());
+        }
+
+        if let Some(crf) = self.crf {
+            dictionary.set("crf", &crf.to_string());
+        }
+
+        if let Some(tile_columns) = self.tile_columns {
+            dictionary.set("tile-columns", &tile_columns.to_string());
+        }
+
+        if let Some(row_mt) = self.row_mt {
+            dictionary.set("row-mt", flag(row_mt));
+        }
+
+        if let Some(lag_in_frames) = self.lag_in_frames {
+            dictionary.set("lag-in-frames", &lag_in_frames.to_string());
+        }
+
+        dictionary
+    }
+}
+
+/// Coefficient search of the native AAC encoder.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum AacCoder {
+    /// Best quality, slowest.
+    Anmr,
+    /// The default.
+    TwoLoop,
+    Fast,
+}
+
+impl AacCoder {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            AacCoder::Anmr => "anmr",
+            AacCoder::TwoLoop => "twoloop",
+            AacCoder::Fast => "fast",
+        }
+    }
+}
+
+/// Options of the native `aac` encoder.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct AacOptions {
+    pub coder: Option<AacCoder>,
+
+    /// Perceptual noise substitution.
+    pub pns: Option<bool>,
+
+    /// Temporal noise shaping.
+    pub tns: Option<bool>,
+
+    /// Intensity stereo.
+    pub intensity_stereo: Option<bool>,
+
+    /// Mid/side stereo, `None` deciding per band.
+    pub mid_side: Option<bool>,
+}
+
+impl Private for AacOptions {
+    fn encoder(&self) -> &'static str {
+        "aac"
+    }
+
+    fn to_dictionary(&self) -> Dictionary<'static> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(coder) = self.coder {
+            dictionary.set("aac_coder", coder.name());
+        }
+
+        if let Some(pns) = self.pns {
+            dictionary.set("aac_pns", flag(pns));
+        }
+
+        if let Some(tns) = self.tns {
+            dictionary.set("aac_tns", flag(tns));
+        }
+
+        if let Some(intensity_stereo) = self.intensity_stereo {
+            dictionary.set("aac_is", flag(intensity_stereo));
+        }
+
+        if let Some(mid_side) = self.mid_side {
+            dictionary.set("aac_ms", flag(mid_side));
+        }
+
+        dictionary
+    }
+}
+
+fn flag(value: bool) -> &'static str {
+    if value {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_x264() {
+        let options = X264Options {
+            preset: Some(Preset::VeryFast),
+            tune: Some(X264Tune::ZeroLatency),
+            crf: Some(20.0),
+            ..Default::default()
+        };
+
+        let dictionary = options.to_dictionary();
+
+        assert_eq!(options.encoder(), "libx264");
+        assert_eq!(dictionary.get("preset"), Some("veryfast"));
+        assert_eq!(dictionary.get("tune"), Some("zerolatency"));
+        assert_eq!(dictionary.get("crf"), Some("20"));
+        assert_eq!(dictionary.get("profile"), None);
+    }
+}
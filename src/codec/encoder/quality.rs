@@ -0,0 +1,187 @@
+use packet::QualityStats;
+use picture;
+use Packet;
+
+/// Distribution of the quantizers encoders report for their packets, to
+/// compare encodes against each other, for instance to catch quality
+/// regressions.
+///
+/// Quantizers are rounded to the nearest integer.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct QpHistogram {
+    bins: Vec<u64>,
+    kinds: Vec<(picture::Type, u64)>,
+
+    count: u64,
+    sum: f64,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl QpHistogram {
+    pub fn new() -> Self {
+        QpHistogram::default()
+    }
+
+    /// Record the statistics of `packet`, returning whether it had any.
+    pub fn record(&mut self, packet: &Packet) -> bool {
+        match packet.quality_stats() {
+            Some(stats) => {
+                self.record_stats(&stats);
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    pub fn record_stats(&mut self, stats: &QualityStats) {
+        let qp = stats.qp();
+        let bin = qp.round().max(0.0) as usize;
+
+        if bin >= self.bins.len() {
+            self.bins.resize(bin + 1, 0);
+        }
+
+        self.bins[bin] += 1;
+
+        match self
+            .kinds
+            .iter_mut()
+            .find(|&&mut (kind, _)| kind == stats.kind)
+        {
+            Some(&mut (_, ref mut count)) => *count += 1,
+            None => self.kinds.push((stats.kind, 1)),
+        }
+
+        self.count += 1;
+        self.sum += qp;
+        self.min = Some(self.min.map_or(qp, |min| min.min(qp)));
+        self.max = Some(self.max.map_or(qp, |max| max.max(qp)));
+    }
+
+    /// Add the packets recorded by `other`.
+    pub fn merge(&mut self, other: &QpHistogram) {
+        if other.bins.len() > self.bins.len() {
+            self.bins.resize(other.bins.len(), 0);
+        }
+
+        for (bin, &count) in self.bins.iter_mut().zip(&other.bins) {
+            *bin += count;
+        }
+
+        for &(kind, count) in &other.kinds {
+            match self.kinds.iter_mut().find(|&&mut (k, _)| k == kind) {
+                Some(&mut (_, ref mut total)) => *total += count,
+                None => self.kinds.push((kind, count)),
+            }
+        }
+
+        self.count += other.count;
+        self.sum += other.sum;
+        self.min = combine(self.min, other.min, f64::min);
+        self.max = combine(self.max, other.max, f64::max);
+    }
+
+    /// Packets recorded per quantizer, indexed by quantizer.
+    pub fn bins(&self) -> &[u64] {
+        &self.bins
+    }
+
+    /// Packets recorded per picture type.
+    pub fn kinds(&self) -> &[(picture::Type, u64)] {
+        &self.kinds
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(self.sum / self.count as f64)
+        }
+    }
+
+    /// The quantizer at or below which `fraction` of the packets fall.
+    pub fn percentile(&self, fraction: f64) -> Option<u32> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let target = (fraction.max(0.0).min(1.0) * self.count as f64)
+            .ceil()
+            .max(1.0) as u64;
+        let mut seen = 0;
+
+        for (qp, &count) in self.bins.iter().enumerate() {
+            seen += count;
+
+            if seen >= target {
+                return Some(qp as u32);
+            }
+        }
+
+        None
+    }
+}
+
+// Combine optional extremes with `f`.
+fn combine(a: Option<f64>, b: Option<f64>, f: fn(f64, f64) -> f64) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(f(a, b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(qp: u32, kind: picture::Type) -> QualityStats {
+        QualityStats {
+            quality: qp * 118,
+            kind,
+            errors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_histogram() {
+        let mut histogram = QpHistogram::new();
+
+        histogram.record_stats(&stats(20, picture::Type::I));
+        histogram.record_stats(&stats(25, picture::Type::P));
+        histogram.record_stats(&stats(30, picture::Type::P));
+
+        let mut other = QpHistogram::new();
+        other.record_stats(&stats(35, picture::Type::B));
+        histogram.merge(&other);
+
+        assert_eq!(histogram.count(), 4);
+        assert_eq!(histogram.min(), Some(20.0));
+        assert_eq!(histogram.max(), Some(35.0));
+        assert_eq!(histogram.mean(), Some(27.5));
+        assert_eq!(histogram.percentile(0.5), Some(25));
+        assert_eq!(histogram.percentile(1.0), Some(35));
+        assert_eq!(
+            histogram.kinds(),
+            &[
+                (picture::Type::I, 1),
+                (picture::Type::P, 2),
+                (picture::Type::B, 1)
+            ]
+        );
+    }
+}
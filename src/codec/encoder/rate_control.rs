@@ -0,0 +1,150 @@
+use std::ffi::{CStr, CString};
+use std::str::from_utf8_unchecked;
+
+use super::Encoder;
+use codec::Flags;
+use ffi::*;
+use libc::{c_int, c_void};
+use Error;
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Pass {
+    /// Analyze the input and produce statistics in `stats_out()`.
+    First,
+
+    /// Encode using the statistics given to `set_stats_in()`.
+    Second,
+}
+
+impl Encoder {
+    pub fn set_rc_max_rate(&mut self, value: i64) {
+        unsafe {
+            (*self.as_mut_ptr()).rc_max_rate = value;
+        }
+    }
+
+    pub fn set_rc_min_rate(&mut self, value: i64) {
+        unsafe {
+            (*self.as_mut_ptr()).rc_min_rate = value;
+        }
+    }
+
+    /// Size of the VBV buffer in bits.
+    pub fn set_rc_buffer_size(&mut self, value: i32) {
+        unsafe {
+            (*self.as_mut_ptr()).rc_buffer_size = value as c_int;
+        }
+    }
+
+    /// Number of bits in the VBV buffer before decoding starts.
+    pub fn set_rc_initial_buffer_occupancy(&mut self, value: i32) {
+        unsafe {
+            (*self.as_mut_ptr()).rc_initial_buffer_occupancy = value as c_int;
+        }
+    }
+
+    /// Encode at constant perceptual quality.
+    ///
+    /// Sets the `crf` private option where the encoder has one (libx264,
+    /// libx265, libvpx, libaom), then `cq` (NVENC), and otherwise falls back to
+    /// a fixed quantizer scale. Must be called before opening, on a context
+    /// created for a specific codec, such as the one of a stream added with
+    /// that codec. libvpx additionally needs a zero bit rate for constant
+    /// quality rather than constrained quality.
+    pub fn set_crf(&mut self, value: f32) -> Result<(), Error> {
+        self.set_private_quality(&["crf", "cq"], value)
+    }
+
+    /// Encode with a constant quantizer, through the `qp` private option where
+    /// the encoder has one and a fixed quantizer scale otherwise.
+    pub fn set_qp(&mut self, value: f32) -> Result<(), Error> {
+        self.set_private_quality(&["qp"], value)
+    }
+
+    fn set_private_quality(&mut self, names: &[&str], value: f32) -> Result<(), Error> {
+        unsafe {
+            let priv_data = (*self.as_ptr()).priv_data;
+
+            if priv_data.is_null() {
+                return Err(Error::OptionNotFound);
+            }
+
+            let value_str = CString::new(value.to_string()).unwrap();
+
+            for name in names {
+                let name = CString::new(*name).unwrap();
+
+                match av_opt_set(priv_data, name.as_ptr(), value_str.as_ptr(), 0) {
+                    0 => return Ok(()),
+                    AVERROR_OPTION_NOT_FOUND => (),
+                    e => return Err(Error::from(e)),
+                }
+            }
+
+            let flags = self.flags() | Flags::QSCALE;
+            self.set_flags(flags);
+            (*self.as_mut_ptr()).global_quality = (value * FF_QP2LAMBDA as f32) as c_int;
+
+            Ok(())
+        }
+    }
+
+    /// Select the pass of a two-pass encode, or `None` for a single pass.
+    pub fn set_pass(&mut self, value: Option<Pass>) {
+        let mut flags = self.flags() - (Flags::PASS1 | Flags::PASS2);
+
+        match value {
+            Some(Pass::First) => flags |= Flags::PASS1,
+            Some(Pass::Second) => flags |= Flags::PASS2,
+            None => (),
+        }
+
+        self.set_flags(flags);
+    }
+
+    /// Statistics of the first pass, to give to the second one.
+    ///
+    /// Encoders update them as packets are produced; read them once the
+    /// encoder is drained. Encoders that keep their own log file, such as
+    /// libx264, leave them empty and use `set_stats_file()` instead.
+    pub fn stats_out(&self) -> Option<&str> {
+        unsafe {
+            let ptr = (*self.as_ptr()).stats_out;
+
+            if ptr.is_null() {
+                None
+            } else {
+                Some(from_utf8_unchecked(CStr::from_ptr(ptr).to_bytes()))
+            }
+        }
+    }
+
+    pub fn set_stats_in(&mut self, value: &str) {
+        unsafe {
+            let value = CString::new(value).unwrap();
+
+            av_freep(&mut (*self.as_mut_ptr()).stats_in as *mut _ as *mut c_void);
+            (*self.as_mut_ptr()).stats_in = av_strdup(value.as_ptr());
+        }
+    }
+
+    /// Set the log file of encoders managing their own, through the `stats`
+    /// private option of libx264 and libxavs.
+    pub fn set_stats_file(&mut self, path: &str) -> Result<(), Error> {
+        unsafe {
+            let priv_data = (*self.as_ptr()).priv_data;
+
+            if priv_data.is_null() {
+                return Err(Error::OptionNotFound);
+            }
+
+            let name = CString::new("stats").unwrap();
+            let path = CString::new(path).unwrap();
+
+            match av_opt_set(priv_data, name.as_ptr(), path.as_ptr(), 0) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+}
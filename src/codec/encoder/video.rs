@@ -1,13 +1,18 @@
+#[cfg(feature = "nonzero")]
+use std::num::NonZeroU32;
 use std::ops::{Deref, DerefMut};
 use std::ptr;
 
 use ffi::*;
 use libc::{c_float, c_int};
 
+use super::private::Private;
+pub use super::private::{Deadline, Preset, Vp9Options, X264Options, X264Profile, X264Tune};
+pub use super::private::{X265Options, X265Profile, X265Tune};
 use super::Encoder as Super;
 use super::{Comparison, Decision, MotionEstimation, Prediction};
 use codec::{traits, Context};
-use {color, format, frame, packet, Dictionary, Error, Rational};
+use {color, format, frame, packet, Dictionary, Error, Rational, Rescale};
 
 pub struct Video(pub Super);
 
@@ -74,6 +79,12 @@ impl Video {
         }
     }
 
+    /// Open the encoder the typed `options` belong to, with them, failing
+    /// with `Error::EncoderNotFound` when FFmpeg was built without it.
+    pub fn open_private<P: Private>(self, options: &P) -> Result<Encoder, Error> {
+        self.open_as_with(options.encoder(), options.to_dictionary())
+    }
+
     #[inline]
     pub fn set_width(&mut self, value: u32) {
         unsafe {
@@ -81,9 +92,17 @@ impl Video {
         }
     }
 
+    #[cfg(not(feature = "nonzero"))]
     #[inline]
     pub fn width(&self) -> u32 {
-        unsafe { (*self.as_ptr()).width as u32 }
+        self.raw_width()
+    }
+
+    /// `None` until set.
+    #[cfg(feature = "nonzero")]
+    #[inline]
+    pub fn width(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_width())
     }
 
     #[inline]
@@ -93,9 +112,17 @@ impl Video {
         }
     }
 
+    #[cfg(not(feature = "nonzero"))]
     #[inline]
     pub fn height(&self) -> u32 {
-        unsafe { (*self.as_ptr()).height as u32 }
+        self.raw_height()
+    }
+
+    /// `None` until set.
+    #[cfg(feature = "nonzero")]
+    #[inline]
+    pub fn height(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_height())
     }
 
     #[inline]
@@ -209,6 +236,11 @@ impl Video {
         }
     }
 
+    #[inline]
+    pub fn aspect_ratio(&self) -> Rational {
+        unsafe { Rational::from((*self.as_ptr()).sample_aspect_ratio) }
+    }
+
     #[inline]
     pub fn set_me_comparison(&mut self, value: Comparison) {
         unsafe {
@@ -428,8 +460,8 @@ impl Encoder {
     ) -> Result<bool, Error> {
         unsafe {
             if self.format() != frame.format()
-                || self.width() != frame.width()
-                || self.height() != frame.height()
+                || self.raw_width() != frame.raw_width()
+                || self.raw_height() != frame.raw_height()
             {
                 return Err(Error::InvalidData);
             }
@@ -474,6 +506,37 @@ impl Encoder {
     pub fn frame_size(&self) -> u32 {
         unsafe { (*self.as_ptr()).frame_size as u32 }
     }
+
+    /// Depth of the frame reorder buffer, that is how many frames the DTS of
+    /// a packet can lag behind its PTS.
+    #[inline]
+    pub fn reorder_depth(&self) -> usize {
+        unsafe { (*self.as_ptr()).has_b_frames as usize }
+    }
+
+    /// Number of frames the encoder buffers before producing packets.
+    #[inline]
+    pub fn delay(&self) -> usize {
+        unsafe { (*self.as_ptr()).delay as usize }
+    }
+
+    /// The reorder depth as a duration in `time_base`, assuming the frame
+    /// rate of the encoder or, when unset, one tick of its time base per
+    /// frame. It is how far the DTS of the first packets can fall below the
+    /// PTS of the first frame.
+    ///
+    /// The depth is only known once the encoder is opened. To offset the
+    /// output with `Output::set_output_ts_offset`, which shifts every stream,
+    /// pass `rescale::TIME_BASE` as `time_base`; the DTS then stay non-negative
+    /// when the first frame has a PTS of zero.
+    pub fn muxing_delay<R: Into<Rational>>(&self, time_base: R) -> i64 {
+        let frame = match self.frame_rate() {
+            Some(rate) if rate.numerator() > 0 => rate.invert(),
+            _ => self.time_base(),
+        };
+
+        (self.reorder_depth() as i64).rescale(frame, time_base)
+    }
 }
 
 impl Deref for Encoder {
@@ -1,5 +1,5 @@
 use ffi::*;
-use libc::c_uint;
+use libc::{c_int, c_uint};
 
 bitflags! {
     pub struct Flags: c_uint {
@@ -23,3 +23,18 @@ bitflags! {
         const CLOSED_GOP      = AV_CODEC_FLAG_CLOSED_GOP;
     }
 }
+
+bitflags! {
+    pub struct Flags2: c_int {
+        const FAST                = AV_CODEC_FLAG2_FAST;
+        const NO_OUTPUT           = AV_CODEC_FLAG2_NO_OUTPUT;
+        const LOCAL_HEADER        = AV_CODEC_FLAG2_LOCAL_HEADER;
+        const DROP_FRAME_TIMECODE = AV_CODEC_FLAG2_DROP_FRAME_TIMECODE;
+        const CHUNKS              = AV_CODEC_FLAG2_CHUNKS;
+        const IGNORE_CROP         = AV_CODEC_FLAG2_IGNORE_CROP;
+        const SHOW_ALL            = AV_CODEC_FLAG2_SHOW_ALL;
+        const EXPORT_MVS          = AV_CODEC_FLAG2_EXPORT_MVS;
+        const SKIP_MANUAL         = AV_CODEC_FLAG2_SKIP_MANUAL;
+        const RO_FLUSH_NOOP       = AV_CODEC_FLAG2_RO_FLUSH_NOOP;
+    }
+}
@@ -1,6 +1,7 @@
 use std::ffi::CStr;
 use std::str::from_utf8_unchecked;
 
+use super::descriptor::{self, Descriptor};
 use ffi::AVCodecID::*;
 use ffi::*;
 use util::media;
@@ -608,6 +609,10 @@ impl Id {
     pub fn name(&self) -> &'static str {
         unsafe { from_utf8_unchecked(CStr::from_ptr(avcodec_get_name((*self).into())).to_bytes()) }
     }
+
+    pub fn descriptor(&self) -> Option<Descriptor> {
+        descriptor::find(*self)
+    }
 }
 
 impl From<AVCodecID> for Id {
@@ -1,11 +1,14 @@
 pub mod flag;
-pub use self::flag::Flags;
+pub use self::flag::{Flags, Flags2};
 
 pub mod id;
 pub use self::id::Id;
 
 pub mod packet;
 
+pub mod parser;
+pub use self::parser::Parser;
+
 pub mod subtitle;
 
 pub mod picture;
@@ -13,13 +16,16 @@ pub mod picture;
 pub mod discard;
 
 pub mod context;
-pub use self::context::Context;
+pub use self::context::{Context, State};
 
 pub mod capabilities;
 pub use self::capabilities::Capabilities;
 
 pub mod codec;
 
+pub mod descriptor;
+pub use self::descriptor::Descriptor;
+
 pub mod parameters;
 pub use self::parameters::Parameters;
 
@@ -43,14 +49,21 @@ pub use self::profile::Profile;
 
 pub mod threading;
 
+pub mod report;
+
+pub mod bsf;
 pub mod decoder;
 pub mod encoder;
 pub mod traits;
 
 use std::ffi::CStr;
+use std::ptr;
+use std::slice;
 use std::str::from_utf8_unchecked;
 
 use ffi::*;
+use libc::{c_int, ENOMEM};
+use Error;
 
 pub fn version() -> u32 {
     unsafe { avcodec_version() }
@@ -63,3 +76,41 @@ pub fn configuration() -> &'static str {
 pub fn license() -> &'static str {
     unsafe { from_utf8_unchecked(CStr::from_ptr(avcodec_license()).to_bytes()) }
 }
+
+// Extradata is shared between contexts and parameters, which both own it
+// through `av_free` and expect it to be zero padded.
+unsafe fn extradata<'a>(data: *const u8, size: c_int) -> Option<&'a [u8]> {
+    if data.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts(data, size as usize))
+    }
+}
+
+unsafe fn set_extradata(
+    data: &mut *mut u8,
+    size: &mut c_int,
+    value: Option<&[u8]>,
+) -> Result<(), Error> {
+    let buffer = match value {
+        Some(value) => {
+            let buffer = av_mallocz(value.len() + AV_INPUT_BUFFER_PADDING_SIZE as usize) as *mut u8;
+
+            if buffer.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr::copy_nonoverlapping(value.as_ptr(), buffer, value.len());
+            buffer
+        }
+
+        None => ptr::null_mut(),
+    };
+
+    av_freep(data as *mut *mut u8 as *mut _);
+
+    *data = buffer;
+    *size = value.map_or(0, |value| value.len() as c_int);
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use std::{mem, ptr, slice};
+
+use super::{side_data, Packet, Ref};
+use ffi::*;
+use Error;
+
+/// A 3x3 transformation matrix applied to decoded video before display,
+/// carried as `AV_PKT_DATA_DISPLAYMATRIX` side data.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct DisplayMatrix(pub [i32; 9]);
+
+impl DisplayMatrix {
+    /// A pure rotation by `degrees`, counterclockwise.
+    pub fn rotation(degrees: f64) -> Self {
+        let mut matrix = [0; 9];
+
+        unsafe {
+            av_display_rotation_set(matrix.as_mut_ptr(), degrees);
+        }
+
+        DisplayMatrix(matrix)
+    }
+
+    /// Mirror the transformation along either axis.
+    pub fn flip(mut self, horizontal: bool, vertical: bool) -> Self {
+        unsafe {
+            av_display_matrix_flip(self.0.as_mut_ptr(), horizontal as _, vertical as _);
+        }
+
+        self
+    }
+
+    /// Counterclockwise rotation in degrees in the range `[-180, 180]`, or
+    /// `None` when the matrix is degenerate.
+    pub fn angle(&self) -> Option<f64> {
+        let value = unsafe { av_display_rotation_get(self.0.as_ptr()) };
+
+        if value.is_nan() {
+            None
+        } else {
+            Some(value)
+        }
+    }
+}
+
+impl Packet {
+    pub fn display_matrix(&self) -> Option<DisplayMatrix> {
+        unsafe {
+            let mut size = 0;
+            let ptr = av_packet_get_side_data(
+                self.as_ptr(),
+                side_data::Type::DisplayMatrix.into(),
+                &mut size,
+            ) as *const [i32; 9];
+
+            if ptr.is_null() || (size as usize) < mem::size_of::<[i32; 9]>() {
+                return None;
+            }
+
+            Some(DisplayMatrix(ptr::read_unaligned(ptr)))
+        }
+    }
+
+    pub fn set_display_matrix(&mut self, value: &DisplayMatrix) -> Result<(), Error> {
+        let bytes = unsafe {
+            slice::from_raw_parts(value.0.as_ptr() as *const u8, mem::size_of::<[i32; 9]>())
+        };
+
+        self.add_side_data(side_data::Type::DisplayMatrix, bytes)
+    }
+}
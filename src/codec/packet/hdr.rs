@@ -0,0 +1,25 @@
+use super::{side_data, Packet};
+use util::hdr::{ContentLightLevel, MasteringDisplay};
+use Error;
+
+impl Packet {
+    pub fn mastering_display(&self) -> Option<MasteringDisplay> {
+        self.side_data()
+            .find(|data| data.kind() == side_data::Type::MasteringDisplayMetadata)
+            .and_then(|data| MasteringDisplay::from_bytes(data.data()))
+    }
+
+    pub fn set_mastering_display(&mut self, value: &MasteringDisplay) -> Result<(), Error> {
+        self.add_side_data(side_data::Type::MasteringDisplayMetadata, &value.to_bytes())
+    }
+
+    pub fn content_light_level(&self) -> Option<ContentLightLevel> {
+        self.side_data()
+            .find(|data| data.kind() == side_data::Type::ContentLightLevel)
+            .and_then(|data| ContentLightLevel::from_bytes(data.data()))
+    }
+
+    pub fn set_content_light_level(&mut self, value: &ContentLightLevel) -> Result<(), Error> {
+        self.add_side_data(side_data::Type::ContentLightLevel, &value.to_bytes())
+    }
+}
@@ -12,3 +12,22 @@ pub use self::side_data::SideData;
 
 pub mod flag;
 pub use self::flag::Flags;
+
+pub mod pool;
+pub use self::pool::{PacketPool, PooledPacket};
+
+pub mod display_matrix;
+pub use self::display_matrix::DisplayMatrix;
+
+pub mod skip_samples;
+pub use self::skip_samples::SkipSamples;
+
+pub mod quality_stats;
+pub use self::quality_stats::QualityStats;
+
+pub mod hdr;
+
+#[cfg(feature = "ffmpeg_4_3")]
+pub mod prft;
+#[cfg(feature = "ffmpeg_4_3")]
+pub use self::prft::ProducerReferenceTime;
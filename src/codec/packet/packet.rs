@@ -1,11 +1,14 @@
 use std::marker::PhantomData;
 use std::mem;
+use std::ptr;
 use std::slice;
 
-use super::{Borrow, Flags, Mut, Ref, SideData};
+use super::{side_data, Borrow, Flags, Mut, Ref, SideData};
 use ffi::*;
-use libc::c_int;
-use {format, Error, Rational};
+use libc::{c_int, ENOMEM};
+use mathematics::Retime;
+use time::Timestamp;
+use {format, Error, Rational, Rounding};
 
 pub struct Packet(AVPacket);
 
@@ -87,6 +90,17 @@ impl Packet {
         }
     }
 
+    /// Retime the timestamps and duration, see `Retime`.
+    pub fn retime(&mut self, retime: &Retime) {
+        let pts = self.pts().map(|pts| retime.map(pts));
+        let dts = self.dts().map(|dts| retime.map(dts));
+        let duration = retime.duration(self.duration());
+
+        self.set_pts(pts);
+        self.set_dts(dts);
+        self.set_duration(duration);
+    }
+
     #[inline]
     pub fn flags(&self) -> Flags {
         Flags::from_bits_truncate(self.0.flags)
@@ -143,6 +157,29 @@ impl Packet {
         self.0.dts = value.unwrap_or(AV_NOPTS_VALUE);
     }
 
+    /// The presentation timestamp, given the time base of the stream.
+    #[inline]
+    pub fn pts_in(&self, time_base: Rational) -> Timestamp {
+        Timestamp::new(self.0.pts, time_base)
+    }
+
+    /// Set the presentation timestamp from `value`, rescaled to the time
+    /// base of the stream.
+    #[inline]
+    pub fn set_pts_in(&mut self, value: Timestamp, time_base: Rational) {
+        self.set_pts(value.rescale_to(time_base, Rounding::NearInfinity).value());
+    }
+
+    #[inline]
+    pub fn dts_in(&self, time_base: Rational) -> Timestamp {
+        Timestamp::new(self.0.dts, time_base)
+    }
+
+    #[inline]
+    pub fn set_dts_in(&mut self, value: Timestamp, time_base: Rational) {
+        self.set_dts(value.rescale_to(time_base, Rounding::NearInfinity).value());
+    }
+
     #[inline]
     pub fn size(&self) -> usize {
         self.0.size as usize
@@ -178,6 +215,22 @@ impl Packet {
         SideDataIter::new(&self.0)
     }
 
+    /// Attach a copy of `data` as side data of the given kind, replacing any
+    /// existing entry of that kind.
+    pub fn add_side_data(&mut self, kind: side_data::Type, data: &[u8]) -> Result<(), Error> {
+        unsafe {
+            let ptr = av_packet_new_side_data(self.as_mut_ptr(), kind.into(), data.len() as _);
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+        }
+
+        Ok(())
+    }
+
     #[inline]
     pub fn data(&self) -> Option<&[u8]> {
         unsafe {
@@ -210,6 +263,41 @@ impl Packet {
         }
     }
 
+    /// Make sure the data is reference counted, copying it if it is not, so
+    /// the packet no longer depends on the context that produced it.
+    #[cfg(feature = "ffmpeg_4_1")]
+    #[inline]
+    pub fn make_refcounted(&mut self) -> Result<(), Error> {
+        unsafe {
+            match av_packet_make_refcounted(self.as_mut_ptr()) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Make sure the data is reference counted, copying it if it is not, so
+    /// the packet no longer depends on the context that produced it.
+    #[cfg(not(feature = "ffmpeg_4_1"))]
+    pub fn make_refcounted(&mut self) -> Result<(), Error> {
+        unsafe {
+            if !self.0.buf.is_null() {
+                return Ok(());
+            }
+
+            // Referencing a packet without a buffer copies its data.
+            let mut copy = Packet::empty();
+
+            match av_packet_ref(copy.as_mut_ptr(), self.as_ptr()) {
+                0 => {
+                    mem::swap(self, &mut copy);
+                    Ok(())
+                }
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
     #[inline]
     pub fn write(&self, format: &mut format::context::Output) -> Result<bool, Error> {
         unsafe {
@@ -0,0 +1,93 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex};
+
+use super::{Mut, Packet};
+use ffi::*;
+
+/// A bounded free list of packets, handing out `PooledPacket`s that are
+/// unreferenced and returned to it when dropped instead of being freed.
+#[derive(Clone)]
+pub struct PacketPool {
+    free: Arc<Mutex<Vec<Packet>>>,
+    capacity: usize,
+}
+
+impl PacketPool {
+    /// Create a pool keeping at most `capacity` idle packets around.
+    pub fn new(capacity: usize) -> Self {
+        PacketPool {
+            free: Arc::new(Mutex::new(Vec::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of idle packets ready for reuse.
+    pub fn idle(&self) -> usize {
+        self.free.lock().unwrap().len()
+    }
+
+    /// Take an idle packet, or allocate a new one when none is left.
+    pub fn get(&self) -> PooledPacket {
+        let packet = self
+            .free
+            .lock()
+            .unwrap()
+            .pop()
+            .unwrap_or_else(Packet::empty);
+
+        PooledPacket {
+            packet: Some(packet),
+            pool: self.clone(),
+        }
+    }
+
+    fn recycle(&self, mut packet: Packet) {
+        unsafe {
+            av_packet_unref(packet.as_mut_ptr());
+        }
+
+        let mut free = self.free.lock().unwrap();
+
+        if free.len() < self.capacity {
+            free.push(packet);
+        }
+    }
+}
+
+pub struct PooledPacket {
+    packet: Option<Packet>,
+    pool: PacketPool,
+}
+
+impl PooledPacket {
+    /// Detach the packet from the pool.
+    pub fn into_inner(mut self) -> Packet {
+        self.packet.take().unwrap()
+    }
+}
+
+impl Deref for PooledPacket {
+    type Target = Packet;
+
+    fn deref(&self) -> &Packet {
+        self.packet.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledPacket {
+    fn deref_mut(&mut self) -> &mut Packet {
+        self.packet.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledPacket {
+    fn drop(&mut self) {
+        if let Some(packet) = self.packet.take() {
+            self.pool.recycle(packet);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::mem;
+use std::ptr;
+
+use super::{side_data, Mut, Packet, Ref};
+use ffi::*;
+use libc::ENOMEM;
+use {time, Error};
+
+/// Wallclock time at which a packet was produced, carried as
+/// `AV_PKT_DATA_PRFT` side data.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct ProducerReferenceTime {
+    /// Microseconds since the Unix epoch.
+    pub wallclock: i64,
+    pub flags: i32,
+}
+
+impl ProducerReferenceTime {
+    /// Stamp with the current wallclock time.
+    pub fn now() -> Self {
+        ProducerReferenceTime {
+            wallclock: time::current(),
+            flags: 0,
+        }
+    }
+
+    /// Microseconds elapsed between production and now.
+    pub fn latency(&self) -> i64 {
+        time::current() - self.wallclock
+    }
+}
+
+impl Packet {
+    pub fn producer_reference_time(&self) -> Option<ProducerReferenceTime> {
+        unsafe {
+            let mut size = 0;
+            let ptr =
+                av_packet_get_side_data(self.as_ptr(), side_data::Type::PRFT.into(), &mut size)
+                    as *const AVProducerReferenceTime;
+
+            if ptr.is_null() || (size as usize) < mem::size_of::<AVProducerReferenceTime>() {
+                return None;
+            }
+
+            let value = ptr::read_unaligned(ptr);
+
+            Some(ProducerReferenceTime {
+                wallclock: value.wallclock,
+                flags: value.flags,
+            })
+        }
+    }
+
+    pub fn set_producer_reference_time(
+        &mut self,
+        value: ProducerReferenceTime,
+    ) -> Result<(), Error> {
+        unsafe {
+            let ptr = av_packet_new_side_data(
+                self.as_mut_ptr(),
+                side_data::Type::PRFT.into(),
+                mem::size_of::<AVProducerReferenceTime>() as _,
+            ) as *mut AVProducerReferenceTime;
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr::write_unaligned(
+                ptr,
+                AVProducerReferenceTime {
+                    wallclock: value.wallclock,
+                    flags: value.flags,
+                },
+            );
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::mem;
+
+use super::{side_data, Packet};
+use ffi::*;
+use libc::c_int;
+use picture;
+
+/// Encoding statistics of a packet, carried as `AV_PKT_DATA_QUALITY_STATS`
+/// side data by most encoders.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct QualityStats {
+    /// Quality as a lagrange multiplier, see `qp` for the quantizer.
+    pub quality: u32,
+
+    pub kind: picture::Type,
+
+    /// Sum of squared errors per plane, only filled in when encoding with
+    /// `codec::Flags::PSNR`.
+    pub errors: Vec<u64>,
+}
+
+impl QualityStats {
+    /// The quantizer the lagrange multiplier corresponds to.
+    pub fn qp(&self) -> f64 {
+        f64::from(self.quality) / f64::from(FF_QP2LAMBDA)
+    }
+
+    // Little-endian quality, picture type, error count and 2 reserved bytes,
+    // followed by one little-endian 64 bits error per plane.
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 8 {
+            return None;
+        }
+
+        let word = |offset: usize, size: usize| {
+            (0..size).fold(0u64, |value, i| {
+                value | (u64::from(bytes[offset + i]) << (8 * i))
+            })
+        };
+
+        let count = bytes[5] as usize;
+
+        if bytes.len() < 8 + count * 8 {
+            return None;
+        }
+
+        Some(QualityStats {
+            quality: word(0, 4) as u32,
+            kind: kind(bytes[4]),
+            errors: (0..count).map(|i| word(8 + i * 8, 8)).collect(),
+        })
+    }
+}
+
+// Out of range values are not valid `AVPictureType`s, so cannot be converted.
+fn kind(value: u8) -> picture::Type {
+    if value > AVPictureType::AV_PICTURE_TYPE_BI as u8 {
+        return picture::Type::None;
+    }
+
+    unsafe { picture::Type::from(mem::transmute::<_, AVPictureType>(c_int::from(value))) }
+}
+
+impl Packet {
+    pub fn quality_stats(&self) -> Option<QualityStats> {
+        self.side_data()
+            .find(|data| data.kind() == side_data::Type::QualityStats)
+            .and_then(|data| QualityStats::from_bytes(data.data()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes() {
+        let bytes = [0x2c, 0x0b, 0, 0, 2, 1, 0, 0, 0x10, 0x27, 0, 0, 0, 0, 0, 0];
+        let stats = QualityStats::from_bytes(&bytes).unwrap();
+
+        assert_eq!(stats.quality, 2860);
+        assert_eq!(stats.kind, picture::Type::P);
+        assert_eq!(stats.errors, vec![10000]);
+        assert!((stats.qp() - 2860.0 / 118.0).abs() < 1e-9);
+        assert_eq!(QualityStats::from_bytes(&bytes[..12]), None);
+    }
+}
@@ -0,0 +1,86 @@
+use super::{side_data, Packet};
+use Error;
+
+/// Samples a decoder should discard from an audio packet, such as encoder
+/// priming at the start of a stream or padding at its end, carried as
+/// `AV_PKT_DATA_SKIP_SAMPLES` side data.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct SkipSamples {
+    /// Samples to skip from the start of the packet.
+    pub start: u32,
+    /// Samples to skip from the end of the packet.
+    pub end: u32,
+
+    pub start_reason: u8,
+    pub end_reason: u8,
+}
+
+impl SkipSamples {
+    // Little-endian start and end followed by the two reasons.
+    fn to_bytes(&self) -> [u8; 10] {
+        let mut bytes = [0; 10];
+
+        for i in 0..4 {
+            bytes[i] = (self.start >> (8 * i)) as u8;
+            bytes[4 + i] = (self.end >> (8 * i)) as u8;
+        }
+
+        bytes[8] = self.start_reason;
+        bytes[9] = self.end_reason;
+
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 10 {
+            return None;
+        }
+
+        let word = |offset: usize| {
+            (0..4).fold(0u32, |value, i| {
+                value | (u32::from(bytes[offset + i]) << (8 * i))
+            })
+        };
+
+        Some(SkipSamples {
+            start: word(0),
+            end: word(4),
+            start_reason: bytes[8],
+            end_reason: bytes[9],
+        })
+    }
+}
+
+impl Packet {
+    pub fn skip_samples(&self) -> Option<SkipSamples> {
+        self.side_data()
+            .find(|data| data.kind() == side_data::Type::SkipSamples)
+            .and_then(|data| SkipSamples::from_bytes(data.data()))
+    }
+
+    pub fn set_skip_samples(&mut self, value: &SkipSamples) -> Result<(), Error> {
+        self.add_side_data(side_data::Type::SkipSamples, &value.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes() {
+        let value = SkipSamples {
+            start: 1024,
+            end: 0x0102_0304,
+            start_reason: 0,
+            end_reason: 1,
+        };
+
+        assert_eq!(
+            value.to_bytes(),
+            [0x00, 0x04, 0x00, 0x00, 0x04, 0x03, 0x02, 0x01, 0, 1]
+        );
+        assert_eq!(SkipSamples::from_bytes(&value.to_bytes()), Some(value));
+        assert_eq!(SkipSamples::from_bytes(&[0; 9]), None);
+    }
+}
@@ -1,19 +1,24 @@
 use std::any::Any;
-use std::rc::Rc;
+#[cfg(feature = "nonzero")]
+use std::num::NonZeroU32;
+use std::sync::Arc;
 
 use super::{Context, Id};
 use ffi::*;
-use media;
+use {media, Error};
 
 pub struct Parameters {
     ptr: *mut AVCodecParameters,
-    owner: Option<Rc<dyn Any>>,
+    owner: Option<Arc<dyn Any + Send + Sync>>,
 }
 
 unsafe impl Send for Parameters {}
 
 impl Parameters {
-    pub unsafe fn wrap(ptr: *mut AVCodecParameters, owner: Option<Rc<dyn Any>>) -> Self {
+    pub unsafe fn wrap(
+        ptr: *mut AVCodecParameters,
+        owner: Option<Arc<dyn Any + Send + Sync>>,
+    ) -> Self {
         Parameters { ptr, owner }
     }
 
@@ -43,6 +48,74 @@ impl Parameters {
     pub fn id(&self) -> Id {
         unsafe { Id::from((*self.as_ptr()).codec_id) }
     }
+
+    /// Width of video streams, 0 when unknown.
+    #[cfg(not(feature = "nonzero"))]
+    pub fn width(&self) -> u32 {
+        self.raw_width()
+    }
+
+    /// Width of video streams, `None` when unknown.
+    #[cfg(feature = "nonzero")]
+    pub fn width(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_width())
+    }
+
+    /// Height of video streams, 0 when unknown.
+    #[cfg(not(feature = "nonzero"))]
+    pub fn height(&self) -> u32 {
+        self.raw_height()
+    }
+
+    /// Height of video streams, `None` when unknown.
+    #[cfg(feature = "nonzero")]
+    pub fn height(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_height())
+    }
+
+    /// Sample rate of audio streams, 0 when unknown.
+    #[cfg(not(feature = "nonzero"))]
+    pub fn rate(&self) -> u32 {
+        self.raw_rate()
+    }
+
+    /// Sample rate of audio streams, `None` when unknown.
+    #[cfg(feature = "nonzero")]
+    pub fn rate(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_rate())
+    }
+
+    // The fields behind `width`, `height` and `rate`, whatever type the
+    // `nonzero` feature makes them return.
+
+    #[doc(hidden)]
+    pub fn raw_width(&self) -> u32 {
+        unsafe { (*self.as_ptr()).width as u32 }
+    }
+
+    #[doc(hidden)]
+    pub fn raw_height(&self) -> u32 {
+        unsafe { (*self.as_ptr()).height as u32 }
+    }
+
+    #[doc(hidden)]
+    pub fn raw_rate(&self) -> u32 {
+        unsafe { (*self.as_ptr()).sample_rate as u32 }
+    }
+
+    /// Out of band codec data, such as the `avcC` record of H.264 holding
+    /// its SPS and PPS.
+    pub fn extradata(&self) -> Option<&[u8]> {
+        unsafe { super::extradata((*self.as_ptr()).extradata, (*self.as_ptr()).extradata_size) }
+    }
+
+    /// Replace the extradata with a padded copy of `value`.
+    pub fn set_extradata(&mut self, value: Option<&[u8]>) -> Result<(), Error> {
+        unsafe {
+            let ptr = self.as_mut_ptr();
+            super::set_extradata(&mut (*ptr).extradata, &mut (*ptr).extradata_size, value)
+        }
+    }
 }
 
 impl Default for Parameters {
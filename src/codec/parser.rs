@@ -0,0 +1,284 @@
+//! Codec parsers, splitting raw bitstreams into frames and reporting what
+//! they learn about each one, such as whether it is a keyframe, for codecs
+//! and containers that do not flag packets reliably.
+
+use std::marker::PhantomData;
+#[cfg(feature = "nonzero")]
+use std::num::NonZeroU32;
+use std::ptr;
+use std::slice;
+
+use super::field_order::FieldOrder;
+use super::{Context, Id};
+use ffi::AVPictureStructure::*;
+use ffi::AVPictureType::*;
+use ffi::*;
+use libc::c_int;
+use {picture, Error};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Structure {
+    Unknown,
+    TopField,
+    BottomField,
+    Frame,
+}
+
+impl From<AVPictureStructure> for Structure {
+    fn from(value: AVPictureStructure) -> Self {
+        match value {
+            AV_PICTURE_STRUCTURE_UNKNOWN => Structure::Unknown,
+            AV_PICTURE_STRUCTURE_TOP_FIELD => Structure::TopField,
+            AV_PICTURE_STRUCTURE_BOTTOM_FIELD => Structure::BottomField,
+            AV_PICTURE_STRUCTURE_FRAME => Structure::Frame,
+        }
+    }
+}
+
+/// What a parser found out about the last frame it output.
+pub struct State<'a> {
+    ptr: *const AVCodecParserContext,
+
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> State<'a> {
+    pub unsafe fn wrap(ptr: *const AVCodecParserContext) -> Self {
+        State {
+            ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVCodecParserContext {
+        self.ptr
+    }
+}
+
+impl<'a> State<'a> {
+    /// Whether the frame is a keyframe or recovery point, `None` when the
+    /// parser cannot tell.
+    pub fn is_key(&self) -> Option<bool> {
+        unsafe {
+            match (*self.as_ptr()).key_frame {
+                -1 => None,
+                0 => Some(false),
+                _ => Some(true),
+            }
+        }
+    }
+
+    pub fn kind(&self) -> picture::Type {
+        let kind = unsafe { (*self.as_ptr()).pict_type };
+
+        match kind {
+            t if t == AV_PICTURE_TYPE_I as c_int => picture::Type::I,
+            t if t == AV_PICTURE_TYPE_P as c_int => picture::Type::P,
+            t if t == AV_PICTURE_TYPE_B as c_int => picture::Type::B,
+            t if t == AV_PICTURE_TYPE_S as c_int => picture::Type::S,
+            t if t == AV_PICTURE_TYPE_SI as c_int => picture::Type::SI,
+            t if t == AV_PICTURE_TYPE_SP as c_int => picture::Type::SP,
+            t if t == AV_PICTURE_TYPE_BI as c_int => picture::Type::BI,
+            _ => picture::Type::None,
+        }
+    }
+
+    /// Whether the frame is a whole picture or a single field, which
+    /// segmenters must not split a field pair at.
+    pub fn structure(&self) -> Structure {
+        unsafe { Structure::from((*self.as_ptr()).picture_structure) }
+    }
+
+    pub fn field_order(&self) -> FieldOrder {
+        unsafe { FieldOrder::from((*self.as_ptr()).field_order) }
+    }
+
+    /// Extra field durations the frame is displayed for, as in
+    /// `AVFrame::repeat_pict`.
+    pub fn repeat_pict(&self) -> i32 {
+        unsafe { (*self.as_ptr()).repeat_pict as i32 }
+    }
+
+    /// Position of the frame in display order, for codecs that tell.
+    pub fn output_picture_number(&self) -> i32 {
+        unsafe { (*self.as_ptr()).output_picture_number as i32 }
+    }
+
+    #[cfg(not(feature = "nonzero"))]
+    pub fn width(&self) -> u32 {
+        unsafe { (*self.as_ptr()).width as u32 }
+    }
+
+    /// `None` when the parser cannot tell.
+    #[cfg(feature = "nonzero")]
+    pub fn width(&self) -> Option<NonZeroU32> {
+        unsafe { NonZeroU32::new((*self.as_ptr()).width as u32) }
+    }
+
+    #[cfg(not(feature = "nonzero"))]
+    pub fn height(&self) -> u32 {
+        unsafe { (*self.as_ptr()).height as u32 }
+    }
+
+    /// `None` when the parser cannot tell.
+    #[cfg(feature = "nonzero")]
+    pub fn height(&self) -> Option<NonZeroU32> {
+        unsafe { NonZeroU32::new((*self.as_ptr()).height as u32) }
+    }
+
+    #[cfg(not(feature = "nonzero"))]
+    pub fn coded_width(&self) -> u32 {
+        unsafe { (*self.as_ptr()).coded_width as u32 }
+    }
+
+    /// `None` when the parser cannot tell.
+    #[cfg(feature = "nonzero")]
+    pub fn coded_width(&self) -> Option<NonZeroU32> {
+        unsafe { NonZeroU32::new((*self.as_ptr()).coded_width as u32) }
+    }
+
+    #[cfg(not(feature = "nonzero"))]
+    pub fn coded_height(&self) -> u32 {
+        unsafe { (*self.as_ptr()).coded_height as u32 }
+    }
+
+    /// `None` when the parser cannot tell.
+    #[cfg(feature = "nonzero")]
+    pub fn coded_height(&self) -> Option<NonZeroU32> {
+        unsafe { NonZeroU32::new((*self.as_ptr()).coded_height as u32) }
+    }
+}
+
+/// A standalone parser, for packets read without libavformat parsing them,
+/// or to inspect packets as they are copied.
+pub struct Parser {
+    ptr: *mut AVCodecParserContext,
+    context: Context,
+}
+
+unsafe impl Send for Parser {}
+
+impl Parser {
+    /// A parser for `context`, which should have the stream parameters set.
+    ///
+    /// Returns `Error::DecoderNotFound` when there is no parser for the
+    /// codec.
+    pub fn new(context: Context) -> Result<Self, Error> {
+        unsafe {
+            let ptr = av_parser_init(AVCodecID::from(context.id()) as c_int);
+
+            if ptr.is_null() {
+                return Err(Error::DecoderNotFound);
+            }
+
+            Ok(Parser { ptr, context })
+        }
+    }
+
+    /// A parser for `id`, without stream parameters.
+    pub fn find(id: Id) -> Result<Self, Error> {
+        let mut context = Context::new();
+
+        unsafe {
+            (*context.as_mut_ptr()).codec_id = id.into();
+        }
+
+        Parser::new(context)
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVCodecParserContext {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVCodecParserContext {
+        self.ptr
+    }
+
+    /// Take every input buffer as exactly one frame instead of looking for
+    /// frame boundaries, as is right for demuxed packets.
+    pub fn set_complete_frames(&mut self, value: bool) {
+        unsafe {
+            if value {
+                (*self.ptr).flags |= PARSER_FLAG_COMPLETE_FRAMES;
+            } else {
+                (*self.ptr).flags &= !PARSER_FLAG_COMPLETE_FRAMES;
+            }
+        }
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    pub fn state(&self) -> State {
+        unsafe { State::wrap(self.ptr) }
+    }
+
+    /// Feed `data`, returning how much of it was used and the next frame
+    /// when one is complete. An empty `data` flushes the last frame.
+    pub fn parse(
+        &mut self,
+        data: &[u8],
+        pts: Option<i64>,
+        dts: Option<i64>,
+        position: i64,
+    ) -> (usize, Option<&[u8]>) {
+        unsafe {
+            let mut out = ptr::null_mut();
+            let mut size = 0;
+
+            let used = av_parser_parse2(
+                self.ptr,
+                self.context.as_mut_ptr(),
+                &mut out,
+                &mut size,
+                data.as_ptr(),
+                data.len() as c_int,
+                pts.unwrap_or(AV_NOPTS_VALUE),
+                dts.unwrap_or(AV_NOPTS_VALUE),
+                position,
+            );
+
+            let frame = if size > 0 {
+                Some(slice::from_raw_parts(out as *const u8, size as usize))
+            } else {
+                None
+            };
+
+            (used.max(0) as usize, frame)
+        }
+    }
+
+    /// Parse all of `data`, returning whether any frame in it is a
+    /// keyframe.
+    pub fn is_key(&mut self, mut data: &[u8]) -> bool {
+        let mut key = false;
+
+        while !data.is_empty() {
+            let (used, complete) = {
+                let (used, frame) = self.parse(data, None, None, -1);
+                (used, frame.is_some())
+            };
+
+            if used == 0 {
+                break;
+            }
+
+            if complete && self.state().is_key() == Some(true) {
+                key = true;
+            }
+
+            data = &data[used..];
+        }
+
+        key
+    }
+}
+
+impl Drop for Parser {
+    fn drop(&mut self) {
+        unsafe {
+            av_parser_close(self.ptr);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::ptr;
+
+use super::codec::Codec;
+use super::{Capabilities, Id};
+use ffi::*;
+#[cfg(feature = "ffmpeg_4_0")]
+use hwcontext;
+#[cfg(feature = "ffmpeg_4_0")]
+use libc::c_int;
+use {format, media, ChannelLayout, Rational};
+
+#[cfg(feature = "ffmpeg_4_0")]
+bitflags! {
+    /// How a codec can be set up for a hardware configuration, mirroring
+    /// `AV_CODEC_HW_CONFIG_METHOD_*`.
+    pub struct Methods: c_int {
+        const HW_DEVICE_CTX = AV_CODEC_HW_CONFIG_METHOD_HW_DEVICE_CTX as c_int;
+        const HW_FRAMES_CTX = AV_CODEC_HW_CONFIG_METHOD_HW_FRAMES_CTX as c_int;
+        const INTERNAL      = AV_CODEC_HW_CONFIG_METHOD_INTERNAL as c_int;
+        const AD_HOC        = AV_CODEC_HW_CONFIG_METHOD_AD_HOC as c_int;
+    }
+}
+
+#[cfg(feature = "ffmpeg_4_0")]
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct HwConfig {
+    pub format: format::Pixel,
+    pub device: hwcontext::Type,
+    pub methods: Methods,
+}
+
+/// Everything an encoder declares about what it accepts.
+///
+/// Lists are `None` when the encoder does not restrict them.
+#[derive(PartialEq, Clone, Debug)]
+pub struct EncoderInfo {
+    pub name: String,
+    pub description: String,
+    pub id: Id,
+    pub medium: media::Type,
+    pub capabilities: Capabilities,
+
+    pub pixel_formats: Option<Vec<format::Pixel>>,
+    pub frame_rates: Option<Vec<Rational>>,
+
+    pub sample_formats: Option<Vec<format::Sample>>,
+    pub sample_rates: Option<Vec<i32>>,
+    pub channel_layouts: Option<Vec<ChannelLayout>>,
+
+    #[cfg(feature = "ffmpeg_4_0")]
+    pub hw_configs: Vec<HwConfig>,
+}
+
+impl EncoderInfo {
+    pub fn new(codec: Codec) -> Self {
+        let mut info = EncoderInfo {
+            name: codec.name().to_owned(),
+            description: codec.description().to_owned(),
+            id: codec.id(),
+            medium: codec.medium(),
+            capabilities: codec.capabilities(),
+
+            pixel_formats: None,
+            frame_rates: None,
+
+            sample_formats: None,
+            sample_rates: None,
+            channel_layouts: None,
+
+            #[cfg(feature = "ffmpeg_4_0")]
+            hw_configs: hw_configs(codec),
+        };
+
+        if let Ok(video) = codec.video() {
+            info.pixel_formats = video.formats().map(Iterator::collect);
+            info.frame_rates = video.rates().map(Iterator::collect);
+        }
+
+        if let Ok(audio) = codec.audio() {
+            info.sample_formats = audio.formats().map(Iterator::collect);
+            info.sample_rates = audio.rates().map(Iterator::collect);
+            info.channel_layouts = audio.channel_layouts().map(Iterator::collect);
+        }
+
+        info
+    }
+}
+
+#[cfg(feature = "ffmpeg_4_0")]
+fn hw_configs(codec: Codec) -> Vec<HwConfig> {
+    let mut configs = Vec::new();
+
+    unsafe {
+        for index in 0.. {
+            let config = avcodec_get_hw_config(codec.as_ptr(), index);
+
+            if config.is_null() {
+                break;
+            }
+
+            configs.push(HwConfig {
+                format: format::Pixel::from((*config).pix_fmt),
+                device: hwcontext::Type::from((*config).device_type),
+                methods: Methods::from_bits_truncate((*config).methods),
+            });
+        }
+    }
+
+    configs
+}
+
+/// Describe every encoder of the linked FFmpeg, to compare builds or pick
+/// encoders by capability.
+#[cfg(feature = "ffmpeg_4_0")]
+pub fn encoders() -> Vec<EncoderInfo> {
+    let mut encoders = Vec::new();
+
+    unsafe {
+        let mut opaque = ptr::null_mut();
+
+        loop {
+            let ptr = av_codec_iterate(&mut opaque);
+
+            if ptr.is_null() {
+                break;
+            }
+
+            if av_codec_is_encoder(ptr) != 0 {
+                encoders.push(EncoderInfo::new(Codec::wrap(ptr as *mut _)));
+            }
+        }
+    }
+
+    encoders
+}
+
+/// Describe every encoder of the linked FFmpeg, to compare builds or pick
+/// encoders by capability.
+#[cfg(not(feature = "ffmpeg_4_0"))]
+pub fn encoders() -> Vec<EncoderInfo> {
+    let mut encoders = Vec::new();
+
+    unsafe {
+        let mut ptr = av_codec_next(ptr::null());
+
+        while !ptr.is_null() {
+            if av_codec_is_encoder(ptr) != 0 {
+                encoders.push(EncoderInfo::new(Codec::wrap(ptr)));
+            }
+
+            ptr = av_codec_next(ptr);
+        }
+    }
+
+    encoders
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    use super::*;
+
+    // Enums serialize to their names and rationals to `num/den`, as in
+    // `format::describe`.
+    fn names<T: ::std::fmt::Debug>(list: &Option<Vec<T>>) -> Option<Vec<String>> {
+        list.as_ref()
+            .map(|list| list.iter().map(|item| format!("{:?}", item)).collect())
+    }
+
+    impl Serialize for EncoderInfo {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("EncoderInfo", 11)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("description", &self.description)?;
+            state.serialize_field("id", &format!("{:?}", self.id))?;
+            state.serialize_field("medium", &format!("{:?}", self.medium))?;
+            state.serialize_field("capabilities", &self.capabilities.bits())?;
+            state.serialize_field("pixel_formats", &names(&self.pixel_formats))?;
+            state.serialize_field(
+                "frame_rates",
+                &self
+                    .frame_rates
+                    .as_ref()
+                    .map(|rates| rates.iter().map(|r| r.to_string()).collect::<Vec<_>>()),
+            )?;
+            state.serialize_field("sample_formats", &names(&self.sample_formats))?;
+            state.serialize_field("sample_rates", &self.sample_rates)?;
+            state.serialize_field(
+                "channel_layouts",
+                &self
+                    .channel_layouts
+                    .as_ref()
+                    .map(|layouts| layouts.iter().map(|l| l.bits()).collect::<Vec<_>>()),
+            )?;
+            #[cfg(feature = "ffmpeg_4_0")]
+            state.serialize_field("hw_configs", &self.hw_configs)?;
+            state.end()
+        }
+    }
+
+    #[cfg(feature = "ffmpeg_4_0")]
+    impl Serialize for HwConfig {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("HwConfig", 3)?;
+            state.serialize_field("format", &format!("{:?}", self.format))?;
+            state.serialize_field("device", &format!("{:?}", self.device))?;
+            state.serialize_field("methods", &self.methods.bits())?;
+            state.end()
+        }
+    }
+}
@@ -12,7 +12,8 @@ use std::mem;
 
 use ffi::AVSubtitleType::*;
 use ffi::*;
-use libc::{c_uint, size_t};
+use libc::{c_uint, c_void, size_t};
+use std::ptr;
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub enum Type {
@@ -44,8 +45,15 @@ impl From<Type> for AVSubtitleType {
     }
 }
 
+/// An owned subtitle, freeing its rectangles when dropped.
+///
+/// It does not borrow from the decoder that produced it, so it can be queued
+/// or sent to another thread for later rendering.
 pub struct Subtitle(AVSubtitle);
 
+unsafe impl Send for Subtitle {}
+unsafe impl Sync for Subtitle {}
+
 impl Subtitle {
     pub unsafe fn as_ptr(&self) -> *const AVSubtitle {
         &self.0
@@ -121,6 +129,65 @@ impl Default for Subtitle {
     }
 }
 
+impl Clone for Subtitle {
+    fn clone(&self) -> Self {
+        unsafe {
+            let mut subtitle = Subtitle(self.0);
+            subtitle.0.num_rects = 0;
+            subtitle.0.rects = ptr::null_mut();
+
+            for rect in self.rects() {
+                let source = rect.as_ptr();
+                let kind = Type::from((*source).type_);
+                let target = subtitle.add_rect(kind).as_ptr() as *mut AVSubtitleRect;
+
+                copy_rect(target, source);
+            }
+
+            subtitle
+        }
+    }
+}
+
+unsafe fn copy_rect(target: *mut AVSubtitleRect, source: *const AVSubtitleRect) {
+    (*target).x = (*source).x;
+    (*target).y = (*source).y;
+    (*target).w = (*source).w;
+    (*target).h = (*source).h;
+    (*target).nb_colors = (*source).nb_colors;
+    (*target).flags = (*source).flags;
+
+    // Bitmaps carry indices in the first plane and the palette in the second.
+    let sizes = [
+        (*source).linesize[0] as usize * (*source).h as usize,
+        AVPALETTE_SIZE as usize,
+    ];
+
+    for (plane, &size) in sizes.iter().enumerate() {
+        if !(*source).data[plane].is_null() {
+            (*target).data[plane] =
+                av_memdup((*source).data[plane] as *const c_void, size as _) as *mut u8;
+            (*target).linesize[plane] = (*source).linesize[plane];
+        }
+    }
+
+    if !(*source).text.is_null() {
+        (*target).text = av_strdup((*source).text);
+    }
+
+    if !(*source).ass.is_null() {
+        (*target).ass = av_strdup((*source).ass);
+    }
+}
+
+impl Drop for Subtitle {
+    fn drop(&mut self) {
+        unsafe {
+            avsubtitle_free(&mut self.0);
+        }
+    }
+}
+
 pub struct RectIter<'a> {
     ptr: *const AVSubtitle,
     cur: c_uint,
@@ -208,3 +275,29 @@ impl<'a> Iterator for RectMutIter<'a> {
 }
 
 impl<'a> ExactSizeIterator for RectMutIter<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone() {
+        let mut subtitle = Subtitle::new();
+        subtitle.set_pts(Some(42));
+
+        if let RectMut::Text(mut text) = subtitle.add_rect(Type::Text) {
+            text.set("hello");
+        }
+
+        let copy = subtitle.clone();
+        drop(subtitle);
+
+        assert_eq!(copy.pts(), Some(42));
+        assert_eq!(copy.rects().len(), 1);
+
+        match copy.rects().next() {
+            Some(Rect::Text(text)) => assert_eq!(text.get(), "hello"),
+            _ => panic!("expected a text rectangle"),
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::ops::Deref;
 
 use super::{Ass, Bitmap, Flags, Text, Type};
 use ffi::*;
-use libc::c_int;
+use libc::{c_int, c_void};
 
 pub enum RectMut<'a> {
     None(*mut AVSubtitleRect),
@@ -131,6 +131,7 @@ impl<'a> TextMut<'a> {
         let value = CString::new(value).unwrap();
 
         unsafe {
+            av_freep(&mut (*self.as_mut_ptr()).text as *mut _ as *mut c_void);
             (*self.as_mut_ptr()).text = av_strdup(value.as_ptr());
         }
     }
@@ -165,6 +166,7 @@ impl<'a> AssMut<'a> {
         let value = CString::new(value).unwrap();
 
         unsafe {
+            av_freep(&mut (*self.as_mut_ptr()).ass as *mut _ as *mut c_void);
             (*self.as_mut_ptr()).ass = av_strdup(value.as_ptr());
         }
     }
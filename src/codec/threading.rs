@@ -29,6 +29,24 @@ impl Config {
             ..Default::default()
         }
     }
+
+    /// Change the threading method, keeping the other settings.
+    pub fn with_kind(mut self, value: Type) -> Self {
+        self.kind = value;
+        self
+    }
+
+    /// Change the number of threads, keeping the other settings.
+    pub fn with_count(mut self, value: usize) -> Self {
+        self.count = value;
+        self
+    }
+
+    /// Change whether callbacks are thread safe, keeping the other settings.
+    pub fn with_safe(mut self, value: bool) -> Self {
+        self.safe = value;
+        self
+    }
 }
 
 impl Default for Config {
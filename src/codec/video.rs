@@ -2,6 +2,7 @@ use std::ops::Deref;
 
 use super::codec::Codec;
 use ffi::*;
+use libc::c_int;
 use {format, Rational};
 
 #[derive(PartialEq, Eq, Copy, Clone)]
@@ -35,6 +36,52 @@ impl Video {
             }
         }
     }
+
+    /// Whether the codec takes `format`, codecs listing no formats being
+    /// assumed to take any.
+    pub fn supports_format(&self, format: format::Pixel) -> bool {
+        self.formats()
+            .map_or(true, |mut formats| formats.any(|f| f == format))
+    }
+
+    /// The supported format converting frames in `source` to loses the
+    /// least, as ranked by FFmpeg, or `source` itself when the codec lists
+    /// no formats.
+    pub fn pick_best_pixel_format(&self, source: format::Pixel) -> format::Pixel {
+        unsafe {
+            let list = (*self.codec.as_ptr()).pix_fmts;
+
+            if list.is_null() {
+                return source;
+            }
+
+            let alpha = source.descriptor().map_or(false, |descriptor| {
+                (*descriptor.as_ptr()).flags & AV_PIX_FMT_FLAG_ALPHA as u64 != 0
+            });
+            let mut loss = 0;
+
+            format::Pixel::from(avcodec_find_best_pix_fmt_of_list(
+                list,
+                source.into(),
+                alpha as c_int,
+                &mut loss,
+            ))
+        }
+    }
+
+    /// The supported frame rate closest to `rate`, or `rate` itself when
+    /// the codec lists no rates.
+    pub fn pick_best_rate(&self, rate: Rational) -> Rational {
+        unsafe {
+            let list = (*self.codec.as_ptr()).supported_framerates;
+
+            if list.is_null() {
+                return rate;
+            }
+
+            Rational::from(*list.offset(av_find_nearest_q_idx(rate.into(), list) as isize))
+        }
+    }
 }
 
 impl Deref for Video {
@@ -0,0 +1,288 @@
+use std::collections::VecDeque;
+use std::mem;
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+use ffi::AVAppToDevMessageType::*;
+use ffi::AVDevToAppMessageType::*;
+use ffi::*;
+use format::context::common::Context;
+use libc::{c_int, c_void, EBUSY, ENOSYS};
+use Error;
+
+/// An area of the window a device renders to, in pixels.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl From<AVDeviceRect> for Rect {
+    fn from(value: AVDeviceRect) -> Self {
+        Rect {
+            x: value.x,
+            y: value.y,
+            width: value.width,
+            height: value.height,
+        }
+    }
+}
+
+impl From<Rect> for AVDeviceRect {
+    fn from(value: Rect) -> Self {
+        AVDeviceRect {
+            x: value.x,
+            y: value.y,
+            width: value.width,
+            height: value.height,
+        }
+    }
+}
+
+/// A request from the application to a device.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Control {
+    /// The window was resized to the given area.
+    WindowSize(Rect),
+
+    /// Repaint the given area, or the whole window.
+    WindowRepaint(Option<Rect>),
+
+    Pause,
+    Play,
+    TogglePause,
+
+    /// Set the volume, from 0.0 to 1.0.
+    SetVolume(f64),
+
+    Mute,
+    Unmute,
+    ToggleMute,
+
+    /// Ask for the volume, answered by `Message::VolumeLevelChanged`.
+    GetVolume,
+
+    /// Ask for the mute state, answered by `Message::MuteStateChanged`.
+    GetMute,
+}
+
+/// A notification from a device to the application.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Message {
+    None,
+
+    /// The device wants a window with the given area, or of its own choice.
+    CreateWindowBuffer(Option<Rect>),
+    PrepareWindowBuffer,
+    DisplayWindowBuffer,
+    DestroyWindowBuffer,
+
+    BufferOverflow,
+    BufferUnderflow,
+
+    /// The device has data to read, with the amount of bytes if known.
+    BufferReadable(Option<i64>),
+
+    /// The device has room to write, with the amount of bytes if known.
+    BufferWritable(Option<i64>),
+
+    MuteStateChanged(bool),
+    VolumeLevelChanged(f64),
+}
+
+impl Message {
+    unsafe fn read(kind: c_int, data: *const c_void, size: usize) -> Option<Self> {
+        let rect = || {
+            if data.is_null() || size < mem::size_of::<AVDeviceRect>() {
+                None
+            } else {
+                Some(Rect::from(ptr::read_unaligned(data as *const AVDeviceRect)))
+            }
+        };
+
+        let amount = || {
+            if data.is_null() || size < mem::size_of::<i64>() {
+                None
+            } else {
+                Some(ptr::read_unaligned(data as *const i64))
+            }
+        };
+
+        Some(match kind {
+            k if k == AV_DEV_TO_APP_NONE as c_int => Message::None,
+            k if k == AV_DEV_TO_APP_CREATE_WINDOW_BUFFER as c_int => {
+                Message::CreateWindowBuffer(rect())
+            }
+            k if k == AV_DEV_TO_APP_PREPARE_WINDOW_BUFFER as c_int => Message::PrepareWindowBuffer,
+            k if k == AV_DEV_TO_APP_DISPLAY_WINDOW_BUFFER as c_int => Message::DisplayWindowBuffer,
+            k if k == AV_DEV_TO_APP_DESTROY_WINDOW_BUFFER as c_int => Message::DestroyWindowBuffer,
+            k if k == AV_DEV_TO_APP_BUFFER_OVERFLOW as c_int => Message::BufferOverflow,
+            k if k == AV_DEV_TO_APP_BUFFER_UNDERFLOW as c_int => Message::BufferUnderflow,
+            k if k == AV_DEV_TO_APP_BUFFER_READABLE as c_int => Message::BufferReadable(amount()),
+            k if k == AV_DEV_TO_APP_BUFFER_WRITABLE as c_int => Message::BufferWritable(amount()),
+
+            k if k == AV_DEV_TO_APP_MUTE_STATE_CHANGED as c_int => {
+                if data.is_null() || size < mem::size_of::<c_int>() {
+                    return None;
+                }
+
+                Message::MuteStateChanged(ptr::read_unaligned(data as *const c_int) != 0)
+            }
+
+            k if k == AV_DEV_TO_APP_VOLUME_LEVEL_CHANGED as c_int => {
+                if data.is_null() || size < mem::size_of::<f64>() {
+                    return None;
+                }
+
+                Message::VolumeLevelChanged(ptr::read_unaligned(data as *const f64))
+            }
+
+            _ => return None,
+        })
+    }
+
+    fn is_window(&self) -> bool {
+        match *self {
+            Message::CreateWindowBuffer(..)
+            | Message::PrepareWindowBuffer
+            | Message::DisplayWindowBuffer
+            | Message::DestroyWindowBuffer => true,
+            _ => false,
+        }
+    }
+}
+
+/// The messages a device sent, queued up until the application gets to
+/// them. Clones share the same queue.
+#[derive(Clone)]
+pub struct Messages {
+    queue: Arc<Mutex<VecDeque<Message>>>,
+}
+
+impl Messages {
+    pub fn pop(&self) -> Option<Message> {
+        self.queue.lock().unwrap().pop_front()
+    }
+
+    /// Take every queued message, oldest first.
+    pub fn drain(&self) -> Vec<Message> {
+        self.queue.lock().unwrap().drain(..).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.lock().unwrap().is_empty()
+    }
+}
+
+impl Context {
+    /// Send `control` to the device behind this context, failing with
+    /// `ENOSYS` when the device does not handle it.
+    pub fn send_control(&mut self, control: Control) -> Result<(), Error> {
+        unsafe {
+            let mut rect;
+            let mut volume;
+
+            let (kind, data, size) = match control {
+                Control::WindowSize(value) => {
+                    rect = AVDeviceRect::from(value);
+                    (
+                        AV_APP_TO_DEV_WINDOW_SIZE,
+                        &mut rect as *mut _ as *mut c_void,
+                        mem::size_of::<AVDeviceRect>(),
+                    )
+                }
+                Control::WindowRepaint(Some(value)) => {
+                    rect = AVDeviceRect::from(value);
+                    (
+                        AV_APP_TO_DEV_WINDOW_REPAINT,
+                        &mut rect as *mut _ as *mut c_void,
+                        mem::size_of::<AVDeviceRect>(),
+                    )
+                }
+                Control::WindowRepaint(None) => (AV_APP_TO_DEV_WINDOW_REPAINT, ptr::null_mut(), 0),
+                Control::Pause => (AV_APP_TO_DEV_PAUSE, ptr::null_mut(), 0),
+                Control::Play => (AV_APP_TO_DEV_PLAY, ptr::null_mut(), 0),
+                Control::TogglePause => (AV_APP_TO_DEV_TOGGLE_PAUSE, ptr::null_mut(), 0),
+                Control::SetVolume(value) => {
+                    volume = value;
+                    (
+                        AV_APP_TO_DEV_SET_VOLUME,
+                        &mut volume as *mut _ as *mut c_void,
+                        mem::size_of::<f64>(),
+                    )
+                }
+                Control::Mute => (AV_APP_TO_DEV_MUTE, ptr::null_mut(), 0),
+                Control::Unmute => (AV_APP_TO_DEV_UNMUTE, ptr::null_mut(), 0),
+                Control::ToggleMute => (AV_APP_TO_DEV_TOGGLE_MUTE, ptr::null_mut(), 0),
+                Control::GetVolume => (AV_APP_TO_DEV_GET_VOLUME, ptr::null_mut(), 0),
+                Control::GetMute => (AV_APP_TO_DEV_GET_MUTE, ptr::null_mut(), 0),
+            };
+
+            match avdevice_app_to_dev_control_message(self.as_mut_ptr(), kind, data, size as _) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Queue the messages the device sends from now on, replacing any
+    /// previous queue.
+    ///
+    /// Window buffer requests are queued but answered with `ENOSYS`, there
+    /// being no way to hand a window over from here. Devices relying on the
+    /// application for their window, such as `opengl` with `no_window` set,
+    /// then fail to write their header, so leave them to open their own.
+    ///
+    /// Fails with `EBUSY` when the `opaque` field of the context is already
+    /// in use, as by the `segment` recorder.
+    pub fn messages(&mut self) -> Result<Messages, Error> {
+        unsafe {
+            let ptr = self.as_mut_ptr();
+
+            let ours = (*ptr)
+                .control_message_cb
+                .map_or(false, |cb| cb as usize == callback as usize);
+
+            if !(*ptr).opaque.is_null() && !ours {
+                return Err(Error::Other { errno: EBUSY });
+            }
+
+            let messages = Messages {
+                queue: Arc::new(Mutex::new(VecDeque::new())),
+            };
+
+            let queue = Arc::clone(&messages.queue);
+
+            (*ptr).opaque = &*queue as *const _ as *mut c_void;
+            (*ptr).control_message_cb = Some(callback);
+            self.destructor().set_control(Some(Box::new(queue)));
+
+            Ok(messages)
+        }
+    }
+}
+
+unsafe extern "C" fn callback(
+    s: *mut AVFormatContext,
+    kind: c_int,
+    data: *mut c_void,
+    size: usize,
+) -> c_int {
+    let queue = &*((*s).opaque as *const Mutex<VecDeque<Message>>);
+
+    match Message::read(kind, data, size) {
+        Some(message) => {
+            queue.lock().unwrap().push_back(message);
+
+            if message.is_window() {
+                AVERROR(ENOSYS)
+            } else {
+                0
+            }
+        }
+
+        None => AVERROR(ENOSYS),
+    }
+}
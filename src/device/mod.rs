@@ -2,6 +2,9 @@ pub mod extensions;
 pub mod input;
 pub mod output;
 
+pub mod message;
+pub use self::message::{Control, Message, Messages};
+
 use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::str::from_utf8_unchecked;
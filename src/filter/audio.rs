@@ -0,0 +1,125 @@
+use std::ops::{Deref, DerefMut};
+
+use super::{find, Graph};
+use codec::{decoder, encoder};
+use {Error, Rational};
+
+/// Sample rate mandated by both EBU R128 and ATSC A/85 deliveries.
+pub const BROADCAST_RATE: u32 = 48000;
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Standard {
+    /// EBU R128: -23 LUFS integrated, -1 dBTP maximum true-peak.
+    EbuR128,
+    /// ATSC A/85: -24 LKFS integrated, -2 dBTP maximum true-peak.
+    AtscA85,
+}
+
+impl Standard {
+    /// Integrated loudness target in LUFS.
+    pub fn loudness(&self) -> f64 {
+        match *self {
+            Standard::EbuR128 => -23.0,
+            Standard::AtscA85 => -24.0,
+        }
+    }
+
+    /// Maximum true-peak level in dBTP.
+    pub fn true_peak(&self) -> f64 {
+        match *self {
+            Standard::EbuR128 => -1.0,
+            Standard::AtscA85 => -2.0,
+        }
+    }
+
+    /// Loudness range target in LU.
+    pub fn range(&self) -> f64 {
+        7.0
+    }
+}
+
+/// A configured `abuffer -> loudnorm -> aresample -> alimiter -> abuffersink` graph.
+pub struct Chain {
+    chain: super::Chain,
+    standard: Standard,
+}
+
+impl Chain {
+    pub fn standard(&self) -> Standard {
+        self.standard
+    }
+}
+
+impl Deref for Chain {
+    type Target = super::Chain;
+
+    fn deref(&self) -> &Self::Target {
+        &self.chain
+    }
+}
+
+impl DerefMut for Chain {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.chain
+    }
+}
+
+/// Configure `encoder` for broadcast delivery and build the matching filter
+/// chain from `decoder` output, whose frames are timestamped in `time_base`,
+/// to `encoder` input.
+///
+/// The encoder sample rate and time base are forced to 48 kHz; its sample
+/// format and channel layout must already be set. Loudness normalization is
+/// done by `loudnorm`, whose 192 kHz output is resampled back and passed
+/// through `alimiter` to catch inter-sample overs introduced by resampling.
+pub fn broadcast_preset(
+    standard: Standard,
+    decoder: &decoder::Audio,
+    time_base: Rational,
+    encoder: &mut encoder::Audio,
+) -> Result<Chain, Error> {
+    encoder.set_rate(BROADCAST_RATE as i32);
+    encoder.set_time_base((1, BROADCAST_RATE as i32));
+
+    let mut graph = Graph::new();
+
+    let args = format!(
+        "time_base={}:sample_rate={}:sample_fmt={}:channel_layout=0x{:x}",
+        time_base,
+        decoder.raw_rate(),
+        decoder.format().name(),
+        decoder.channel_layout().bits()
+    );
+
+    graph.add(&find("abuffer").ok_or(Error::FilterNotFound)?, "in", &args)?;
+    graph.add(
+        &find("abuffersink").ok_or(Error::FilterNotFound)?,
+        "out",
+        "",
+    )?;
+
+    {
+        let mut out = graph.get("out").ok_or(Error::InvalidData)?;
+
+        out.set_sample_format(encoder.format());
+        out.set_channel_layout(encoder.channel_layout());
+        out.set_sample_rate(BROADCAST_RATE);
+    }
+
+    let spec = format!(
+        "loudnorm=I={}:TP={}:LRA={},aresample={},alimiter=limit={}",
+        standard.loudness(),
+        standard.true_peak(),
+        standard.range(),
+        BROADCAST_RATE,
+        10f64.powf(standard.true_peak() / 20.0)
+    );
+
+    graph.output("in", 0)?.input("out", 0)?.parse(&spec)?;
+    graph.validate()?;
+
+    Ok(Chain {
+        chain: super::Chain::wrap(graph),
+        standard,
+    })
+}
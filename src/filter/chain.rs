@@ -0,0 +1,53 @@
+use super::{Context, Graph};
+use {Error, Frame, Rational};
+
+/// A configured graph fed through a buffer source named `in` and drained
+/// through a buffer sink named `out`, as built by the helpers of
+/// `filter::audio` and `filter::video`.
+pub struct Chain {
+    graph: Graph,
+}
+
+impl Chain {
+    /// Wrap `graph`, which must already be validated. Operations on a
+    /// missing `in` or `out` filter fail with `Error::InvalidData`.
+    pub fn wrap(graph: Graph) -> Self {
+        Chain { graph }
+    }
+
+    pub fn graph(&mut self) -> &mut Graph {
+        &mut self.graph
+    }
+
+    /// Time base of the output frames.
+    pub fn time_base(&mut self) -> Result<Rational, Error> {
+        Ok(self.get("out")?.sink().time_base())
+    }
+
+    pub fn frame_rate(&mut self) -> Result<Option<Rational>, Error> {
+        Ok(self.get("out")?.sink().frame_rate())
+    }
+
+    /// Set the number of samples per output frame, required for encoders
+    /// lacking `VARIABLE_FRAME_SIZE`.
+    pub fn set_frame_size(&mut self, value: u32) -> Result<(), Error> {
+        self.get("out")?.sink().set_frame_size(value);
+        Ok(())
+    }
+
+    pub fn send_frame(&mut self, frame: &Frame) -> Result<(), Error> {
+        self.get("in")?.source().add(frame)
+    }
+
+    pub fn send_eof(&mut self) -> Result<(), Error> {
+        self.get("in")?.source().flush()
+    }
+
+    pub fn receive_frame(&mut self, frame: &mut Frame) -> Result<(), Error> {
+        self.get("out")?.sink().frame(frame)
+    }
+
+    fn get(&mut self, name: &str) -> Result<Context, Error> {
+        self.graph.get(name).ok_or(Error::InvalidData)
+    }
+}
@@ -65,3 +65,4 @@ unsafe impl<'a> option::Target for Context<'a> {
 }
 
 impl<'a> option::Settable for Context<'a> {}
+impl<'a> option::Gettable for Context<'a> {}
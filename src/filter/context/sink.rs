@@ -1,7 +1,7 @@
 use super::Context;
 use ffi::*;
 use libc::c_int;
-use {Error, Frame};
+use {Error, Frame, Rational};
 
 pub struct Sink<'a> {
     ctx: &'a mut Context<'a>,
@@ -41,4 +41,19 @@ impl<'a> Sink<'a> {
             av_buffersink_set_frame_size(self.ctx.as_mut_ptr(), value);
         }
     }
+
+    /// Time base of the frames returned, once the graph is configured.
+    pub fn time_base(&self) -> Rational {
+        unsafe { Rational::from(av_buffersink_get_time_base(self.ctx.as_ptr())) }
+    }
+
+    /// Frame rate of the output, if constant and known.
+    pub fn frame_rate(&self) -> Option<Rational> {
+        unsafe {
+            match Rational::from(av_buffersink_get_frame_rate(self.ctx.as_ptr())) {
+                Rational(0, _) => None,
+                rate => Some(rate),
+            }
+        }
+    }
 }
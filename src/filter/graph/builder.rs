@@ -0,0 +1,283 @@
+use std::collections::HashSet;
+
+use super::Graph;
+use ffi::*;
+use filter;
+use libc::c_int;
+use {format, frame, media, ChannelLayout, Error, Rational};
+
+/// Parameters of a `buffer` source.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct VideoSrcParams {
+    pub width: u32,
+    pub height: u32,
+    pub format: format::Pixel,
+    pub time_base: Rational,
+    pub aspect_ratio: Rational,
+    pub frame_rate: Option<Rational>,
+}
+
+impl VideoSrcParams {
+    fn args(&self) -> String {
+        let mut args = format!(
+            "video_size={}x{}:pix_fmt={}:time_base={}:pixel_aspect={}",
+            self.width,
+            self.height,
+            AVPixelFormat::from(self.format) as c_int,
+            self.time_base,
+            self.aspect_ratio
+        );
+
+        if let Some(rate) = self.frame_rate {
+            args.push_str(&format!(":frame_rate={}", rate));
+        }
+
+        args
+    }
+}
+
+/// Parameters of an `abuffer` source.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct AudioSrcParams {
+    pub rate: u32,
+    pub format: format::Sample,
+    pub channel_layout: ChannelLayout,
+    pub time_base: Rational,
+}
+
+impl AudioSrcParams {
+    fn args(&self) -> String {
+        format!(
+            "time_base={}:sample_rate={}:sample_fmt={}:channel_layout=0x{:x}",
+            self.time_base,
+            self.rate,
+            self.format.name(),
+            self.channel_layout.bits()
+        )
+    }
+}
+
+/// A filter added to a `Builder`, to be linked by pad index.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Node {
+    index: usize,
+    name: String,
+}
+
+impl Node {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+macro_rules! handle {
+    ($name:ident) => {
+        #[derive(PartialEq, Eq, Clone, Debug)]
+        pub struct $name(Node);
+
+        impl AsRef<Node> for $name {
+            fn as_ref(&self) -> &Node {
+                &self.0
+            }
+        }
+    };
+}
+
+handle!(VideoSource);
+handle!(AudioSource);
+handle!(VideoSink);
+handle!(AudioSink);
+
+impl VideoSource {
+    pub fn add(&self, graph: &mut Graph, frame: &frame::Video) -> Result<(), Error> {
+        context(graph, &self.0)?.source().add(frame)
+    }
+
+    pub fn flush(&self, graph: &mut Graph) -> Result<(), Error> {
+        context(graph, &self.0)?.source().flush()
+    }
+}
+
+impl AudioSource {
+    pub fn add(&self, graph: &mut Graph, frame: &frame::Audio) -> Result<(), Error> {
+        context(graph, &self.0)?.source().add(frame)
+    }
+
+    pub fn flush(&self, graph: &mut Graph) -> Result<(), Error> {
+        context(graph, &self.0)?.source().flush()
+    }
+}
+
+impl VideoSink {
+    pub fn frame(&self, graph: &mut Graph, frame: &mut frame::Video) -> Result<(), Error> {
+        context(graph, &self.0)?.sink().frame(frame)
+    }
+}
+
+impl AudioSink {
+    pub fn frame(&self, graph: &mut Graph, frame: &mut frame::Audio) -> Result<(), Error> {
+        context(graph, &self.0)?.sink().frame(frame)
+    }
+
+    /// Get exactly `samples` samples per frame, except for the last one.
+    pub fn samples(
+        &self,
+        graph: &mut Graph,
+        frame: &mut frame::Audio,
+        samples: usize,
+    ) -> Result<(), Error> {
+        context(graph, &self.0)?.sink().samples(frame, samples)
+    }
+}
+
+fn context<'a>(graph: &'a mut Graph, node: &Node) -> Result<filter::Context<'a>, Error> {
+    graph.get(&node.name).ok_or(Error::InvalidData)
+}
+
+struct Spec {
+    name: String,
+    filter: String,
+    args: String,
+}
+
+#[derive(Clone)]
+struct Link {
+    from: Node,
+    output: usize,
+    to: Node,
+    input: usize,
+}
+
+/// Assemble a filter graph programmatically instead of parsing a textual
+/// description.
+///
+/// Nothing is created until `build`, which reports unknown filters, invalid
+/// arguments, nodes of another builder, out of range or mismatched pads and
+/// unconnected pads before configuring the graph.
+#[derive(Default)]
+pub struct Builder {
+    specs: Vec<Spec>,
+    links: Vec<Link>,
+}
+
+impl Builder {
+    pub fn new() -> Self {
+        Builder::default()
+    }
+
+    pub fn video_source(&mut self, name: &str, params: &VideoSrcParams) -> VideoSource {
+        VideoSource(self.filter(name, "buffer", &params.args()))
+    }
+
+    pub fn audio_source(&mut self, name: &str, params: &AudioSrcParams) -> AudioSource {
+        AudioSource(self.filter(name, "abuffer", &params.args()))
+    }
+
+    pub fn video_sink(&mut self, name: &str) -> VideoSink {
+        VideoSink(self.filter(name, "buffersink", ""))
+    }
+
+    pub fn audio_sink(&mut self, name: &str) -> AudioSink {
+        AudioSink(self.filter(name, "abuffersink", ""))
+    }
+
+    /// Add an instance of the filter named `filter`, configured by `args` in
+    /// the usual `key=value:key=value` syntax.
+    pub fn filter(&mut self, name: &str, filter: &str, args: &str) -> Node {
+        self.specs.push(Spec {
+            name: name.to_owned(),
+            filter: filter.to_owned(),
+            args: args.to_owned(),
+        });
+
+        Node {
+            index: self.specs.len() - 1,
+            name: name.to_owned(),
+        }
+    }
+
+    /// Connect output pad `output` of `from` to input pad `input` of `to`.
+    pub fn link<F: AsRef<Node>, T: AsRef<Node>>(
+        &mut self,
+        from: &F,
+        output: usize,
+        to: &T,
+        input: usize,
+    ) -> &mut Self {
+        self.links.push(Link {
+            from: from.as_ref().clone(),
+            output,
+            to: to.as_ref().clone(),
+            input,
+        });
+
+        self
+    }
+
+    pub fn build(self) -> Result<Graph, Error> {
+        let mut graph = Graph::new();
+        let mut names = HashSet::new();
+
+        for spec in &self.specs {
+            if !names.insert(&spec.name[..]) {
+                return Err(Error::InvalidData);
+            }
+
+            let filter = filter::find(&spec.filter).ok_or(Error::FilterNotFound)?;
+            graph.add(&filter, &spec.name, &spec.args)?;
+        }
+
+        let contexts = unsafe {
+            (0..(*graph.as_ptr()).nb_filters as usize)
+                .map(|i| *(*graph.as_ptr()).filters.add(i))
+                .collect::<Vec<_>>()
+        };
+
+        // Names are unique, so a node matching its spec by name is ours.
+        let lookup = |node: &Node| match self.specs.get(node.index) {
+            Some(spec) if spec.name == node.name => Ok(contexts[node.index]),
+            _ => Err(Error::InvalidData),
+        };
+
+        for link in &self.links {
+            let from = lookup(&link.from)?;
+            let to = lookup(&link.to)?;
+
+            unsafe {
+                if link.output >= (*from).nb_outputs as usize
+                    || link.input >= (*to).nb_inputs as usize
+                {
+                    return Err(Error::InvalidData);
+                }
+
+                let output = avfilter_pad_get_type((*from).output_pads, link.output as c_int);
+                let input = avfilter_pad_get_type((*to).input_pads, link.input as c_int);
+
+                if media::Type::from(output) != media::Type::from(input) {
+                    return Err(Error::InvalidData);
+                }
+
+                match avfilter_link(from, link.output as _, to, link.input as _) {
+                    0 => (),
+                    e => return Err(Error::from(e)),
+                }
+            }
+        }
+
+        unsafe {
+            for &context in &contexts {
+                let inputs = (0..(*context).nb_inputs as usize).map(|i| *(*context).inputs.add(i));
+                let outputs =
+                    (0..(*context).nb_outputs as usize).map(|i| *(*context).outputs.add(i));
+
+                if inputs.chain(outputs).any(|link| link.is_null()) {
+                    return Err(Error::InvalidData);
+                }
+            }
+        }
+
+        graph.validate()?;
+
+        Ok(graph)
+    }
+}
@@ -1,3 +1,8 @@
+pub mod builder;
+pub use self::builder::{
+    AudioSink, AudioSource, AudioSrcParams, Builder, Node, VideoSink, VideoSource, VideoSrcParams,
+};
+
 use std::ffi::{CStr, CString};
 use std::ptr;
 use std::str::from_utf8_unchecked;
@@ -13,6 +13,15 @@ pub use self::context::{Context, Sink, Source};
 pub mod graph;
 pub use self::graph::Graph;
 
+pub mod chain;
+pub use self::chain::Chain;
+
+#[cfg(feature = "codec")]
+pub mod audio;
+
+#[cfg(feature = "codec")]
+pub mod video;
+
 use std::ffi::{CStr, CString};
 use std::str::from_utf8_unchecked;
 
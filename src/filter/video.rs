@@ -0,0 +1,98 @@
+use super::{find, Chain, Graph};
+use codec::decoder;
+use ffi::*;
+use libc::c_int;
+use {frame, Error, Rational, Rescale};
+
+/// Frame rate of film restored from NTSC telecine.
+pub const FILM_RATE: Rational = Rational(24000, 1001);
+
+/// Build a `buffer -> fieldmatch -> yadif -> decimate -> buffersink` chain
+/// recovering progressive film frames from the hard telecined output of
+/// `decoder`, whose frames are timestamped in `time_base`. The output time
+/// base is set by `decimate`.
+///
+/// `fieldmatch` pairs fields back into their original frames, `yadif`
+/// deinterlaces the few left combed, such as at edits, and `decimate` drops
+/// the duplicate out of every five frames, turning 30000/1001 into
+/// 24000/1001.
+pub fn inverse_telecine(decoder: &decoder::Video, time_base: Rational) -> Result<Chain, Error> {
+    let mut graph = Graph::new();
+
+    let args = format!(
+        "video_size={}x{}:pix_fmt={}:time_base={}:pixel_aspect={}",
+        decoder.raw_width(),
+        decoder.raw_height(),
+        AVPixelFormat::from(decoder.format()) as c_int,
+        time_base,
+        decoder.aspect_ratio()
+    );
+
+    graph.add(&find("buffer").ok_or(Error::FilterNotFound)?, "in", &args)?;
+    graph.add(&find("buffersink").ok_or(Error::FilterNotFound)?, "out", "")?;
+
+    graph
+        .output("in", 0)?
+        .input("out", 0)?
+        .parse("fieldmatch=order=auto:combmatch=full,yadif=deint=interlaced,decimate")?;
+    graph.validate()?;
+
+    Ok(Chain::wrap(graph))
+}
+
+/// Removes soft telecine, where progressive film frames are encoded as is
+/// and flagged to repeat fields on display, as MPEG-2 DVDs do.
+///
+/// Decoded frames are already the film frames, only their timing follows
+/// the 3:2 pattern. `retime` restores a steady 24000/1001 cadence and clears
+/// the repeat flags.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct Pulldown {
+    origin: Option<i64>,
+    frames: i64,
+    repeated: u64,
+}
+
+impl Pulldown {
+    pub fn new() -> Self {
+        Pulldown::default()
+    }
+
+    /// Time base of retimed frames, in which each lasts 1001.
+    pub fn time_base() -> Rational {
+        Rational(1, FILM_RATE.numerator())
+    }
+
+    /// Give `frame`, timestamped in `time_base`, the next timestamp in
+    /// `Pulldown::time_base()`, counting from the first frame.
+    pub fn retime(&mut self, frame: &mut frame::Video, time_base: Rational) {
+        if frame.repeated_fields() > 0 {
+            self.repeated += 1;
+        }
+
+        let origin = match self.origin {
+            Some(origin) => origin,
+            None => {
+                let origin = frame
+                    .timestamp()
+                    .map_or(0, |ts| ts.rescale(time_base, Pulldown::time_base()));
+
+                self.origin = Some(origin);
+                origin
+            }
+        };
+
+        frame.set_pts(Some(
+            origin + self.frames * i64::from(FILM_RATE.denominator()),
+        ));
+        frame.set_repeated_fields(0);
+
+        self.frames += 1;
+    }
+
+    /// Whether the frames retimed so far follow the 3:2 pattern, in which
+    /// every other frame repeats a field.
+    pub fn is_telecined(&self) -> bool {
+        self.frames >= 10 && self.repeated * 5 >= self.frames as u64 * 2
+    }
+}
@@ -0,0 +1,123 @@
+//! Demuxing from async code, behind the `async` feature.
+//!
+//! FFmpeg I/O is blocking, so demuxers run on a dedicated thread and hand
+//! packets over through a bounded channel, keeping network sources such as
+//! RTMP or HLS from stalling the runtime. Dropping the `Demuxer` interrupts
+//! blocked reads of inputs opened with `input`.
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::task::{self, Poll};
+use std::thread;
+use std::time::Duration;
+
+use futures_core::Stream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use super::context::Input;
+use libc::{EAGAIN, EIO};
+use {Error, Packet};
+
+/// Packets of an `Input` read on a worker thread, as an async stream of
+/// stream index and packet pairs.
+///
+/// The stream ends after the last packet or the first read error. Stream
+/// information must be taken from the `Input` before handing it over.
+pub struct Demuxer {
+    receiver: mpsc::Receiver<Result<(usize, Packet), Error>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Demuxer {
+    /// Read `input` on a new thread, buffering up to `capacity` packets
+    /// ahead of the consumer.
+    ///
+    /// Without an interrupt callback on `input`, a read blocked on the
+    /// network is only abandoned once it returns.
+    pub fn new(input: Input, capacity: usize) -> Self {
+        Demuxer::spawn(input, capacity, Arc::new(AtomicBool::new(false)))
+    }
+
+    fn spawn(mut input: Input, capacity: usize, cancel: Arc<AtomicBool>) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let stop = cancel.clone();
+
+        thread::spawn(move || loop {
+            if stop.load(Ordering::Relaxed) {
+                break;
+            }
+
+            let mut packet = Packet::empty();
+
+            let item = match packet.read(&mut input) {
+                Ok(()) => Ok((packet.stream(), packet)),
+                Err(Error::Eof) => break,
+                Err(Error::Other { errno: EAGAIN }) => {
+                    thread::sleep(Duration::from_millis(10));
+                    continue;
+                }
+                Err(e) => Err(e),
+            };
+
+            let last = item.is_err();
+
+            if sender.blocking_send(item).is_err() || last {
+                break;
+            }
+        });
+
+        Demuxer { receiver, cancel }
+    }
+}
+
+impl Stream for Demuxer {
+    type Item = Result<(usize, Packet), Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl Drop for Demuxer {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Open the input at `path` on the blocking thread pool of the runtime, with
+/// an interrupt callback so that dropping the resulting `Demuxer` aborts
+/// pending reads.
+pub fn input<P: Into<PathBuf>>(path: P, capacity: usize) -> Open {
+    let path = path.into();
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    let handle = tokio::task::spawn_blocking(move || {
+        let flag = cancel.clone();
+        let input = super::input_with_interrupt(&path, move || flag.load(Ordering::Relaxed))?;
+
+        Ok(Demuxer::spawn(input, capacity, cancel))
+    });
+
+    Open { handle }
+}
+
+/// The future returned by `input`.
+pub struct Open {
+    handle: JoinHandle<Result<Demuxer, Error>>,
+}
+
+impl Future for Open {
+    type Output = Result<Demuxer, Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Self::Output> {
+        match Pin::new(&mut self.handle).poll(cx) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(Ok(result)) => Poll::Ready(result),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(Error::Other { errno: EIO })),
+        }
+    }
+}
@@ -1,16 +1,18 @@
 use std::fmt;
 use std::mem;
 use std::ptr;
-use std::rc::Rc;
+use std::sync::Arc;
 
 use super::destructor::{self, Destructor};
 use ffi::*;
+use format::io::Io;
 use libc::{c_int, c_uint};
-use {media, Chapter, ChapterMut, DictionaryRef, Stream, StreamMut};
+use util::interrupt;
+use {media, Chapter, ChapterMut, Codec, DictionaryRef, Error, Stream, StreamMut};
 
 pub struct Context {
     ptr: *mut AVFormatContext,
-    dtor: Rc<Destructor>,
+    dtor: Arc<Destructor>,
 }
 
 unsafe impl Send for Context {}
@@ -19,7 +21,14 @@ impl Context {
     pub unsafe fn wrap(ptr: *mut AVFormatContext, mode: destructor::Mode) -> Self {
         Context {
             ptr,
-            dtor: Rc::new(Destructor::new(ptr, mode)),
+            dtor: Arc::new(Destructor::new(ptr, mode)),
+        }
+    }
+
+    pub unsafe fn wrap_with_io(ptr: *mut AVFormatContext, mode: destructor::Mode, io: Io) -> Self {
+        Context {
+            ptr,
+            dtor: Arc::new(Destructor::with_io(ptr, mode, io)),
         }
     }
 
@@ -31,12 +40,50 @@ impl Context {
         self.ptr
     }
 
-    pub unsafe fn destructor(&self) -> Rc<Destructor> {
-        Rc::clone(&self.dtor)
+    pub unsafe fn destructor(&self) -> Arc<Destructor> {
+        Arc::clone(&self.dtor)
+    }
+
+    pub unsafe fn keep_interrupt(&mut self, mut closure: destructor::Interrupt) {
+        (*self.ptr).interrupt_callback = interrupt::borrowed(&mut *closure).interrupt;
+        self.dtor.keep_interrupt(closure);
     }
 }
 
 impl Context {
+    /// Install `closure` as the interrupt callback, polled by blocking I/O
+    /// such as reads on a stalled network stream, which fail with
+    /// `Error::Exit` as soon as it returns `true`.
+    ///
+    /// The closure is invoked on the thread doing the I/O, so aborting from
+    /// another one goes through shared state, such as a `cancel::Token`.
+    ///
+    /// Only I/O opened after the call sees the closure: FFmpeg copies the
+    /// callback into every I/O context it opens, so the main one of an
+    /// already opened input or output keeps polling the previous callback.
+    /// Prefer `input_with_interrupt` and `output_with_interrupt`, which set it
+    /// before opening. Every closure installed stays alive until the context
+    /// is closed.
+    pub fn set_interrupt<F>(&mut self, closure: F)
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        unsafe {
+            self.keep_interrupt(Box::new(Box::new(closure)));
+        }
+    }
+
+    /// Remove the interrupt callback for I/O opened from now on. As with
+    /// `set_interrupt`, I/O already opened keeps polling the previous one.
+    pub fn clear_interrupt(&mut self) {
+        unsafe {
+            (*self.ptr).interrupt_callback = AVIOInterruptCB {
+                callback: None,
+                opaque: ptr::null_mut(),
+            };
+        }
+    }
+
     #[inline]
     pub fn nb_streams(&self) -> u32 {
         unsafe { (*self.as_ptr()).nb_streams }
@@ -80,6 +127,10 @@ impl Context {
         unsafe { (*self.as_ptr()).bit_rate }
     }
 
+    pub fn start_time(&self) -> i64 {
+        unsafe { (*self.as_ptr()).start_time }
+    }
+
     pub fn duration(&self) -> i64 {
         unsafe { (*self.as_ptr()).duration }
     }
@@ -183,6 +234,35 @@ impl<'a> Best<'a> {
             }
         }
     }
+
+    /// Like `best`, also returning the decoder for the stream, and telling
+    /// a missing stream (`StreamNotFound`) from one that cannot be decoded
+    /// (`DecoderNotFound`).
+    pub fn best_with_decoder<'b>(self, kind: media::Type) -> Result<(Stream<'b>, Codec), Error>
+    where
+        'a: 'b,
+    {
+        unsafe {
+            let mut decoder = ptr::null_mut();
+            let index = av_find_best_stream(
+                self.context.ptr,
+                kind.into(),
+                self.wanted as c_int,
+                self.related as c_int,
+                &mut decoder,
+                0,
+            );
+
+            if index < 0 {
+                Err(Error::from(index))
+            } else {
+                Ok((
+                    Stream::wrap(self.context, index as usize),
+                    Codec::wrap(decoder as *mut _),
+                ))
+            }
+        }
+    }
 }
 
 pub struct StreamIter<'a> {
@@ -222,6 +302,13 @@ impl<'a> StreamIter<'a> {
     {
         unsafe { Best::new(self.context).best(kind) }
     }
+
+    pub fn best_with_decoder<'b>(&self, kind: media::Type) -> Result<(Stream<'b>, Codec), Error>
+    where
+        'a: 'b,
+    {
+        unsafe { Best::new(self.context).best_with_decoder(kind) }
+    }
 }
 
 impl<'a> Iterator for StreamIter<'a> {
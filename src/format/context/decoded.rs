@@ -0,0 +1,154 @@
+use std::marker::PhantomData;
+
+use super::Input;
+use cancel::Token;
+use format::side_data::Policy;
+use libc::EAGAIN;
+use {codec, decoder, frame, media, Error, Frame, Packet};
+
+/// Frames of one stream of an `Input`, decoded as its packets are read.
+///
+/// Packets of other streams are skipped, and the decoder is drained once the
+/// input ends. Decoding errors are yielded without ending the iteration, so
+/// callers can choose to skip corrupt packets, while read errors end it.
+pub struct DecodedFrames<'a, F> {
+    input: &'a mut Input,
+    stream: usize,
+    decoder: decoder::Opened,
+    packet: Packet,
+    token: Option<Token>,
+    policy: Option<&'a Policy>,
+    done: bool,
+
+    _marker: PhantomData<F>,
+}
+
+impl<'a, F: From<Frame>> DecodedFrames<'a, F> {
+    fn new(input: &'a mut Input, stream: usize, medium: media::Type) -> Result<Self, Error> {
+        let parameters = match input.stream(stream) {
+            Some(stream) => stream.parameters(),
+            None => return Err(Error::StreamNotFound),
+        };
+
+        if parameters.medium() != medium {
+            return Err(Error::InvalidData);
+        }
+
+        let mut context = codec::Context::new();
+        context.set_parameters(parameters)?;
+
+        Ok(DecodedFrames {
+            input,
+            stream,
+            decoder: context.decoder().open()?,
+            packet: Packet::empty(),
+            token: None,
+            policy: None,
+            done: false,
+
+            _marker: PhantomData,
+        })
+    }
+
+    /// Check `token` before reading each packet, ending the iteration with
+    /// `Error::Exit` once it is cancelled.
+    pub fn cancellable(mut self, token: Token) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Run the side data of every decoded frame through `policy`.
+    pub fn side_data(mut self, policy: &'a Policy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    pub fn stream(&self) -> usize {
+        self.stream
+    }
+
+    pub fn decoder(&self) -> &decoder::Opened {
+        &self.decoder
+    }
+
+    pub fn decoder_mut(&mut self) -> &mut decoder::Opened {
+        &mut self.decoder
+    }
+}
+
+impl<'a, F: From<Frame>> Iterator for DecodedFrames<'a, F> {
+    type Item = Result<F, Error>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        while !self.done {
+            let mut frame = unsafe { Frame::empty() };
+
+            match self.decoder.receive_frame(&mut frame) {
+                Ok(()) => {
+                    if let Some(policy) = self.policy {
+                        if let Err(e) = policy.frames(&mut frame) {
+                            return Some(Err(e));
+                        }
+                    }
+
+                    return Some(Ok(F::from(frame)));
+                }
+
+                Err(Error::Other { errno: EAGAIN }) => (),
+
+                Err(Error::Eof) => {
+                    self.done = true;
+                    return None;
+                }
+
+                Err(e) => return Some(Err(e)),
+            }
+
+            loop {
+                if let Some(Err(e)) = self.token.as_ref().map(Token::check) {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+
+                match self.packet.read(self.input) {
+                    Ok(()) if self.packet.stream() == self.stream => {
+                        match self.decoder.send_packet(&self.packet) {
+                            Ok(()) => break,
+                            Err(e) => return Some(Err(e)),
+                        }
+                    }
+
+                    Ok(()) => (),
+
+                    Err(Error::Eof) => {
+                        if let Err(e) = self.decoder.send_eof() {
+                            self.done = true;
+                            return Some(Err(e));
+                        }
+
+                        break;
+                    }
+
+                    Err(e) => {
+                        self.done = true;
+                        return Some(Err(e));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Input {
+    /// Decode the video frames of `stream` with its default decoder.
+    pub fn decoded_video(&mut self, stream: usize) -> Result<DecodedFrames<frame::Video>, Error> {
+        DecodedFrames::new(self, stream, media::Type::Video)
+    }
+
+    /// Decode the audio frames of `stream` with its default decoder.
+    pub fn decoded_audio(&mut self, stream: usize) -> Result<DecodedFrames<frame::Audio>, Error> {
+        DecodedFrames::new(self, stream, media::Type::Audio)
+    }
+}
@@ -1,4 +1,10 @@
+use std::sync::Mutex;
+
 use ffi::*;
+use format::io::Io;
+
+// Boxed twice so the interrupt callback gets a thin pointer to it.
+pub type Interrupt = Box<Box<dyn FnMut() -> bool + Send>>;
 
 #[derive(Copy, Clone, Debug)]
 pub enum Mode {
@@ -9,11 +15,52 @@ pub enum Mode {
 pub struct Destructor {
     ptr: *mut AVFormatContext,
     mode: Mode,
+
+    io: Option<Io>,
+    interrupts: Mutex<Vec<Interrupt>>,
+    control: Mutex<Option<Box<dyn Send>>>,
 }
 
+// The format context is only touched once, when the last reference drops.
+unsafe impl Send for Destructor {}
+unsafe impl Sync for Destructor {}
+
 impl Destructor {
     pub unsafe fn new(ptr: *mut AVFormatContext, mode: Mode) -> Self {
-        Destructor { ptr, mode }
+        Destructor {
+            ptr,
+            mode,
+            io: None,
+            interrupts: Mutex::new(Vec::new()),
+            control: Mutex::new(None),
+        }
+    }
+
+    /// Also take ownership of the custom I/O context, freed once the format
+    /// context is closed.
+    pub unsafe fn with_io(ptr: *mut AVFormatContext, mode: Mode, io: Io) -> Self {
+        Destructor {
+            ptr,
+            mode,
+            io: Some(io),
+            interrupts: Mutex::new(Vec::new()),
+            control: Mutex::new(None),
+        }
+    }
+
+    /// Keep the closure of an interrupt callback alive until the format
+    /// context is closed.
+    ///
+    /// Replaced closures are kept as well, since I/O contexts opened while
+    /// they were installed hold their own copy of the callback.
+    pub unsafe fn keep_interrupt(&self, interrupt: Interrupt) {
+        self.interrupts.lock().unwrap().push(interrupt);
+    }
+
+    /// Keep whatever `opaque` points to for the control message callback
+    /// alive until the format context is closed.
+    pub unsafe fn set_control(&self, control: Option<Box<dyn Send>>) {
+        *self.control.lock().unwrap() = control;
     }
 }
 
@@ -24,7 +71,12 @@ impl Drop for Destructor {
                 Mode::Input => avformat_close_input(&mut self.ptr),
 
                 Mode::Output => {
-                    avio_close((*self.ptr).pb);
+                    if self.io.is_none() {
+                        avio_close((*self.ptr).pb);
+                    } else {
+                        avio_flush((*self.ptr).pb);
+                    }
+
                     avformat_free_context(self.ptr);
                 }
             }
@@ -1,12 +1,19 @@
 use std::ffi::CString;
 use std::mem;
 use std::ops::{Deref, DerefMut};
+use std::time::Duration;
 
-use super::common::Context;
+use super::common::{Best, Context};
 use super::destructor;
+use super::seek;
 use ffi::*;
+use format::io::Io;
+use format::preview::PreviewIter;
+use libc::{c_int, EAGAIN};
+use packet::{PacketPool, PooledPacket};
+use rescale::TIME_BASE;
 use util::range::Range;
-use {format, Codec, Error, Packet, Stream};
+use {codec, decoder, format, media, Codec, Error, Frame, Packet, Rational, Rescale, Stream};
 
 pub struct Input {
     ptr: *mut AVFormatContext,
@@ -23,6 +30,13 @@ impl Input {
         }
     }
 
+    pub unsafe fn wrap_with_io(ptr: *mut AVFormatContext, io: Io) -> Self {
+        Input {
+            ptr,
+            ctx: Context::wrap_with_io(ptr, destructor::Mode::Input, io),
+        }
+    }
+
     pub unsafe fn as_ptr(&self) -> *const AVFormatContext {
         self.ptr as *const _
     }
@@ -85,6 +99,26 @@ impl Input {
         }
     }
 
+    /// The stream of the given type players would pick, along with its
+    /// decoder, favoring streams flagged as default and with more
+    /// information, such as higher resolutions or more channels.
+    pub fn best_stream(&self, kind: media::Type) -> Result<(Stream, Codec), Error> {
+        self.streams().best_with_decoder(kind)
+    }
+
+    /// Like `best_stream`, but prefer streams in the same program as the
+    /// stream at index `related`, such as the audio going with a video.
+    pub fn best_stream_with_related(
+        &self,
+        kind: media::Type,
+        related: usize,
+    ) -> Result<(Stream, Codec), Error> {
+        match self.stream(related) {
+            Some(stream) => unsafe { Best::new(self).related(&stream).best_with_decoder(kind) },
+            None => Err(Error::StreamNotFound),
+        }
+    }
+
     pub fn probe_score(&self) -> i32 {
         unsafe { av_format_get_probe_score(self.as_ptr()) }
     }
@@ -93,6 +127,23 @@ impl Input {
         PacketIter::new(self)
     }
 
+    /// Embedded pictures, such as cover art and thumbnail tracks, which can
+    /// be decoded without reading the main streams.
+    pub fn previews(&self) -> PreviewIter {
+        PreviewIter::new(self)
+    }
+
+    /// Like `packets()`, but reporting read errors and describing the stream
+    /// of each packet by value, so that both can be sent to other threads.
+    pub fn packets_owned(&mut self) -> OwnedPacketIter {
+        OwnedPacketIter::new(self)
+    }
+
+    /// Like `packets()`, reading into packets recycled through `pool`.
+    pub fn packets_pooled<'a>(&'a mut self, pool: &PacketPool) -> PooledPacketIter<'a> {
+        PooledPacketIter::new(self, pool)
+    }
+
     pub fn pause(&mut self) -> Result<(), Error> {
         unsafe {
             match av_read_pause(self.as_mut_ptr()) {
@@ -111,21 +162,188 @@ impl Input {
         }
     }
 
+    /// Seek to `ts`, in `AV_TIME_BASE` units, landing on a keyframe within
+    /// `range`. Same as `seek_range` with no stream and no flags.
     pub fn seek<R: Range<i64>>(&mut self, ts: i64, range: R) -> Result<(), Error> {
+        self.seek_range(None, ts, range, seek::Flags::empty())
+    }
+
+    /// Seek to `ts`, landing within `range`, as close to `ts` as possible.
+    ///
+    /// Timestamps are in the time base of `stream`, or in `AV_TIME_BASE`
+    /// units without one, unless `flags` makes them byte positions or frame
+    /// numbers.
+    pub fn seek_range<R: Range<i64>>(
+        &mut self,
+        stream: Option<usize>,
+        ts: i64,
+        range: R,
+        flags: seek::Flags,
+    ) -> Result<(), Error> {
+        let index = self.seek_index(stream)?;
+
         unsafe {
             match avformat_seek_file(
                 self.as_mut_ptr(),
-                -1,
+                index,
                 range.start().cloned().unwrap_or(i64::min_value()),
                 ts,
                 range.end().cloned().unwrap_or(i64::max_value()),
-                0,
+                flags.bits(),
             ) {
                 s if s >= 0 => Ok(()),
                 e => Err(Error::from(e)),
             }
         }
     }
+
+    /// Seek to the keyframe following `ts`, in `AV_TIME_BASE` units, or the
+    /// one preceding it with `seek::Flags::BACKWARD`.
+    pub fn seek_frame(&mut self, ts: i64, flags: seek::Flags) -> Result<(), Error> {
+        self.seek_timestamp(None, ts, flags)
+    }
+
+    /// Like `seek_frame`, with `ts` in the time base of `stream`.
+    pub fn seek_stream(&mut self, stream: usize, ts: i64, flags: seek::Flags) -> Result<(), Error> {
+        self.seek_timestamp(Some(stream), ts, flags)
+    }
+
+    fn seek_timestamp(
+        &mut self,
+        stream: Option<usize>,
+        ts: i64,
+        flags: seek::Flags,
+    ) -> Result<(), Error> {
+        let index = self.seek_index(stream)?;
+
+        unsafe {
+            match av_seek_frame(self.as_mut_ptr(), index, ts, flags.bits()) {
+                s if s >= 0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    fn seek_index(&self, stream: Option<usize>) -> Result<c_int, Error> {
+        match stream {
+            Some(index) if index < self.nb_streams() as usize => Ok(index as c_int),
+            Some(_) => Err(Error::StreamNotFound),
+            None => Ok(-1),
+        }
+    }
+
+    /// Seek `stream` to frame `number`, counted from its start at its average
+    /// frame rate, and decode that frame with `decoder` into `frame`.
+    ///
+    /// `decoder` must be open for `stream`. The demuxer lands on the previous
+    /// keyframe, and the frames leading up to the target are decoded and
+    /// discarded. Reading can continue from there as usual.
+    pub fn seek_to_frame(
+        &mut self,
+        stream: usize,
+        number: i64,
+        decoder: &mut decoder::Opened,
+        frame: &mut Frame,
+    ) -> Result<(), Error> {
+        let rate = match self.stream(stream) {
+            Some(stream) => stream.raw_avg_frame_rate(),
+            None => return Err(Error::StreamNotFound),
+        };
+
+        if rate.numerator() <= 0 || rate.denominator() <= 0 {
+            return Err(Error::InvalidData);
+        }
+
+        let offset = number.rescale(rate.invert(), TIME_BASE);
+        self.seek_exact(stream, offset, decoder, frame)
+    }
+
+    /// Seek `stream` to `time` from its start and decode the frame presented
+    /// at that time, as `seek_to_frame` does.
+    pub fn seek_to_time(
+        &mut self,
+        stream: usize,
+        time: Duration,
+        decoder: &mut decoder::Opened,
+        frame: &mut Frame,
+    ) -> Result<(), Error> {
+        let offset = time.as_secs() as i64 * 1_000_000 + i64::from(time.subsec_micros());
+        self.seek_exact(stream, offset, decoder, frame)
+    }
+
+    fn seek_exact(
+        &mut self,
+        index: usize,
+        offset: i64,
+        decoder: &mut decoder::Opened,
+        frame: &mut Frame,
+    ) -> Result<(), Error> {
+        let (time_base, start) = match self.stream(index) {
+            Some(stream) => (stream.time_base(), stream.start_time()),
+            None => return Err(Error::StreamNotFound),
+        };
+
+        let target = match start {
+            AV_NOPTS_VALUE => 0,
+            start => start,
+        } + offset.rescale(TIME_BASE, time_base);
+
+        unsafe {
+            match avformat_seek_file(
+                self.as_mut_ptr(),
+                index as c_int,
+                i64::min_value(),
+                target,
+                target,
+                0,
+            ) {
+                s if s >= 0 => (),
+                e => return Err(Error::from(e)),
+            }
+        }
+
+        decoder.flush();
+
+        let mut packet = Packet::empty();
+
+        loop {
+            match decoder.receive_frame(frame) {
+                Ok(()) => {
+                    let reached = match frame.timestamp() {
+                        Some(ts) => ts >= target || ts + frame.packet().duration > target,
+                        None => true,
+                    };
+
+                    if reached {
+                        return Ok(());
+                    }
+
+                    continue;
+                }
+
+                Err(Error::Other { errno: EAGAIN }) => (),
+                Err(e) => return Err(e),
+            }
+
+            loop {
+                match packet.read(self) {
+                    Ok(()) if packet.stream() == index => {
+                        decoder.send_packet(&packet)?;
+                        break;
+                    }
+
+                    Ok(()) => (),
+
+                    Err(Error::Eof) => {
+                        decoder.send_eof()?;
+                        break;
+                    }
+
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
 }
 
 impl Deref for Input {
@@ -175,6 +393,116 @@ impl<'a> Iterator for PacketIter<'a> {
     }
 }
 
+/// What a packet needs to be interpreted without its `Input`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct StreamInfo {
+    pub index: usize,
+    pub time_base: Rational,
+    pub medium: media::Type,
+    pub id: codec::Id,
+}
+
+impl<'a> From<Stream<'a>> for StreamInfo {
+    fn from(stream: Stream) -> Self {
+        let parameters = stream.parameters();
+
+        StreamInfo {
+            index: stream.index(),
+            time_base: stream.time_base(),
+            medium: parameters.medium(),
+            id: parameters.id(),
+        }
+    }
+}
+
+/// Iterator over owned packets, ending at the end of the input.
+///
+/// Read errors other than `Error::Eof` are yielded as they occur and the
+/// iterator can be resumed after them, `Error::Other { errno: EAGAIN }`
+/// meaning no packet is available yet from a non-blocking input.
+pub struct OwnedPacketIter<'a> {
+    context: &'a mut Input,
+    streams: Vec<StreamInfo>,
+}
+
+impl<'a> OwnedPacketIter<'a> {
+    pub fn new(context: &mut Input) -> OwnedPacketIter {
+        OwnedPacketIter {
+            context,
+            streams: Vec::new(),
+        }
+    }
+
+    fn stream(&mut self, index: usize) -> Result<StreamInfo, Error> {
+        // Streams can be added while reading, as with MPEG-TS, refresh then.
+        if index >= self.streams.len() {
+            self.streams = self.context.streams().map(StreamInfo::from).collect();
+        }
+
+        self.streams
+            .get(index)
+            .cloned()
+            .ok_or(Error::StreamNotFound)
+    }
+}
+
+impl<'a> Iterator for OwnedPacketIter<'a> {
+    type Item = Result<(StreamInfo, Packet), Error>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        let mut packet = Packet::empty();
+
+        match packet.read(self.context) {
+            Ok(..) => (),
+            Err(Error::Eof) => return None,
+            Err(e) => return Some(Err(e)),
+        }
+
+        if let Err(e) = packet.make_refcounted() {
+            return Some(Err(e));
+        }
+
+        Some(self.stream(packet.stream()).map(|stream| (stream, packet)))
+    }
+}
+
+pub struct PooledPacketIter<'a> {
+    context: &'a mut Input,
+    pool: PacketPool,
+}
+
+impl<'a> PooledPacketIter<'a> {
+    pub fn new(context: &'a mut Input, pool: &PacketPool) -> Self {
+        PooledPacketIter {
+            context,
+            pool: pool.clone(),
+        }
+    }
+}
+
+impl<'a> Iterator for PooledPacketIter<'a> {
+    type Item = (Stream<'a>, PooledPacket);
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        let mut packet = self.pool.get();
+
+        loop {
+            match packet.read(self.context) {
+                Ok(..) => unsafe {
+                    return Some((
+                        Stream::wrap(mem::transmute_copy(&self.context), packet.stream()),
+                        packet,
+                    ));
+                },
+
+                Err(Error::Eof) => return None,
+
+                Err(..) => (),
+            }
+        }
+    }
+}
+
 pub fn dump(ctx: &Input, index: i32, url: Option<&str>) {
     let url = url.map(|u| CString::new(u).unwrap());
 
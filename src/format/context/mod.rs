@@ -2,10 +2,15 @@ pub mod destructor;
 pub use self::destructor::Destructor;
 
 pub mod input;
-pub use self::input::Input;
+pub use self::input::{Input, StreamInfo};
+
+pub mod seek;
 
 pub mod output;
-pub use self::output::Output;
+pub use self::output::{AvoidNegativeTs, Output};
+
+pub mod decoded;
+pub use self::decoded::DecodedFrames;
 
 #[doc(hidden)]
 pub mod common;
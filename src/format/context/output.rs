@@ -1,5 +1,5 @@
 use std::ffi::CString;
-use std::mem::size_of;
+use std::mem::{self, size_of};
 use std::ops::{Deref, DerefMut};
 use std::ptr;
 
@@ -9,7 +9,34 @@ use super::common::Context;
 use super::destructor;
 use codec::traits;
 use ffi::*;
-use {format, ChapterMut, Dictionary, Error, Rational, StreamMut};
+use format::io::Io;
+use format::options::OutputOptions;
+use {format, ChapterMut, Dictionary, Error, Frame, Packet, Rational, StreamMut};
+
+/// How a muxer shifts timestamps to avoid negative ones, such as the DTS of
+/// video with B-frames.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum AvoidNegativeTs {
+    /// Shift only when the muxer cannot store negative timestamps.
+    Auto,
+    Disabled,
+    /// Shift so that timestamps are non-negative.
+    MakeNonNegative,
+    /// Shift so that the first timestamp is zero.
+    MakeZero,
+}
+
+impl From<AvoidNegativeTs> for libc::c_int {
+    fn from(value: AvoidNegativeTs) -> libc::c_int {
+        match value {
+            AvoidNegativeTs::Auto => AVFMT_AVOID_NEG_TS_AUTO as libc::c_int,
+            // No define before FFmpeg 5.1, the option table uses 0.
+            AvoidNegativeTs::Disabled => 0,
+            AvoidNegativeTs::MakeNonNegative => AVFMT_AVOID_NEG_TS_MAKE_NON_NEGATIVE as libc::c_int,
+            AvoidNegativeTs::MakeZero => AVFMT_AVOID_NEG_TS_MAKE_ZERO as libc::c_int,
+        }
+    }
+}
 
 pub struct Output {
     ptr: *mut AVFormatContext,
@@ -26,6 +53,13 @@ impl Output {
         }
     }
 
+    pub unsafe fn wrap_with_io(ptr: *mut AVFormatContext, io: Io) -> Self {
+        Output {
+            ptr,
+            ctx: Context::wrap_with_io(ptr, destructor::Mode::Output, io),
+        }
+    }
+
     pub unsafe fn as_ptr(&self) -> *const AVFormatContext {
         self.ptr as *const _
     }
@@ -61,6 +95,15 @@ impl Output {
         }
     }
 
+    /// Write the header with typed muxer options, returning the ones the
+    /// muxer did not recognize.
+    pub fn write_header_with_options(
+        &mut self,
+        options: &OutputOptions,
+    ) -> Result<Dictionary, Error> {
+        self.write_header_with(options.to_dictionary())
+    }
+
     pub fn write_trailer(&mut self) -> Result<(), Error> {
         unsafe {
             match av_write_trailer(self.as_mut_ptr()) {
@@ -70,6 +113,64 @@ impl Output {
         }
     }
 
+    /// Whether the muxer takes raw frames on `stream` through
+    /// `write_uncoded_frame`, as device outputs such as `alsa` or `xv` do.
+    pub fn accepts_uncoded_frames(&mut self, stream: usize) -> bool {
+        unsafe { av_write_uncoded_frame_query(self.as_mut_ptr(), stream as libc::c_int) >= 0 }
+    }
+
+    /// Write a raw frame to `stream` without going through an encoder, its
+    /// timestamps being in the stream time base. The muxer gets a new
+    /// reference to `frame`.
+    pub fn write_uncoded_frame(&mut self, stream: usize, frame: &Frame) -> Result<(), Error> {
+        unsafe {
+            let ptr = uncoded(frame)?;
+
+            match av_write_uncoded_frame(self.as_mut_ptr(), stream as libc::c_int, ptr) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Like `write_uncoded_frame`, but let the muxer buffer the frame to
+    /// interleave it with the other streams by timestamp.
+    pub fn write_uncoded_frame_interleaved(
+        &mut self,
+        stream: usize,
+        frame: &Frame,
+    ) -> Result<(), Error> {
+        unsafe {
+            let ptr = uncoded(frame)?;
+
+            match av_interleaved_write_uncoded_frame(self.as_mut_ptr(), stream as libc::c_int, ptr)
+            {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Rescale the timestamps of `packet` from `time_base`, the one of the
+    /// encoder or input stream it comes from, to the time base of its output
+    /// stream, then write it interleaved.
+    ///
+    /// Fails with `Error::StreamNotFound` when the packet stream index is
+    /// not a stream of this output.
+    pub fn write_packet<R: Into<Rational>>(
+        &mut self,
+        packet: &mut Packet,
+        time_base: R,
+    ) -> Result<(), Error> {
+        let destination = match self.stream(packet.stream()) {
+            Some(stream) => stream.time_base(),
+            None => return Err(Error::StreamNotFound),
+        };
+
+        packet.rescale_ts(time_base, destination);
+        packet.write_interleaved(self)
+    }
+
     pub fn add_stream<E: traits::Encoder>(&mut self, codec: E) -> Result<StreamMut, Error> {
         unsafe {
             let codec = codec.encoder();
@@ -86,6 +187,11 @@ impl Output {
         }
     }
 
+    /// Add a chapter spanning `[start, end]` in `time_base`, or update the
+    /// chapter with the same `id`.
+    ///
+    /// Chapters must be added before `write_header` for Matroska, while MP4
+    /// accepts them until `write_trailer`.
     pub fn add_chapter<R: Into<Rational>, S: AsRef<str>>(
         &mut self,
         id: i32,
@@ -148,9 +254,56 @@ impl Output {
 
     pub fn set_metadata(&mut self, dictionary: Dictionary) {
         unsafe {
+            let dictionary = dictionary.disown();
+            av_dict_free(&mut (*self.as_mut_ptr()).metadata);
+            (*self.as_mut_ptr()).metadata = dictionary;
+        }
+    }
+
+    /// Set a single container metadata entry, keeping the others.
+    pub fn add_metadata<K: AsRef<str>, V: AsRef<str>>(&mut self, key: K, value: V) {
+        unsafe {
+            let mut dictionary = Dictionary::own((*self.as_mut_ptr()).metadata);
+            dictionary.set(key.as_ref(), value.as_ref());
             (*self.as_mut_ptr()).metadata = dictionary.disown();
         }
     }
+
+    /// Toggle `AVFMT_FLAG_BITEXACT`, making muxers omit version strings and
+    /// other non-deterministic header fields.
+    pub fn set_bitexact(&mut self, value: bool) {
+        unsafe {
+            if value {
+                (*self.as_mut_ptr()).flags |= AVFMT_FLAG_BITEXACT as libc::c_int;
+            } else {
+                (*self.as_mut_ptr()).flags &= !(AVFMT_FLAG_BITEXACT as libc::c_int);
+            }
+        }
+    }
+
+    pub fn set_avoid_negative_ts(&mut self, value: AvoidNegativeTs) {
+        unsafe {
+            (*self.as_mut_ptr()).avoid_negative_ts = value.into();
+        }
+    }
+
+    /// Offset every timestamp of every stream by `value`, in `AV_TIME_BASE`
+    /// units, such as the muxing delay of a B-frame encoder to keep DTS
+    /// non-negative while the streams stay in sync.
+    pub fn set_output_ts_offset(&mut self, value: i64) {
+        unsafe {
+            (*self.as_mut_ptr()).output_ts_offset = value;
+        }
+    }
+}
+
+// A new reference to `frame`, which the muxer takes ownership of.
+unsafe fn uncoded(frame: &Frame) -> Result<*mut AVFrame, Error> {
+    let mut frame = frame.clone_shallow()?;
+    let ptr = frame.as_mut_ptr();
+    mem::forget(frame);
+
+    Ok(ptr)
 }
 
 impl Deref for Output {
@@ -0,0 +1,18 @@
+use ffi::*;
+use libc::c_int;
+
+bitflags! {
+    /// How a seek target is interpreted and where it may land.
+    pub struct Flags: c_int {
+        /// Land on the last keyframe at or before the target rather than
+        /// the next one. Only meaningful to `Input::seek_frame` and
+        /// `Input::seek_stream`, the range deciding the direction otherwise.
+        const BACKWARD = AVSEEK_FLAG_BACKWARD;
+        /// The target is a byte position in the file.
+        const BYTE     = AVSEEK_FLAG_BYTE;
+        /// Land on any frame, not only keyframes.
+        const ANY      = AVSEEK_FLAG_ANY;
+        /// The target is a frame number, where the demuxer supports it.
+        const FRAME    = AVSEEK_FLAG_FRAME;
+    }
+}
@@ -0,0 +1,228 @@
+//! Typed private options of demuxers that cannot be probed, or need options
+//! to make sense of their input, for `open`.
+//!
+//! Only the fields that are set end up in the options, leaving the rest to
+//! the demuxer defaults.
+
+use std::ffi::CString;
+use std::path::Path;
+use std::ptr;
+
+use super::{context, from_path};
+use ffi::*;
+use util::format::Pixel;
+use {Dictionary, Error, Rational};
+
+/// Private options of a specific demuxer.
+pub trait Demuxer {
+    /// Name of the demuxer the options belong to, which `open` forces.
+    fn format(&self) -> &'static str;
+
+    fn to_dictionary(&self) -> Dictionary<'static>;
+}
+
+/// Options of `rawvideo`, which has no header to learn the frame layout from.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct RawVideo {
+    pub format: Pixel,
+    pub width: u32,
+    pub height: u32,
+
+    /// 25 by default.
+    pub frame_rate: Option<Rational>,
+}
+
+impl RawVideo {
+    pub fn new(format: Pixel, width: u32, height: u32) -> Self {
+        RawVideo {
+            format,
+            width,
+            height,
+            frame_rate: None,
+        }
+    }
+}
+
+impl Demuxer for RawVideo {
+    fn format(&self) -> &'static str {
+        "rawvideo"
+    }
+
+    fn to_dictionary(&self) -> Dictionary<'static> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(descriptor) = self.format.descriptor() {
+            dictionary.set("pixel_format", descriptor.name());
+        }
+
+        dictionary.set("video_size", &format!("{}x{}", self.width, self.height));
+
+        if let Some(frame_rate) = self.frame_rate {
+            dictionary.set("framerate", &frame_rate.to_string());
+        }
+
+        dictionary
+    }
+}
+
+/// How `image2` turns the path into a list of files.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Pattern {
+    /// A single file, taken literally.
+    None,
+
+    /// A `printf` style sequence such as `frame_%04d.png`, the default.
+    Sequence,
+
+    /// A shell glob such as `*.png`, where the platform supports it.
+    Glob,
+}
+
+impl Pattern {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Pattern::None => "none",
+            Pattern::Sequence => "sequence",
+            Pattern::Glob => "glob",
+        }
+    }
+}
+
+/// Options of `image2`, reading a sequence of image files as a video.
+#[derive(Eq, PartialEq, Clone, Copy, Default, Debug)]
+pub struct Image2 {
+    pub pattern: Option<Pattern>,
+
+    /// First number of a `Pattern::Sequence`, searched for from 0 to 4
+    /// by default.
+    pub start_number: Option<i32>,
+
+    /// 25 by default.
+    pub frame_rate: Option<Rational>,
+
+    /// Start over once the last image is read.
+    pub looping: Option<bool>,
+}
+
+impl Demuxer for Image2 {
+    fn format(&self) -> &'static str {
+        "image2"
+    }
+
+    fn to_dictionary(&self) -> Dictionary<'static> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(pattern) = self.pattern {
+            dictionary.set("pattern_type", pattern.name());
+        }
+
+        if let Some(start_number) = self.start_number {
+            dictionary.set("start_number", &start_number.to_string());
+        }
+
+        if let Some(frame_rate) = self.frame_rate {
+            dictionary.set("framerate", &frame_rate.to_string());
+        }
+
+        if let Some(looping) = self.looping {
+            dictionary.set("loop", flag(looping));
+        }
+
+        dictionary
+    }
+}
+
+/// Options of `concat`, reading the files listed in a script one after the
+/// other.
+#[derive(Eq, PartialEq, Clone, Copy, Default, Debug)]
+pub struct Concat {
+    /// Reject absolute paths and paths with special characters in the
+    /// script, on by default. Turning it off is needed for most real world
+    /// paths, and unsafe with untrusted scripts.
+    pub safe: Option<bool>,
+
+    /// Convert H.264 and HEVC between Annex B and length prefixed framing
+    /// as the files change, on by default.
+    pub auto_convert: Option<bool>,
+}
+
+impl Demuxer for Concat {
+    fn format(&self) -> &'static str {
+        "concat"
+    }
+
+    fn to_dictionary(&self) -> Dictionary<'static> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(safe) = self.safe {
+            dictionary.set("safe", flag(safe));
+        }
+
+        if let Some(auto_convert) = self.auto_convert {
+            dictionary.set("auto_convert", flag(auto_convert));
+        }
+
+        dictionary
+    }
+}
+
+/// Open `path` with the demuxer `demuxer` is for, instead of probing, and its
+/// private options.
+///
+/// Returns `Error::DemuxerNotFound` when the demuxer is not built in.
+pub fn open<P: AsRef<Path>, D: Demuxer>(path: &P, demuxer: &D) -> Result<context::Input, Error> {
+    unsafe {
+        let name = CString::new(demuxer.format()).unwrap();
+        let format = av_find_input_format(name.as_ptr());
+
+        if format.is_null() {
+            return Err(Error::DemuxerNotFound);
+        }
+
+        let mut ps = ptr::null_mut();
+        let path = from_path(path);
+        let mut opts = demuxer.to_dictionary().disown();
+        let res = avformat_open_input(&mut ps, path.as_ptr(), format as *mut _, &mut opts);
+
+        Dictionary::own(opts);
+
+        match res {
+            0 => match avformat_find_stream_info(ps, ptr::null_mut()) {
+                r if r >= 0 => Ok(context::Input::wrap(ps)),
+                e => {
+                    avformat_close_input(&mut ps);
+                    Err(Error::from(e))
+                }
+            },
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
+fn flag(value: bool) -> &'static str {
+    if value {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concat() {
+        let options = Concat {
+            safe: Some(false),
+            ..Default::default()
+        };
+
+        let dictionary = options.to_dictionary();
+
+        assert_eq!(options.format(), "concat");
+        assert_eq!(dictionary.get("safe"), Some("0"));
+        assert_eq!(dictionary.get("auto_convert"), None);
+    }
+}
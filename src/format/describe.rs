@@ -0,0 +1,301 @@
+//! A snapshot of the configuration of a transcode, to log along with a job
+//! so that it can be reproduced or compared with another run.
+//!
+//! Containers are described with their streams, codec contexts and filters
+//! with their options, along with the input stream each output stream is
+//! made from. Only options changed from their defaults are kept, which is
+//! what it takes to set the same ones up again. With the `serde`
+//! feature, a `Description` serializes to maps and lists of strings, ready
+//! for JSON.
+
+use std::ffi::CStr;
+use std::ptr;
+
+use super::context::{Input, Output};
+use codec;
+use ffi::*;
+use libc::{c_char, c_void};
+use util::option;
+use {media, Dictionary, Error, Rational};
+
+#[derive(Clone, Default, Debug)]
+pub struct Description {
+    pub inputs: Vec<Container>,
+    pub codecs: Vec<Codec>,
+    #[cfg(feature = "filter")]
+    pub filters: Vec<Filter>,
+    pub outputs: Vec<Container>,
+    pub maps: Vec<Map>,
+}
+
+/// An output stream and the input stream it is made from, each given by the
+/// index of its container in `inputs` or `outputs` and its stream index.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Map {
+    pub input: usize,
+    pub input_stream: usize,
+    pub output: usize,
+    pub output_stream: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Container {
+    pub url: String,
+    pub format: String,
+
+    /// Options of the format context, such as `probesize` or `avoid_negative_ts`.
+    pub options: Dictionary<'static>,
+
+    /// Options of the demuxer or muxer, such as `movflags`.
+    pub private: Dictionary<'static>,
+
+    pub metadata: Dictionary<'static>,
+    pub streams: Vec<Stream>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Stream {
+    pub index: usize,
+    pub medium: media::Type,
+    pub id: codec::Id,
+    pub time_base: Rational,
+
+    /// The codec parameters, as codec context options.
+    pub parameters: Dictionary<'static>,
+
+    pub metadata: Dictionary<'static>,
+}
+
+/// An opened decoder or encoder.
+#[derive(Clone, Debug)]
+pub struct Codec {
+    /// A name to tell codecs apart, such as the output stream they encode.
+    pub label: String,
+    pub name: String,
+    pub options: Dictionary<'static>,
+    pub private: Dictionary<'static>,
+}
+
+#[cfg(feature = "filter")]
+#[derive(Clone, Debug)]
+pub struct Filter {
+    pub name: String,
+    pub filter: String,
+    pub options: Dictionary<'static>,
+}
+
+impl Description {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn input(mut self, input: &Input) -> Result<Self, Error> {
+        self.inputs.push(unsafe { container(input.as_ptr())? });
+        Ok(self)
+    }
+
+    pub fn output(mut self, output: &Output) -> Result<Self, Error> {
+        self.outputs.push(unsafe { container(output.as_ptr())? });
+        Ok(self)
+    }
+
+    /// Record which input streams the output streams are made from.
+    pub fn maps<I: IntoIterator<Item = Map>>(mut self, maps: I) -> Self {
+        self.maps.extend(maps);
+        self
+    }
+
+    pub fn codec(mut self, label: &str, context: &codec::Context) -> Result<Self, Error> {
+        unsafe {
+            let ptr = context.as_ptr();
+
+            self.codecs.push(Codec {
+                label: label.to_owned(),
+                name: match (*ptr).codec {
+                    codec if codec.is_null() => String::new(),
+                    codec => string((*codec).name),
+                },
+                options: option::serialize(ptr as *const c_void, false)?,
+                private: private((*ptr).priv_data)?,
+            });
+        }
+
+        Ok(self)
+    }
+
+    /// Add every filter of `graph`, in the order they were created.
+    #[cfg(feature = "filter")]
+    pub fn graph(mut self, graph: &::filter::Graph) -> Result<Self, Error> {
+        unsafe {
+            let ptr = graph.as_ptr();
+
+            for index in 0..(*ptr).nb_filters as usize {
+                let context = *(*ptr).filters.add(index);
+
+                self.filters.push(Filter {
+                    name: string((*context).name),
+                    filter: string((*(*context).filter).name),
+                    options: private((*context).priv_)?,
+                });
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+unsafe fn container(ptr: *const AVFormatContext) -> Result<Container, Error> {
+    let format = if !(*ptr).iformat.is_null() {
+        string((*(*ptr).iformat).name)
+    } else if !(*ptr).oformat.is_null() {
+        string((*(*ptr).oformat).name)
+    } else {
+        String::new()
+    };
+
+    let mut streams = Vec::with_capacity((*ptr).nb_streams as usize);
+
+    for index in 0..(*ptr).nb_streams as usize {
+        let stream = *(*ptr).streams.add(index);
+        let parameters = (*stream).codecpar;
+
+        // Parameters have no options of their own, a context stands in.
+        let mut context = codec::Context::new();
+
+        match avcodec_parameters_to_context(context.as_mut_ptr(), parameters) {
+            e if e < 0 => return Err(Error::from(e)),
+            _ => (),
+        }
+
+        streams.push(Stream {
+            index,
+            medium: media::Type::from((*parameters).codec_type),
+            id: codec::Id::from((*parameters).codec_id),
+            time_base: Rational::from((*stream).time_base),
+            parameters: option::serialize(context.as_ptr() as *const c_void, false)?,
+            metadata: metadata((*stream).metadata),
+        });
+    }
+
+    Ok(Container {
+        url: url(ptr),
+        format,
+        options: option::serialize(ptr as *const c_void, false)?,
+        private: private((*ptr).priv_data)?,
+        metadata: metadata((*ptr).metadata),
+        streams,
+    })
+}
+
+// Private data only has options when its first member is an `AVClass`.
+unsafe fn private(ptr: *mut c_void) -> Result<Dictionary<'static>, Error> {
+    if ptr.is_null() || (*(ptr as *const *const AVClass)).is_null() {
+        return Ok(Dictionary::new());
+    }
+
+    option::serialize(ptr, false)
+}
+
+unsafe fn metadata(ptr: *mut AVDictionary) -> Dictionary<'static> {
+    let mut copy = ptr::null_mut();
+    av_dict_copy(&mut copy, ptr, 0);
+
+    Dictionary::own(copy)
+}
+
+#[cfg(feature = "ffmpeg_4_1")]
+unsafe fn url(ptr: *const AVFormatContext) -> String {
+    string((*ptr).url)
+}
+
+#[cfg(not(feature = "ffmpeg_4_1"))]
+unsafe fn url(ptr: *const AVFormatContext) -> String {
+    string((*ptr).filename.as_ptr())
+}
+
+unsafe fn string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    use super::*;
+
+    impl Serialize for Description {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("Description", 5)?;
+            state.serialize_field("inputs", &self.inputs)?;
+            state.serialize_field("codecs", &self.codecs)?;
+            #[cfg(feature = "filter")]
+            state.serialize_field("filters", &self.filters)?;
+            state.serialize_field("outputs", &self.outputs)?;
+            state.serialize_field("maps", &self.maps)?;
+            state.end()
+        }
+    }
+
+    impl Serialize for Map {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("Map", 4)?;
+            state.serialize_field("input", &self.input)?;
+            state.serialize_field("input_stream", &self.input_stream)?;
+            state.serialize_field("output", &self.output)?;
+            state.serialize_field("output_stream", &self.output_stream)?;
+            state.end()
+        }
+    }
+
+    impl Serialize for Container {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("Container", 6)?;
+            state.serialize_field("url", &self.url)?;
+            state.serialize_field("format", &self.format)?;
+            state.serialize_field("options", &self.options)?;
+            state.serialize_field("private", &self.private)?;
+            state.serialize_field("metadata", &self.metadata)?;
+            state.serialize_field("streams", &self.streams)?;
+            state.end()
+        }
+    }
+
+    impl Serialize for Stream {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("Stream", 6)?;
+            state.serialize_field("index", &self.index)?;
+            state.serialize_field("medium", &format!("{:?}", self.medium))?;
+            state.serialize_field("id", &format!("{:?}", self.id))?;
+            state.serialize_field("time_base", &self.time_base.to_string())?;
+            state.serialize_field("parameters", &self.parameters)?;
+            state.serialize_field("metadata", &self.metadata)?;
+            state.end()
+        }
+    }
+
+    impl Serialize for Codec {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("Codec", 4)?;
+            state.serialize_field("label", &self.label)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("options", &self.options)?;
+            state.serialize_field("private", &self.private)?;
+            state.end()
+        }
+    }
+
+    #[cfg(feature = "filter")]
+    impl Serialize for Filter {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("Filter", 3)?;
+            state.serialize_field("name", &self.name)?;
+            state.serialize_field("filter", &self.filter)?;
+            state.serialize_field("options", &self.options)?;
+            state.end()
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Content hashes of decoded streams, to find the same essence muxed into
+//! different containers.
+//!
+//! Only decoded pictures and samples are hashed, along with their format,
+//! so timestamps, packetization and container metadata do not matter. The
+//! same encoded stream yields the same fingerprint with any decoder build
+//! producing bit-exact output.
+
+use std::ffi::CString;
+use std::fmt;
+use std::ptr;
+
+use super::context::Input;
+use ffi::*;
+use libc::{c_int, ENOMEM};
+use {frame, media, Discard, Error};
+
+/// Which frames go into the hash.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Sampling {
+    /// Hash one frame out of every `every`, starting with the first.
+    ///
+    /// Above 1, video streams are sampled among their keyframes only, the
+    /// decoder skipping the other frames instead of decoding them.
+    pub every: usize,
+
+    /// Stop after hashing this many frames.
+    pub limit: Option<usize>,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Sampling {
+            every: 1,
+            limit: None,
+        }
+    }
+}
+
+/// A SHA-256 digest of decoded frames. Fingerprints are only comparable when
+/// computed with the same `Sampling`.
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct Fingerprint {
+    pub digest: [u8; 32],
+
+    /// Number of frames hashed.
+    pub frames: usize,
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.digest {
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Decode `stream` of `input` from its current position and hash the
+/// frames picked by `sampling`.
+///
+/// Only audio and video streams can be fingerprinted, and decoding errors
+/// abort instead of being skipped, as they would make the result depend on
+/// the decoder.
+pub fn stream(input: &mut Input, stream: usize, sampling: &Sampling) -> Result<Fingerprint, Error> {
+    let medium = match input.stream(stream) {
+        Some(stream) => stream.parameters().medium(),
+        None => return Err(Error::StreamNotFound),
+    };
+
+    let mut hash = Hash::new()?;
+    let mut frames = 0;
+
+    match medium {
+        media::Type::Video => {
+            let every = sampling.every.max(1);
+            let mut decoded = input.decoded_video(stream)?;
+
+            // Decoders ignoring the hint still output every frame, so
+            // keyframes are picked out below as well.
+            if every > 1 {
+                decoded.decoder_mut().skip_frame(Discard::NonKey);
+            }
+
+            let mut seen = 0;
+
+            for frame in decoded {
+                if reached(sampling, frames) {
+                    break;
+                }
+
+                let frame = frame?;
+
+                if every > 1 && !frame.is_key() {
+                    continue;
+                }
+
+                if seen % every == 0 {
+                    video(&mut hash, &frame)?;
+                    frames += 1;
+                }
+
+                seen += 1;
+            }
+        }
+
+        media::Type::Audio => {
+            for (index, frame) in input.decoded_audio(stream)?.enumerate() {
+                if reached(sampling, frames) {
+                    break;
+                }
+
+                let frame = frame?;
+
+                if index % sampling.every.max(1) == 0 {
+                    audio(&mut hash, &frame);
+                    frames += 1;
+                }
+            }
+        }
+
+        _ => return Err(Error::InvalidData),
+    }
+
+    Ok(Fingerprint {
+        digest: hash.finish(),
+        frames,
+    })
+}
+
+fn reached(sampling: &Sampling, frames: usize) -> bool {
+    sampling.limit.map_or(false, |limit| frames >= limit)
+}
+
+fn video(hash: &mut Hash, frame: &frame::Video) -> Result<(), Error> {
+    if frame.is_hardware() {
+        return Err(Error::InvalidData);
+    }
+
+    let format = AVPixelFormat::from(frame.format());
+
+    hash.update_u32(format as u32);
+    hash.update_u32(frame.raw_width());
+    hash.update_u32(frame.raw_height());
+
+    for index in 0..frame.planes() {
+        for row in frame.row_bytes(index) {
+            hash.update(row);
+        }
+    }
+
+    Ok(())
+}
+
+fn audio(hash: &mut Hash, frame: &frame::Audio) {
+    let format = frame.format();
+    let mut bytes = frame.samples() * format.bytes();
+
+    if !format.is_planar() {
+        bytes *= frame.channels() as usize;
+    }
+
+    hash.update_u32(AVSampleFormat::from(format) as u32);
+    hash.update_u32(u32::from(frame.channels()));
+    hash.update_u32(frame.raw_rate());
+
+    for index in 0..frame.planes() {
+        hash.update(&frame.data(index)[..bytes]);
+    }
+}
+
+struct Hash {
+    ptr: *mut AVHashContext,
+}
+
+impl Hash {
+    fn new() -> Result<Self, Error> {
+        unsafe {
+            let name = CString::new("SHA256").unwrap();
+            let mut ptr = ptr::null_mut();
+
+            match av_hash_alloc(&mut ptr, name.as_ptr()) {
+                0 if ptr.is_null() => Err(Error::Other { errno: ENOMEM }),
+                0 => {
+                    av_hash_init(ptr);
+                    Ok(Hash { ptr })
+                }
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        unsafe {
+            av_hash_update(self.ptr, data.as_ptr(), data.len() as _);
+        }
+    }
+
+    fn update_u32(&mut self, value: u32) {
+        self.update(&value.to_le_bytes());
+    }
+
+    fn finish(self) -> [u8; 32] {
+        let mut digest = [0; 32];
+
+        unsafe {
+            av_hash_final_bin(self.ptr, digest.as_mut_ptr(), digest.len() as c_int);
+        }
+
+        digest
+    }
+}
+
+impl Drop for Hash {
+    fn drop(&mut self) {
+        unsafe {
+            av_hash_freep(&mut self.ptr);
+        }
+    }
+}
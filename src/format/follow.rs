@@ -0,0 +1,114 @@
+use std::cmp;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+use super::context::Input;
+use super::io::Io;
+use Error;
+
+/// How to wait for a file that is still being written.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Follow {
+    /// Wait after the first read that hits the end of the file.
+    pub initial: Duration,
+
+    /// Longest wait between two attempts, the wait doubling each time.
+    pub max: Duration,
+
+    /// Give up and report the end of the file after waiting this long
+    /// without new data.
+    pub idle: Duration,
+
+    /// Reopen the file by path before every attempt, seeking back to the
+    /// current position, for writers that replace the file or filesystems
+    /// that cache its size.
+    pub reopen: bool,
+}
+
+impl Default for Follow {
+    fn default() -> Self {
+        Follow {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(500),
+            idle: Duration::from_secs(10),
+            reopen: false,
+        }
+    }
+}
+
+/// A file reader that waits for more data at the end of the file instead of
+/// reporting it, as `tail -f` does.
+pub struct Tail {
+    path: PathBuf,
+    file: File,
+    position: u64,
+    policy: Follow,
+}
+
+impl Tail {
+    pub fn open<P: AsRef<Path>>(path: &P, policy: Follow) -> io::Result<Self> {
+        Ok(Tail {
+            path: path.as_ref().to_owned(),
+            file: File::open(path)?,
+            position: 0,
+            policy,
+        })
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+impl Read for Tail {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut wait = self.policy.initial;
+        let mut waited = Duration::from_secs(0);
+
+        loop {
+            let read = self.file.read(buf)?;
+
+            if read > 0 || buf.is_empty() {
+                self.position += read as u64;
+                return Ok(read);
+            }
+
+            if waited >= self.policy.idle {
+                return Ok(0);
+            }
+
+            thread::sleep(wait);
+            waited += wait;
+            wait = cmp::min(wait * 2, self.policy.max);
+
+            if self.policy.reopen {
+                let mut file = File::open(&self.path)?;
+                file.seek(SeekFrom::Start(self.position))?;
+
+                self.file = file;
+            }
+        }
+    }
+}
+
+impl Seek for Tail {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        self.position = self.file.seek(position)?;
+        Ok(self.position)
+    }
+}
+
+/// Open a file that is still being written, reading it as it grows.
+///
+/// Only formats that can be read without their end, such as MPEG-TS,
+/// Matroska or fragmented MP4, are suitable. Reads block following `policy`,
+/// so the returned input reaches its end only once the file stops growing for
+/// `policy.idle`.
+pub fn input<P: AsRef<Path>>(path: &P, policy: Follow) -> Result<Input, Error> {
+    let tail = Tail::open(path, policy).map_err(Error::from)?;
+
+    super::input_from(Io::seekable(tail))
+}
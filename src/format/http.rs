@@ -0,0 +1,102 @@
+//! What an HTTP(S) server answered when an input was opened.
+//!
+//! libavformat keeps only part of the response: the final location, the
+//! content type, cookies and ICY (SHOUTcast) headers. The status code of a
+//! successful request is not retained; a failed one surfaces as the error
+//! returned when opening, see `Error::http_status`.
+
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+use super::context::Input;
+use ffi::*;
+use libc::c_void;
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Response {
+    /// The URL the data came from, after following redirects.
+    pub location: Option<String>,
+
+    pub content_type: Option<String>,
+
+    /// `Set-Cookie` and `icy-*` headers, in order. Cookies passed in the
+    /// `cookies` option when opening are listed as well.
+    pub headers: Vec<(String, String)>,
+}
+
+impl Response {
+    /// The first value of the header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|&&(ref key, _)| key.eq_ignore_ascii_case(name))
+            .map(|&(_, ref value)| &value[..])
+    }
+}
+
+/// The response behind `input`, or `None` when it was not opened over
+/// HTTP(S) or uses custom I/O.
+pub fn response(input: &Input) -> Option<Response> {
+    let pb = unsafe { (*input.as_ptr()).pb };
+
+    if pb.is_null() {
+        return None;
+    }
+
+    // Only the HTTP protocol has a `location` option.
+    let location = get(pb, "location")?;
+    let mut headers = Vec::new();
+
+    if let Some(cookies) = get(pb, "cookies") {
+        for cookie in cookies.lines().filter(|line| !line.is_empty()) {
+            headers.push(("Set-Cookie".to_owned(), cookie.to_owned()));
+        }
+    }
+
+    if let Some(icy) = get(pb, "icy_metadata_headers") {
+        headers.extend(icy.lines().filter_map(split));
+    }
+
+    Some(Response {
+        location: Some(location).filter(|value| !value.is_empty()),
+        content_type: get(pb, "mime_type").filter(|value| !value.is_empty()),
+        headers,
+    })
+}
+
+fn get(pb: *mut AVIOContext, name: &str) -> Option<String> {
+    unsafe {
+        let name = CString::new(name).unwrap();
+        let mut value = ptr::null_mut();
+
+        if av_opt_get(
+            pb as *mut c_void,
+            name.as_ptr(),
+            AV_OPT_SEARCH_CHILDREN,
+            &mut value,
+        ) < 0
+        {
+            return None;
+        }
+
+        if value.is_null() {
+            return Some(String::new());
+        }
+
+        let result = CStr::from_ptr(value as *const _)
+            .to_string_lossy()
+            .into_owned();
+        av_free(value as *mut c_void);
+
+        Some(result)
+    }
+}
+
+fn split(line: &str) -> Option<(String, String)> {
+    let index = line.find(':')?;
+
+    Some((
+        line[..index].trim().to_owned(),
+        line[index + 1..].trim().to_owned(),
+    ))
+}
@@ -0,0 +1,142 @@
+//! Single images in and out of `frame::Video`, for thumbnails and snapshots.
+
+use std::path::Path;
+
+use super::{input, output_as};
+use codec::{self, Flags};
+use encoder::video::Encoder;
+use ffi::*;
+use libc::c_int;
+use {encoder, frame, media, Dictionary, Error, Packet, Rational};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Kind {
+    Png,
+
+    /// Baseline JPEG through the MJPEG encoder, at a quantizer scale from 2,
+    /// the best, to 31, as `-q:v` takes it.
+    Jpeg {
+        qscale: f32,
+    },
+}
+
+impl Kind {
+    /// The kind matching the extension of `path`, JPEG at a quantizer scale
+    /// of 2.
+    pub fn from_path<P: AsRef<Path>>(path: &P) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+
+        match &extension[..] {
+            "png" => Some(Kind::Png),
+            "jpg" | "jpeg" => Some(Kind::Jpeg { qscale: 2.0 }),
+            _ => None,
+        }
+    }
+
+    pub fn id(&self) -> codec::Id {
+        match *self {
+            Kind::Png => codec::Id::PNG,
+            Kind::Jpeg { .. } => codec::Id::MJPEG,
+        }
+    }
+}
+
+/// Encode `frame` as a complete image file, converting it to a pixel format
+/// the encoder takes when needed.
+pub fn encode(frame: &frame::Video, kind: Kind) -> Result<Packet, Error> {
+    open(frame, kind).map(|(_, packet)| packet)
+}
+
+/// Write `frame` to `path` as a single image.
+pub fn save<P: AsRef<Path>>(frame: &frame::Video, path: &P, kind: Kind) -> Result<(), Error> {
+    let (encoder, mut packet) = open(frame, kind)?;
+    let time_base = encoder.time_base();
+
+    let mut output = output_as(path, "image2")?;
+
+    {
+        let mut stream = output.add_stream(encoder.codec())?;
+        stream.set_time_base(time_base);
+        stream.set_parameters(&encoder);
+    }
+
+    let mut options = Dictionary::new();
+    options.set("update", "1");
+
+    output.write_header_with(options)?;
+    packet.set_stream(0);
+    output.write_packet(&mut packet, time_base)?;
+    output.write_trailer()
+}
+
+/// Decode the first picture of the file at `path`, in whatever pixel format
+/// the decoder produces, such as `PAL8` for paletted PNG.
+pub fn load<P: AsRef<Path>>(path: &P) -> Result<frame::Video, Error> {
+    let mut input = input(path)?;
+
+    let index = input
+        .streams()
+        .best(media::Type::Video)
+        .map(|stream| stream.index())
+        .ok_or(Error::StreamNotFound)?;
+
+    let mut frames = input.decoded_video(index)?;
+
+    frames.next().unwrap_or(Err(Error::Eof))
+}
+
+fn open(frame: &frame::Video, kind: Kind) -> Result<(Encoder, Packet), Error> {
+    let codec = encoder::find(kind.id())
+        .ok_or(Error::EncoderNotFound)?
+        .video()?;
+    let format = codec.pick_best_pixel_format(frame.format());
+
+    let mut context = codec::Context::new().encoder().video()?;
+    context.set_width(frame.raw_width());
+    context.set_height(frame.raw_height());
+    context.set_format(format);
+    context.set_time_base(Rational(1, 25));
+
+    if frame.aspect_ratio().numerator() > 0 {
+        context.set_aspect_ratio(frame.aspect_ratio());
+    }
+
+    if let Kind::Jpeg { qscale } = kind {
+        let flags = context.flags() | Flags::QSCALE;
+        context.set_flags(flags);
+        context.set_global_quality((qscale * FF_QP2LAMBDA as f32) as c_int);
+    }
+
+    let mut encoder = context.open_as(codec)?;
+
+    if format == frame.format() {
+        encoder.send_frame(frame)?;
+    } else {
+        let mut converted = frame::Video::empty();
+        frame.converter(format)?.run(frame, &mut converted)?;
+        encoder.send_frame(&converted)?;
+    }
+
+    encoder.send_eof()?;
+
+    let mut packet = Packet::empty();
+    encoder.receive_packet(&mut packet)?;
+
+    Ok((encoder, packet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(Kind::from_path(&"thumb.PNG"), Some(Kind::Png));
+        assert_eq!(
+            Kind::from_path(&"thumb.jpeg"),
+            Some(Kind::Jpeg { qscale: 2.0 })
+        );
+        assert_eq!(Kind::from_path(&"thumb.webp"), None);
+        assert_eq!(Kind::from_path(&"thumb"), None);
+    }
+}
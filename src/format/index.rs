@@ -0,0 +1,247 @@
+use std::io::{self, BufRead, Write};
+
+use super::context::Input;
+use codec::Parser;
+use ffi::*;
+use libc::c_int;
+use {codec, Error, Rational};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Entry {
+    pub pts: i64,
+    pub dts: i64,
+    pub position: i64,
+    pub size: usize,
+}
+
+/// Keyframe positions of a single stream, in its time base.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct KeyframeIndex {
+    stream: usize,
+    time_base: Rational,
+    entries: Vec<Entry>,
+}
+
+impl KeyframeIndex {
+    pub fn stream(&self) -> usize {
+        self.stream
+    }
+
+    pub fn time_base(&self) -> Rational {
+        self.time_base
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Find the last keyframe presented at or before `ts`.
+    pub fn nearest(&self, ts: i64) -> Option<&Entry> {
+        match self.entries.binary_search_by_key(&ts, |entry| entry.pts) {
+            Ok(index) => Some(&self.entries[index]),
+            Err(0) => None,
+            Err(index) => Some(&self.entries[index - 1]),
+        }
+    }
+
+    /// Register the entries with the demuxer so later seeks on `input` use
+    /// them instead of probing the file.
+    pub fn apply(&self, input: &mut Input) -> Result<(), Error> {
+        unsafe {
+            let stream = match input.stream(self.stream) {
+                Some(stream) => stream.as_ptr() as *mut AVStream,
+                None => return Err(Error::StreamNotFound),
+            };
+
+            for entry in &self.entries {
+                match av_add_index_entry(
+                    stream,
+                    entry.position,
+                    entry.dts,
+                    entry.size as c_int,
+                    0,
+                    AVINDEX_KEYFRAME,
+                ) {
+                    e if e < 0 => return Err(Error::from(e)),
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Serialize as text: a `stream num/den` header followed by one
+    /// `pts dts position size` line per entry.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(writer, "{} {}", self.stream, self.time_base)?;
+
+        for entry in &self.entries {
+            writeln!(
+                writer,
+                "{} {} {} {}",
+                entry.pts, entry.dts, entry.position, entry.size
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn read<R: BufRead>(reader: &mut R) -> io::Result<Self> {
+        let mut lines = reader.lines();
+
+        let header = match lines.next() {
+            Some(line) => line?,
+            None => return Err(invalid()),
+        };
+
+        let mut fields = header.split(|c| c == ' ' || c == '/');
+        let stream = parse(fields.next())?;
+        let time_base = Rational(parse(fields.next())?, parse(fields.next())?);
+
+        let mut entries = Vec::new();
+
+        for line in lines {
+            let line = line?;
+            let mut fields = line.split(' ');
+
+            entries.push(Entry {
+                pts: parse(fields.next())?,
+                dts: parse(fields.next())?,
+                position: parse(fields.next())?,
+                size: parse(fields.next())?,
+            });
+        }
+
+        Ok(KeyframeIndex {
+            stream,
+            time_base,
+            entries,
+        })
+    }
+}
+
+/// Scan the remaining packets of `input` and index the keyframes of `stream`
+/// as flagged by the demuxer.
+///
+/// `input` is left at the end of the file and must be seeked before reuse.
+pub fn build(input: &mut Input, stream: usize) -> Result<KeyframeIndex, Error> {
+    scan(input, stream, None)
+}
+
+/// Like `build`, but run the packets through the codec parser and index the
+/// recovery points it reports, for demuxers that do not flag them reliably.
+pub fn build_parsed(input: &mut Input, stream: usize) -> Result<KeyframeIndex, Error> {
+    let parameters = match input.stream(stream) {
+        Some(stream) => stream.parameters(),
+        None => return Err(Error::StreamNotFound),
+    };
+
+    let mut context = codec::Context::new();
+    context.set_parameters(parameters)?;
+
+    let mut parser = Parser::new(context)?;
+    parser.set_complete_frames(true);
+
+    scan(input, stream, Some(parser))
+}
+
+fn scan(
+    input: &mut Input,
+    index: usize,
+    mut parser: Option<Parser>,
+) -> Result<KeyframeIndex, Error> {
+    let time_base = match input.stream(index) {
+        Some(stream) => stream.time_base(),
+        None => return Err(Error::StreamNotFound),
+    };
+
+    let mut entries = Vec::new();
+
+    for (stream, packet) in input.packets() {
+        if stream.index() != index {
+            continue;
+        }
+
+        let key = match parser {
+            Some(ref mut parser) => parser.is_key(packet.data().unwrap_or(&[])),
+            None => packet.is_key(),
+        };
+
+        if !key {
+            continue;
+        }
+
+        let (pts, dts) = match (packet.pts(), packet.dts()) {
+            (Some(pts), dts) => (pts, dts.unwrap_or(pts)),
+            (None, Some(dts)) => (dts, dts),
+            (None, None) => continue,
+        };
+
+        entries.push(Entry {
+            pts,
+            dts,
+            position: packet.position() as i64,
+            size: packet.size(),
+        });
+    }
+
+    entries.sort_by_key(|entry| entry.pts);
+
+    Ok(KeyframeIndex {
+        stream: index,
+        time_base,
+        entries,
+    })
+}
+
+fn parse<T: ::std::str::FromStr>(field: Option<&str>) -> io::Result<T> {
+    field.and_then(|v| v.parse().ok()).ok_or_else(invalid)
+}
+
+fn invalid() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed keyframe index")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let index = KeyframeIndex {
+            stream: 1,
+            time_base: Rational(1, 90000),
+            entries: vec![
+                Entry {
+                    pts: 0,
+                    dts: -3003,
+                    position: 48,
+                    size: 1024,
+                },
+                Entry {
+                    pts: 180180,
+                    dts: 177177,
+                    position: 65536,
+                    size: 2048,
+                },
+            ],
+        };
+
+        let mut buffer = Vec::new();
+        index.write(&mut buffer).unwrap();
+
+        assert_eq!(KeyframeIndex::read(&mut &buffer[..]).unwrap(), index);
+        assert_eq!(index.nearest(-1), None);
+        assert_eq!(index.nearest(90000).unwrap().pts, 0);
+        assert_eq!(index.nearest(180180).unwrap().pts, 180180);
+    }
+}
@@ -0,0 +1,289 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Cursor, Read, Seek, SeekFrom, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::slice;
+use std::sync::{Arc, Mutex};
+
+use ffi::*;
+use libc::{c_int, c_void, EINVAL, SEEK_CUR, SEEK_END, SEEK_SET};
+use Error;
+
+const BUFFER_SIZE: usize = 32 * 1024;
+
+/// An `AVIOContext` backed by a Rust reader or writer.
+pub struct Io {
+    ptr: *mut AVIOContext,
+
+    opaque: *mut c_void,
+    free: unsafe fn(*mut c_void),
+}
+
+unsafe impl Send for Io {}
+
+impl Io {
+    pub unsafe fn as_ptr(&self) -> *const AVIOContext {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVIOContext {
+        self.ptr
+    }
+}
+
+/// Tuning for file outputs opened with `Io::file`, aimed at high bitrate
+/// recordings. Hints the platform does not support are ignored.
+///
+/// Direct IO is not supported: `O_DIRECT` needs writes aligned in offset and
+/// size to the disk blocks, while muxers seek back to patch headers at
+/// arbitrary offsets and end on a partial block. Batching large writes gets
+/// most of the benefit without bypassing the page cache.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct WriteHints {
+    /// Size of the buffer the muxer output is batched into, so each write
+    /// system call carries at least this many bytes.
+    pub batch: usize,
+
+    /// Reserve this many bytes on disk up front, without changing the file
+    /// size, to limit fragmentation. Only honored on Linux.
+    pub preallocate: u64,
+}
+
+impl Default for WriteHints {
+    fn default() -> Self {
+        WriteHints {
+            batch: BUFFER_SIZE,
+            preallocate: 0,
+        }
+    }
+}
+
+impl Io {
+    /// Read from a stream that cannot seek, such as a socket or pipe.
+    pub fn reader<R: Read + Send + 'static>(reader: R) -> Self {
+        unsafe { Io::new(reader, BUFFER_SIZE, 0, Some(read::<R>), None, None) }
+    }
+
+    /// Read from a seekable stream, such as a file or an in-memory buffer.
+    pub fn seekable<R: Read + Seek + Send + 'static>(reader: R) -> Self {
+        unsafe {
+            Io::new(
+                reader,
+                BUFFER_SIZE,
+                0,
+                Some(read::<R>),
+                None,
+                Some(seek::<R>),
+            )
+        }
+    }
+
+    /// Write to a stream that cannot seek. Only muxers that do not go back to
+    /// patch their headers, such as fragmented MP4 or MPEG-TS, can use it.
+    pub fn writer<W: Write + Send + 'static>(writer: W) -> Self {
+        unsafe { Io::new(writer, BUFFER_SIZE, 1, None, Some(write::<W>), None) }
+    }
+
+    /// Write to a seekable stream, such as a file or a `Memory` buffer.
+    pub fn seekable_writer<W: Write + Seek + Send + 'static>(writer: W) -> Self {
+        unsafe {
+            Io::new(
+                writer,
+                BUFFER_SIZE,
+                1,
+                None,
+                Some(write::<W>),
+                Some(seek::<W>),
+            )
+        }
+    }
+
+    /// Create or truncate the file at `path` and write to it in batches of
+    /// `hints.batch` bytes.
+    pub fn file<P: AsRef<Path>>(path: &P, hints: &WriteHints) -> Result<Self, Error> {
+        let file = match open(path.as_ref(), hints) {
+            Ok(file) => file,
+            Err(e) => return Err(Error::from(e)),
+        };
+        let batch = hints.batch.max(4096).min(c_int::max_value() as usize);
+
+        unsafe {
+            Ok(Io::new(
+                file,
+                batch,
+                1,
+                None,
+                Some(write::<File>),
+                Some(seek::<File>),
+            ))
+        }
+    }
+
+    unsafe fn new<T>(
+        inner: T,
+        size: usize,
+        write_flag: c_int,
+        read: Option<unsafe extern "C" fn(*mut c_void, *mut u8, c_int) -> c_int>,
+        write: Option<unsafe extern "C" fn(*mut c_void, *mut u8, c_int) -> c_int>,
+        seek: Option<unsafe extern "C" fn(*mut c_void, i64, c_int) -> i64>,
+    ) -> Self {
+        let opaque = Box::into_raw(Box::new(inner)) as *mut c_void;
+        let buffer = av_malloc(size) as *mut u8;
+
+        if buffer.is_null() {
+            panic!("out of memory");
+        }
+
+        let ptr = avio_alloc_context(buffer, size as c_int, write_flag, opaque, read, write, seek);
+
+        if ptr.is_null() {
+            panic!("out of memory");
+        }
+
+        Io {
+            ptr,
+
+            opaque,
+            free: free::<T>,
+        }
+    }
+}
+
+impl Drop for Io {
+    fn drop(&mut self) {
+        unsafe {
+            av_freep(&mut (*self.ptr).buffer as *mut _ as *mut c_void);
+            avio_context_free(&mut self.ptr);
+
+            (self.free)(self.opaque);
+        }
+    }
+}
+
+unsafe fn free<T>(opaque: *mut c_void) {
+    drop(Box::from_raw(opaque as *mut T));
+}
+
+unsafe extern "C" fn read<R: Read>(opaque: *mut c_void, buf: *mut u8, size: c_int) -> c_int {
+    let reader = &mut *(opaque as *mut R);
+    let buf = slice::from_raw_parts_mut(buf, size as usize);
+
+    guard(|| loop {
+        match reader.read(buf) {
+            Ok(0) => return AVERROR_EOF,
+            Ok(n) => return n as c_int,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return errno(e),
+        }
+    })
+}
+
+unsafe extern "C" fn write<W: Write>(opaque: *mut c_void, buf: *mut u8, size: c_int) -> c_int {
+    let writer = &mut *(opaque as *mut W);
+    let buf = slice::from_raw_parts(buf as *const u8, size as usize);
+
+    guard(|| match writer.write_all(buf) {
+        Ok(()) => size,
+        Err(e) => errno(e),
+    })
+}
+
+unsafe extern "C" fn seek<S: Seek>(opaque: *mut c_void, offset: i64, whence: c_int) -> i64 {
+    let stream = &mut *(opaque as *mut S);
+
+    guard(|| {
+        let result = if whence & AVSEEK_SIZE != 0 {
+            size(stream)
+        } else {
+            match whence & !AVSEEK_FORCE {
+                SEEK_SET => stream.seek(SeekFrom::Start(offset as u64)),
+                SEEK_CUR => stream.seek(SeekFrom::Current(offset)),
+                SEEK_END => stream.seek(SeekFrom::End(offset)),
+                _ => return i64::from(AVERROR(EINVAL)),
+            }
+        };
+
+        match result {
+            Ok(position) => position as i64,
+            Err(e) => errno(e),
+        }
+    })
+}
+
+fn size<S: Seek>(stream: &mut S) -> io::Result<u64> {
+    let position = stream.seek(SeekFrom::Current(0))?;
+    let size = stream.seek(SeekFrom::End(0))?;
+    stream.seek(SeekFrom::Start(position))?;
+
+    Ok(size)
+}
+
+fn errno<T: From<c_int>>(error: io::Error) -> T {
+    T::from(Error::from(error).into())
+}
+
+// Unwinding into C is undefined behavior, report panics as external errors.
+fn guard<T: From<c_int>, F: FnOnce() -> T>(f: F) -> T {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| T::from(AVERROR_EXTERNAL))
+}
+
+// Create or truncate a file, honoring `WriteHints`.
+fn open(path: &Path, hints: &WriteHints) -> io::Result<File> {
+    let file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)?;
+
+    if hints.preallocate > 0 {
+        preallocate(&file, hints.preallocate);
+    }
+
+    Ok(file)
+}
+
+// Best effort: filesystems without fallocate support are left alone.
+#[cfg(target_os = "linux")]
+fn preallocate(file: &File, size: u64) {
+    use libc::{fallocate, off_t, FALLOC_FL_KEEP_SIZE};
+    use std::os::unix::io::AsRawFd;
+
+    unsafe {
+        fallocate(file.as_raw_fd(), FALLOC_FL_KEEP_SIZE, 0, size as off_t);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn preallocate(_file: &File, _size: u64) {}
+
+/// A growable in-memory buffer to mux into, shared between its clones so the
+/// data can be retrieved once the `Io` owning one of them is gone.
+#[derive(Clone, Default, Debug)]
+pub struct Memory(Arc<Mutex<Cursor<Vec<u8>>>>);
+
+impl Memory {
+    pub fn new() -> Self {
+        Memory::default()
+    }
+
+    /// Copy out everything written so far.
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.0.lock().unwrap().get_ref().clone()
+    }
+}
+
+impl Write for Memory {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for Memory {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        self.0.lock().unwrap().seek(position)
+    }
+}
@@ -0,0 +1,251 @@
+//! Matroska and WebM tag hierarchies, as flattened into metadata keys.
+//!
+//! The demuxer resolves each `Tag` element against its `Targets`: tags aimed
+//! at a track by `TagTrackUID` land in the metadata of that stream, tags aimed
+//! at a chapter or attachment in theirs, and untargeted tags in the container
+//! metadata, prefixed with their `TargetType` when one is set. Nested
+//! `SimpleTag` elements are joined with `/`, and tags in a language other than
+//! the default get a `-lang` suffix, so a Matroska file can carry
+//! `ALBUM/ARTIST/SORT_WITH-fre`.
+//!
+//! libavformat keeps track UIDs to itself: they are not exported when
+//! demuxing, and the muxer assigns new ones (deterministic ones when the
+//! output is bitexact). Tags still follow their tracks through a remux since
+//! they are attached to the streams rather than to the UIDs.
+//!
+//! The muxer writes stream tags targeted at their track and container tags
+//! at the `ALBUM` / `MOVIE` level, keeping keys as they are, so a `TargetType`
+//! prefix or nesting does not survive a round trip as structure.
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::stream::{Stream, StreamMut};
+use {Dictionary, DictionaryRef};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Target {
+    Collection,
+    Edition,
+    Issue,
+    Volume,
+    Opus,
+    Season,
+    Sequel,
+    Album,
+    Opera,
+    Concert,
+    Movie,
+    Episode,
+    Part,
+    Session,
+    Track,
+    Song,
+    Chapter,
+    Subtrack,
+    Movement,
+    Scene,
+    Shot,
+}
+
+impl Target {
+    /// The `TargetTypeValue` this type belongs to, from 70 for collections
+    /// down to 10 for shots.
+    pub fn level(&self) -> u32 {
+        match *self {
+            Target::Collection => 70,
+            Target::Edition | Target::Issue | Target::Volume => 60,
+            Target::Opus | Target::Season | Target::Sequel => 60,
+            Target::Album | Target::Opera | Target::Concert => 50,
+            Target::Movie | Target::Episode => 50,
+            Target::Part | Target::Session => 40,
+            Target::Track | Target::Song | Target::Chapter => 30,
+            Target::Subtrack | Target::Movement | Target::Scene => 20,
+            Target::Shot => 10,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Target::Collection => "COLLECTION",
+            Target::Edition => "EDITION",
+            Target::Issue => "ISSUE",
+            Target::Volume => "VOLUME",
+            Target::Opus => "OPUS",
+            Target::Season => "SEASON",
+            Target::Sequel => "SEQUEL",
+            Target::Album => "ALBUM",
+            Target::Opera => "OPERA",
+            Target::Concert => "CONCERT",
+            Target::Movie => "MOVIE",
+            Target::Episode => "EPISODE",
+            Target::Part => "PART",
+            Target::Session => "SESSION",
+            Target::Track => "TRACK",
+            Target::Song => "SONG",
+            Target::Chapter => "CHAPTER",
+            Target::Subtrack => "SUBTRACK",
+            Target::Movement => "MOVEMENT",
+            Target::Scene => "SCENE",
+            Target::Shot => "SHOT",
+        }
+    }
+}
+
+impl FromStr for Target {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, ()> {
+        match value {
+            "COLLECTION" => Ok(Target::Collection),
+            "EDITION" => Ok(Target::Edition),
+            "ISSUE" => Ok(Target::Issue),
+            "VOLUME" => Ok(Target::Volume),
+            "OPUS" => Ok(Target::Opus),
+            "SEASON" => Ok(Target::Season),
+            "SEQUEL" => Ok(Target::Sequel),
+            "ALBUM" => Ok(Target::Album),
+            "OPERA" => Ok(Target::Opera),
+            "CONCERT" => Ok(Target::Concert),
+            "MOVIE" => Ok(Target::Movie),
+            "EPISODE" => Ok(Target::Episode),
+            "PART" => Ok(Target::Part),
+            "SESSION" => Ok(Target::Session),
+            "TRACK" => Ok(Target::Track),
+            "SONG" => Ok(Target::Song),
+            "CHAPTER" => Ok(Target::Chapter),
+            "SUBTRACK" => Ok(Target::Subtrack),
+            "MOVEMENT" => Ok(Target::Movement),
+            "SCENE" => Ok(Target::Scene),
+            "SHOT" => Ok(Target::Shot),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A single `SimpleTag` along with the names of the tags it is nested in.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Tag {
+    pub target: Option<Target>,
+    pub path: Vec<String>,
+    pub language: Option<String>,
+    pub value: String,
+}
+
+impl Tag {
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, value: V) -> Self {
+        Tag {
+            target: None,
+            path: vec![name.into()],
+            language: None,
+            value: value.into(),
+        }
+    }
+
+    /// Split a metadata key as produced by the demuxer.
+    pub fn parse(key: &str, value: &str) -> Self {
+        let (key, language) = match key.rfind('-') {
+            Some(index) if is_language(&key[index + 1..]) => {
+                (&key[..index], Some(key[index + 1..].to_owned()))
+            }
+            _ => (key, None),
+        };
+
+        let mut path = key.split('/').map(String::from).collect::<Vec<_>>();
+        let target = match path.len() {
+            1 => None,
+            _ => path[0].parse().ok(),
+        };
+
+        if target.is_some() {
+            path.remove(0);
+        }
+
+        Tag {
+            target,
+            path,
+            language,
+            value: value.to_owned(),
+        }
+    }
+
+    /// The innermost tag name.
+    pub fn name(&self) -> &str {
+        self.path.last().map_or("", |name| &name[..])
+    }
+
+    /// The metadata key this tag is stored under.
+    pub fn key(&self) -> String {
+        let mut key = self.path.join("/");
+
+        if let Some(target) = self.target {
+            key = format!("{}/{}", target, key);
+        }
+
+        if let Some(ref language) = self.language {
+            key.push('-');
+            key.push_str(language);
+        }
+
+        key
+    }
+}
+
+// ISO 639-2 codes, which is what Matroska stores in `TagLanguage`.
+fn is_language(value: &str) -> bool {
+    value.len() == 3 && value.bytes().all(|c| c.is_ascii_lowercase())
+}
+
+/// Split every entry of `metadata` into a tag.
+pub fn tags(metadata: &DictionaryRef) -> Vec<Tag> {
+    metadata
+        .iter()
+        .map(|(key, value)| Tag::parse(key, value))
+        .collect()
+}
+
+/// Flatten `tags` back into metadata, the way the demuxer would.
+pub fn metadata<'a, 'b, I: IntoIterator<Item = &'a Tag>>(tags: I) -> Dictionary<'b> {
+    tags.into_iter()
+        .map(|tag| (tag.key(), tag.value.clone()))
+        .collect()
+}
+
+/// The `Name` element of the track behind `stream`.
+pub fn track_name(stream: &Stream) -> Option<String> {
+    stream.metadata().get("title").map(String::from)
+}
+
+pub fn set_track_name(stream: &mut StreamMut, name: &str) {
+    stream.add_metadata("title", name);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let tag = Tag::parse("ALBUM/ARTIST/SORT_WITH-fre", "Beatles, The");
+
+        assert_eq!(tag.target, Some(Target::Album));
+        assert_eq!(tag.path, vec!["ARTIST", "SORT_WITH"]);
+        assert_eq!(tag.language, Some("fre".to_owned()));
+        assert_eq!(tag.name(), "SORT_WITH");
+        assert_eq!(tag.key(), "ALBUM/ARTIST/SORT_WITH-fre");
+
+        let tag = Tag::parse("BPS-eng", "1000");
+        assert_eq!(tag.target, None);
+        assert_eq!(tag.key(), "BPS-eng");
+
+        assert_eq!(Tag::parse("TRACK", "x").target, None);
+        assert_eq!(Tag::parse("creation-time", "x").language, None);
+        assert_eq!(Target::Shot.level(), 10);
+    }
+}
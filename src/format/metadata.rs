@@ -0,0 +1,114 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::context::Input;
+use ffi::*;
+use {Dictionary, DictionaryRef, Error};
+
+/// How `update` applied the changes.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Update {
+    /// The file already carried the requested tags and was left untouched.
+    Unchanged,
+
+    /// The file was copied with the new tags into a temporary file next to
+    /// it, which then replaced it. Packets are copied as is, so this costs
+    /// I/O but no decoding.
+    Remuxed,
+}
+
+/// Set (`Some`) or remove (`None`) container level tags of the file at
+/// `path`.
+///
+/// FFmpeg muxers always write files from scratch and cannot patch tags in
+/// place, not even where a container reserves padding for it, so any actual
+/// change goes through a stream copy. Streams, their tags and dispositions,
+/// and chapters are preserved.
+pub fn update<P: AsRef<Path>>(path: &P, changes: &[(&str, Option<&str>)]) -> Result<Update, Error> {
+    let path = path.as_ref();
+    let mut input = super::input(&path)?;
+
+    let metadata = apply(input.metadata(), changes);
+
+    if same(input.metadata(), &metadata) {
+        return Ok(Update::Unchanged);
+    }
+
+    let temporary = temporary(path);
+
+    match remux(&mut input, &temporary, metadata) {
+        Ok(()) => (),
+        Err(e) => {
+            let _ = fs::remove_file(&temporary);
+            return Err(e);
+        }
+    }
+
+    drop(input);
+
+    match fs::rename(&temporary, path) {
+        Ok(()) => Ok(Update::Remuxed),
+        Err(e) => {
+            let _ = fs::remove_file(&temporary);
+
+            Err(Error::from(e))
+        }
+    }
+}
+
+fn apply<'a>(metadata: DictionaryRef, changes: &[(&str, Option<&str>)]) -> Dictionary<'a> {
+    let mut result = metadata
+        .iter()
+        .filter(|&(key, _)| !changes.iter().any(|&(k, _)| k == key))
+        .collect::<Dictionary>();
+
+    for &(key, value) in changes {
+        if let Some(value) = value {
+            result.set(key, value);
+        }
+    }
+
+    result
+}
+
+fn same(metadata: DictionaryRef, other: &Dictionary) -> bool {
+    metadata.iter().count() == other.iter().count()
+        && metadata
+            .iter()
+            .all(|(key, value)| other.get(key) == Some(value))
+}
+
+// Same directory, so the final rename stays on one filesystem, and same
+// extension, so the muxer is guessed the same way.
+fn temporary(path: &Path) -> PathBuf {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or_default());
+
+    let mut temporary = path.with_file_name(name);
+    temporary.set_extension(match path.extension() {
+        Some(extension) => {
+            let mut value = OsString::from("tmp.");
+            value.push(extension);
+            value
+        }
+
+        None => OsString::from("tmp"),
+    });
+
+    temporary
+}
+
+fn remux(input: &mut Input, path: &Path, metadata: Dictionary) -> Result<(), Error> {
+    let mut output = super::output(&path)?;
+
+    // `remux::copy` writes the tags of the input, which is dropped after.
+    unsafe {
+        let ptr = input.as_mut_ptr();
+
+        av_dict_free(&mut (*ptr).metadata);
+        (*ptr).metadata = metadata.disown();
+    }
+
+    super::remux::copy(input, &mut output, |_| true)
+}
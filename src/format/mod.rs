@@ -7,6 +7,7 @@ pub mod stream;
 pub mod chapter;
 
 pub mod context;
+pub use self::context::seek::Flags as SeekFlags;
 pub use self::context::Context;
 
 pub mod format;
@@ -15,6 +16,50 @@ pub use self::format::{list, Input, Output};
 
 pub mod network;
 
+pub mod http;
+
+pub mod options;
+pub use self::options::{OutputOptions, RtmpOptions, RtspOptions, SrtOptions};
+
+pub mod describe;
+pub use self::describe::Description;
+
+pub mod demuxer;
+pub use self::demuxer::Demuxer;
+
+pub mod io;
+
+pub mod image;
+
+pub mod preview;
+
+pub mod probe;
+pub use self::probe::{probe, Probe};
+
+pub mod follow;
+
+pub mod index;
+
+pub mod fingerprint;
+
+pub mod remux;
+pub use self::remux::copy as remux;
+
+pub mod report;
+
+pub mod rtp;
+
+#[cfg(feature = "async")]
+pub mod asynchronous;
+
+pub mod matroska;
+
+pub mod metadata;
+
+pub mod segment;
+
+pub mod side_data;
+
 use std::ffi::{CStr, CString};
 use std::path::Path;
 use std::ptr;
@@ -189,21 +234,50 @@ pub fn input_with_dictionary<P: AsRef<Path>>(
     }
 }
 
+/// Open an input with `closure` as interrupt callback, already polled while
+/// opening and probing. See `Context::set_interrupt`.
 pub fn input_with_interrupt<P: AsRef<Path>, F>(
     path: &P,
     closure: F,
 ) -> Result<context::Input, Error>
 where
-    F: FnMut() -> bool,
+    F: FnMut() -> bool + Send + 'static,
 {
     unsafe {
+        let mut closure: context::destructor::Interrupt = Box::new(Box::new(closure));
         let mut ps = avformat_alloc_context();
         let path = from_path(path);
-        (*ps).interrupt_callback = interrupt::new(Box::new(closure)).interrupt;
+        (*ps).interrupt_callback = interrupt::borrowed(&mut *closure).interrupt;
 
         match avformat_open_input(&mut ps, path.as_ptr(), ptr::null_mut(), ptr::null_mut()) {
             0 => match avformat_find_stream_info(ps, ptr::null_mut()) {
-                r if r >= 0 => Ok(context::Input::wrap(ps)),
+                r if r >= 0 => {
+                    let mut input = context::Input::wrap(ps);
+                    input.keep_interrupt(closure);
+
+                    Ok(input)
+                }
+                e => {
+                    avformat_close_input(&mut ps);
+                    Err(Error::from(e))
+                }
+            },
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
+/// Open an input reading through custom I/O instead of a path.
+pub fn input_from(mut io: io::Io) -> Result<context::Input, Error> {
+    unsafe {
+        let mut ps = avformat_alloc_context();
+        (*ps).pb = io.as_mut_ptr();
+        (*ps).flags |= AVFMT_FLAG_CUSTOM_IO;
+
+        match avformat_open_input(&mut ps, ptr::null(), ptr::null_mut(), ptr::null_mut()) {
+            0 => match avformat_find_stream_info(ps, ptr::null_mut()) {
+                r if r >= 0 => Ok(context::Input::wrap_with_io(ps, io)),
                 e => {
                     avformat_close_input(&mut ps);
                     Err(Error::from(e))
@@ -263,6 +337,50 @@ pub fn output_with<P: AsRef<Path>>(
     }
 }
 
+/// Open an output with `closure` as interrupt callback, already polled while
+/// opening. See `Context::set_interrupt`.
+pub fn output_with_interrupt<P: AsRef<Path>, F>(
+    path: &P,
+    closure: F,
+) -> Result<context::Output, Error>
+where
+    F: FnMut() -> bool + Send + 'static,
+{
+    unsafe {
+        let mut closure: context::destructor::Interrupt = Box::new(Box::new(closure));
+        let mut ps = ptr::null_mut();
+        let path = from_path(path);
+
+        match avformat_alloc_output_context2(&mut ps, ptr::null_mut(), ptr::null(), path.as_ptr()) {
+            0 => {
+                (*ps).interrupt_callback = interrupt::borrowed(&mut *closure).interrupt;
+
+                match avio_open2(
+                    &mut (*ps).pb,
+                    path.as_ptr(),
+                    AVIO_FLAG_WRITE,
+                    &(*ps).interrupt_callback,
+                    ptr::null_mut(),
+                ) {
+                    0 => {
+                        let mut output = context::Output::wrap(ps);
+                        output.keep_interrupt(closure);
+
+                        Ok(output)
+                    }
+
+                    e => {
+                        avformat_free_context(ps);
+                        Err(Error::from(e))
+                    }
+                }
+            }
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
 pub fn output_as<P: AsRef<Path>>(path: &P, format: &str) -> Result<context::Output, Error> {
     unsafe {
         let mut ps = ptr::null_mut();
@@ -285,6 +403,27 @@ pub fn output_as<P: AsRef<Path>>(path: &P, format: &str) -> Result<context::Outp
     }
 }
 
+/// Open an output in the named format writing through custom I/O instead
+/// of a path.
+pub fn output_to(mut io: io::Io, format: &str) -> Result<context::Output, Error> {
+    unsafe {
+        let mut ps = ptr::null_mut();
+        let format = CString::new(format).unwrap();
+
+        match avformat_alloc_output_context2(&mut ps, ptr::null_mut(), format.as_ptr(), ptr::null())
+        {
+            0 => {
+                (*ps).pb = io.as_mut_ptr();
+                (*ps).flags |= AVFMT_FLAG_CUSTOM_IO;
+
+                Ok(context::Output::wrap_with_io(ps, io))
+            }
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
 pub fn output_as_with<P: AsRef<Path>>(
     path: &P,
     format: &str,
@@ -1,4 +1,9 @@
+use std::ffi::CString;
+use std::ptr;
+
+use super::context;
 use ffi::*;
+use {Dictionary, Error};
 
 pub fn init() {
     unsafe {
@@ -11,3 +16,153 @@ pub fn deinit() {
         avformat_network_deinit();
     }
 }
+
+/// Wait for a single peer to connect to `url` and open what it publishes.
+///
+/// For protocols that only ever serve one connection, such as
+/// `rtmp://0.0.0.0/live/key` or `rtsp://0.0.0.0:8554/live`, which are put
+/// in listen mode through their own options. Blocks until the peer connects.
+pub fn listen(url: &str, options: Dictionary) -> Result<context::Input, Error> {
+    let mut options = options;
+
+    if url.starts_with("rtsp:") {
+        options.set("rtsp_flags", "listen");
+    } else {
+        options.set("listen", "1");
+    }
+
+    super::input_with_dictionary(&url, options)
+}
+
+/// A listening socket accepting any number of connections, for the
+/// protocols implementing `avio_accept`: `tcp`, `unix`, `http` and
+/// `https`.
+pub struct Server {
+    ptr: *mut AVIOContext,
+}
+
+unsafe impl Send for Server {}
+
+impl Server {
+    pub fn bind(url: &str) -> Result<Self, Error> {
+        Server::bind_with(url, Dictionary::new())
+    }
+
+    pub fn bind_with(url: &str, options: Dictionary) -> Result<Self, Error> {
+        let mut options = options;
+        options.set("listen", "2");
+
+        unsafe {
+            let url = CString::new(url).unwrap();
+            let mut ptr = ptr::null_mut();
+            let mut opts = options.disown();
+
+            let res = avio_open2(
+                &mut ptr,
+                url.as_ptr(),
+                AVIO_FLAG_READ,
+                ptr::null(),
+                &mut opts,
+            );
+
+            Dictionary::own(opts);
+
+            match res {
+                0 => Ok(Server { ptr }),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVIOContext {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVIOContext {
+        self.ptr
+    }
+
+    /// Wait for the next connection and complete its protocol handshake.
+    pub fn accept(&mut self) -> Result<Client, Error> {
+        unsafe {
+            let mut ptr = ptr::null_mut();
+
+            match avio_accept(self.ptr, &mut ptr) {
+                0 => (),
+                e => return Err(Error::from(e)),
+            }
+
+            let client = Client { ptr };
+
+            loop {
+                match avio_handshake(client.ptr) {
+                    0 => return Ok(client),
+                    e if e < 0 => return Err(Error::from(e)),
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        unsafe {
+            avio_closep(&mut self.ptr);
+        }
+    }
+}
+
+/// A connection accepted by a `Server`.
+pub struct Client {
+    ptr: *mut AVIOContext,
+}
+
+unsafe impl Send for Client {}
+
+impl Client {
+    pub unsafe fn as_ptr(&self) -> *const AVIOContext {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVIOContext {
+        self.ptr
+    }
+
+    /// Probe what the peer sends and open it as an input, which then owns
+    /// the connection.
+    pub fn input(mut self) -> Result<context::Input, Error> {
+        unsafe {
+            let mut ps = avformat_alloc_context();
+            (*ps).pb = self.ptr;
+
+            match avformat_open_input(&mut ps, ptr::null(), ptr::null_mut(), ptr::null_mut()) {
+                0 => (),
+                e => return Err(Error::from(e)),
+            }
+
+            // Opening flags a preset `pb` as custom I/O, clear it so that
+            // closing the input closes the connection too.
+            (*ps).flags &= !AVFMT_FLAG_CUSTOM_IO;
+            self.ptr = ptr::null_mut();
+
+            match avformat_find_stream_info(ps, ptr::null_mut()) {
+                r if r >= 0 => Ok(context::Input::wrap(ps)),
+                e => {
+                    avformat_close_input(&mut ps);
+                    Err(Error::from(e))
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.ptr.is_null() {
+                avio_closep(&mut self.ptr);
+            }
+        }
+    }
+}
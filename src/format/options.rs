@@ -0,0 +1,512 @@
+//! Typed muxer options, for the `mov`/`mp4`, `hls` and `dash` muxers, and
+//! protocol options for opening `rtsp`, `srt` and `rtmp` URLs.
+
+use std::time::Duration;
+
+use Dictionary;
+
+bitflags! {
+    /// Flags of the `movflags` option of the `mov` family of muxers.
+    #[derive(Default)]
+    pub struct MovFlags: u32 {
+        const EMPTY_MOOV           = 1 << 0;
+        const FRAG_KEYFRAME        = 1 << 1;
+        const SEPARATE_MOOF        = 1 << 2;
+        const FRAG_CUSTOM          = 1 << 3;
+        const ISML                 = 1 << 4;
+        const FASTSTART            = 1 << 5;
+        const OMIT_TFHD_OFFSET     = 1 << 6;
+        const DISABLE_CHPL         = 1 << 7;
+        const DEFAULT_BASE_MOOF    = 1 << 8;
+        const DASH                 = 1 << 9;
+        const FRAG_DISCONT         = 1 << 10;
+        const DELAY_MOOV           = 1 << 11;
+        const GLOBAL_SIDX          = 1 << 12;
+        const WRITE_COLR           = 1 << 13;
+        const WRITE_GAMA           = 1 << 14;
+        const USE_METADATA_TAGS    = 1 << 15;
+        const SKIP_TRAILER         = 1 << 16;
+        const NEGATIVE_CTS_OFFSETS = 1 << 17;
+    }
+}
+
+const MOV_FLAGS: &[(MovFlags, &str)] = &[
+    (MovFlags::EMPTY_MOOV, "empty_moov"),
+    (MovFlags::FRAG_KEYFRAME, "frag_keyframe"),
+    (MovFlags::SEPARATE_MOOF, "separate_moof"),
+    (MovFlags::FRAG_CUSTOM, "frag_custom"),
+    (MovFlags::ISML, "isml"),
+    (MovFlags::FASTSTART, "faststart"),
+    (MovFlags::OMIT_TFHD_OFFSET, "omit_tfhd_offset"),
+    (MovFlags::DISABLE_CHPL, "disable_chpl"),
+    (MovFlags::DEFAULT_BASE_MOOF, "default_base_moof"),
+    (MovFlags::DASH, "dash"),
+    (MovFlags::FRAG_DISCONT, "frag_discont"),
+    (MovFlags::DELAY_MOOV, "delay_moov"),
+    (MovFlags::GLOBAL_SIDX, "global_sidx"),
+    (MovFlags::WRITE_COLR, "write_colr"),
+    (MovFlags::WRITE_GAMA, "write_gama"),
+    (MovFlags::USE_METADATA_TAGS, "use_metadata_tags"),
+    (MovFlags::SKIP_TRAILER, "skip_trailer"),
+    (MovFlags::NEGATIVE_CTS_OFFSETS, "negative_cts_offsets"),
+];
+
+impl MovFlags {
+    /// The value of the `movflags` option, as in `+frag_keyframe+empty_moov`.
+    pub fn to_option(&self) -> String {
+        let mut value = String::new();
+
+        for &(flag, name) in MOV_FLAGS {
+            if self.contains(flag) {
+                value.push('+');
+                value.push_str(name);
+            }
+        }
+
+        value
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum HlsSegment {
+    MpegTs,
+    Fmp4,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum PlaylistType {
+    /// Segments are only ever appended to the playlist.
+    Event,
+    /// The playlist is complete once written.
+    Vod,
+}
+
+/// Options of the `hls` muxer.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Hls {
+    /// Target segment length in seconds, segments being cut on keyframes.
+    pub time: f64,
+
+    /// Number of segments kept in the playlist, 0 to keep them all.
+    pub list_size: u32,
+
+    pub segment: HlsSegment,
+
+    /// Pattern of segment file names, such as `segment_%03d.ts`.
+    pub segment_filename: Option<String>,
+
+    pub playlist_type: Option<PlaylistType>,
+
+    /// Delete segments once they leave the playlist.
+    pub delete_segments: bool,
+
+    /// Mark every segment as starting with a keyframe.
+    pub independent_segments: bool,
+}
+
+impl Default for Hls {
+    fn default() -> Self {
+        Hls {
+            time: 2.0,
+            list_size: 5,
+            segment: HlsSegment::MpegTs,
+            segment_filename: None,
+            playlist_type: None,
+            delete_segments: false,
+            independent_segments: false,
+        }
+    }
+}
+
+/// Options of the `dash` muxer.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Dash {
+    /// Target segment length in seconds, segments being cut on keyframes.
+    pub segment_duration: f64,
+
+    /// Number of segments kept in the manifest, 0 to keep them all.
+    pub window_size: u32,
+
+    /// Number of segments kept on disk past the window before removal.
+    pub extra_window_size: u32,
+
+    /// Write each frame as its own fragment for low latency.
+    pub streaming: bool,
+
+    /// Describe segments with `SegmentTemplate` and `SegmentTimeline`.
+    pub use_template: bool,
+    pub use_timeline: bool,
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        Dash {
+            segment_duration: 5.0,
+            window_size: 0,
+            extra_window_size: 5,
+            streaming: false,
+            use_template: true,
+            use_timeline: true,
+        }
+    }
+}
+
+/// Muxer options to pass to `Output::write_header_with`, built from typed
+/// helpers instead of raw strings.
+///
+/// Options a muxer does not know are left in the dictionary
+/// `write_header_with` returns.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct OutputOptions {
+    mov_flags: MovFlags,
+    entries: Vec<(String, String)>,
+}
+
+impl OutputOptions {
+    pub fn new() -> Self {
+        OutputOptions::default()
+    }
+
+    /// Set a raw option, replacing a previous value.
+    pub fn set(mut self, key: &str, value: &str) -> Self {
+        self.entries.retain(|&(ref k, _)| k != key);
+        self.entries.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Add `flags` to the `movflags` option.
+    pub fn mov_flags(mut self, flags: MovFlags) -> Self {
+        self.mov_flags |= flags;
+        self
+    }
+
+    /// Fragmented MP4, with a fragment starting on every keyframe after an
+    /// empty `moov`, as needed to write to a non seekable output.
+    pub fn fragmented(self) -> Self {
+        self.mov_flags(MovFlags::FRAG_KEYFRAME | MovFlags::EMPTY_MOOV | MovFlags::DEFAULT_BASE_MOOF)
+    }
+
+    /// Move the `moov` before the media data once done, so playback can
+    /// start before the file is downloaded. This takes a second pass over
+    /// the file, which must be seekable.
+    pub fn faststart(self) -> Self {
+        self.mov_flags(MovFlags::FASTSTART)
+    }
+
+    pub fn hls(self, hls: &Hls) -> Self {
+        let mut flags = Vec::new();
+
+        if hls.delete_segments {
+            flags.push("delete_segments");
+        }
+
+        if hls.independent_segments {
+            flags.push("independent_segments");
+        }
+
+        let mut options = self
+            .set("hls_time", &hls.time.to_string())
+            .set("hls_list_size", &hls.list_size.to_string())
+            .set(
+                "hls_segment_type",
+                match hls.segment {
+                    HlsSegment::MpegTs => "mpegts",
+                    HlsSegment::Fmp4 => "fmp4",
+                },
+            );
+
+        if let Some(ref filename) = hls.segment_filename {
+            options = options.set("hls_segment_filename", filename);
+        }
+
+        if let Some(kind) = hls.playlist_type {
+            options = options.set(
+                "hls_playlist_type",
+                match kind {
+                    PlaylistType::Event => "event",
+                    PlaylistType::Vod => "vod",
+                },
+            );
+        }
+
+        if !flags.is_empty() {
+            options = options.set("hls_flags", &flags.join("+"));
+        }
+
+        options
+    }
+
+    pub fn dash(self, dash: &Dash) -> Self {
+        self.set("seg_duration", &dash.segment_duration.to_string())
+            .set("window_size", &dash.window_size.to_string())
+            .set("extra_window_size", &dash.extra_window_size.to_string())
+            .set("streaming", flag(dash.streaming))
+            .set("use_template", flag(dash.use_template))
+            .set("use_timeline", flag(dash.use_timeline))
+    }
+
+    pub fn to_dictionary<'a>(&self) -> Dictionary<'a> {
+        let mut dictionary = self.entries.iter().collect::<Dictionary>();
+
+        if !self.mov_flags.is_empty() {
+            dictionary.set("movflags", &self.mov_flags.to_option());
+        }
+
+        dictionary
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum RtspTransport {
+    Udp,
+    Tcp,
+    UdpMulticast,
+    /// Tunneled through HTTP, to get past proxies.
+    Http,
+}
+
+impl RtspTransport {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            RtspTransport::Udp => "udp",
+            RtspTransport::Tcp => "tcp",
+            RtspTransport::UdpMulticast => "udp_multicast",
+            RtspTransport::Http => "http",
+        }
+    }
+}
+
+/// Options of the `rtsp` demuxer, for `input_with_dictionary`.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct RtspOptions {
+    /// Transports to try, in order, all but HTTP by default.
+    pub transports: Vec<RtspTransport>,
+
+    /// Try TCP first when the server offers it.
+    pub prefer_tcp: bool,
+
+    /// Socket timeout, after which reads fail instead of blocking.
+    pub timeout: Option<Duration>,
+
+    /// Packets buffered to reorder UDP input, 0 disabling reordering.
+    pub reorder_queue_size: Option<u32>,
+}
+
+impl RtspOptions {
+    pub fn to_dictionary<'a>(&self) -> Dictionary<'a> {
+        let mut dictionary = Dictionary::new();
+
+        if !self.transports.is_empty() {
+            let transports = self
+                .transports
+                .iter()
+                .map(RtspTransport::name)
+                .collect::<Vec<_>>();
+
+            dictionary.set("rtsp_transport", &transports.join("+"));
+        }
+
+        if self.prefer_tcp {
+            dictionary.set("rtsp_flags", "prefer_tcp");
+        }
+
+        if let Some(timeout) = self.timeout {
+            dictionary.set("stimeout", &microseconds(timeout).to_string());
+        }
+
+        if let Some(size) = self.reorder_queue_size {
+            dictionary.set("reorder_queue_size", &size.to_string());
+        }
+
+        dictionary
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum SrtMode {
+    Caller,
+    Listener,
+    Rendezvous,
+}
+
+impl SrtMode {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            SrtMode::Caller => "caller",
+            SrtMode::Listener => "listener",
+            SrtMode::Rendezvous => "rendezvous",
+        }
+    }
+}
+
+/// Options of the `srt` protocol, for opening inputs and outputs.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct SrtOptions {
+    /// Caller by default.
+    pub mode: Option<SrtMode>,
+
+    /// How long packets are buffered to recover losses, on both ends, 120
+    /// milliseconds by default.
+    pub latency: Option<Duration>,
+
+    /// Encryption passphrase, from 10 to 79 characters, which libsrt checks
+    /// when connecting.
+    pub passphrase: Option<String>,
+
+    /// Encryption key length in bytes: 16, 24 or 32.
+    pub key_length: Option<u32>,
+
+    /// Stream identifier sent to listeners, to pick a resource or route the
+    /// connection.
+    pub stream_id: Option<String>,
+
+    pub connect_timeout: Option<Duration>,
+}
+
+impl SrtOptions {
+    pub fn to_dictionary<'a>(&self) -> Dictionary<'a> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(mode) = self.mode {
+            dictionary.set("mode", mode.name());
+        }
+
+        if let Some(latency) = self.latency {
+            dictionary.set("latency", &microseconds(latency).to_string());
+        }
+
+        if let Some(ref passphrase) = self.passphrase {
+            dictionary.set("passphrase", passphrase);
+        }
+
+        if let Some(key_length) = self.key_length {
+            dictionary.set("pbkeylen", &key_length.to_string());
+        }
+
+        if let Some(ref stream_id) = self.stream_id {
+            dictionary.set("streamid", stream_id);
+        }
+
+        if let Some(timeout) = self.connect_timeout {
+            dictionary.set("connect_timeout", &milliseconds(timeout).to_string());
+        }
+
+        dictionary
+    }
+}
+
+/// What the `rtmp` protocol asks the server to play.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum RtmpLive {
+    /// A live stream, falling back to a recorded one.
+    Any,
+    Live,
+    Recorded,
+}
+
+impl RtmpLive {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            RtmpLive::Any => "any",
+            RtmpLive::Live => "live",
+            RtmpLive::Recorded => "recorded",
+        }
+    }
+}
+
+/// Options of the `rtmp` protocol, for opening inputs and outputs.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct RtmpOptions {
+    pub live: Option<RtmpLive>,
+
+    /// Client buffer the server is told about, 3 seconds by default.
+    pub buffer: Option<Duration>,
+
+    /// Application name, when it cannot be told apart from the stream name
+    /// in the URL.
+    pub app: Option<String>,
+
+    /// Stream name, likewise.
+    pub playpath: Option<String>,
+}
+
+impl RtmpOptions {
+    pub fn to_dictionary<'a>(&self) -> Dictionary<'a> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(live) = self.live {
+            dictionary.set("rtmp_live", live.name());
+        }
+
+        if let Some(buffer) = self.buffer {
+            dictionary.set("rtmp_buffer", &milliseconds(buffer).to_string());
+        }
+
+        if let Some(ref app) = self.app {
+            dictionary.set("rtmp_app", app);
+        }
+
+        if let Some(ref playpath) = self.playpath {
+            dictionary.set("rtmp_playpath", playpath);
+        }
+
+        dictionary
+    }
+}
+
+fn microseconds(value: Duration) -> u64 {
+    value.as_secs() * 1_000_000 + u64::from(value.subsec_micros())
+}
+
+fn milliseconds(value: Duration) -> u64 {
+    value.as_secs() * 1_000 + u64::from(value.subsec_millis())
+}
+
+fn flag(value: bool) -> &'static str {
+    if value {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options() {
+        let options = OutputOptions::new()
+            .fragmented()
+            .set("brand", "isom")
+            .set("brand", "mp42");
+
+        assert_eq!(
+            options.mov_flags.to_option(),
+            "+empty_moov+frag_keyframe+default_base_moof"
+        );
+        assert_eq!(options.entries, vec![("brand".into(), "mp42".into())]);
+
+        let options = OutputOptions::new().hls(&Hls {
+            delete_segments: true,
+            independent_segments: true,
+            ..Hls::default()
+        });
+
+        assert!(options.entries.contains(&(
+            "hls_flags".into(),
+            "delete_segments+independent_segments".into()
+        )));
+
+        let options = RtspOptions {
+            transports: vec![RtspTransport::Tcp, RtspTransport::Udp],
+            timeout: Some(Duration::from_millis(2500)),
+            ..RtspOptions::default()
+        }
+        .to_dictionary();
+
+        assert_eq!(options.get("rtsp_transport"), Some("tcp+udp"));
+        assert_eq!(options.get("stimeout"), Some("2500000"));
+        assert_eq!(options.get("rtsp_flags"), None);
+    }
+}
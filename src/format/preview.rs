@@ -0,0 +1,112 @@
+use super::context::Input;
+use super::stream::Disposition;
+use ffi::*;
+use {codec, decoder, frame, Error, Packet, Stream};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Kind {
+    /// A single picture attached to the file, such as ID3v2 `APIC` cover
+    /// art, an MP4 `covr` atom or a Matroska image attachment.
+    Cover,
+
+    /// A sparse track of thumbnails, as QuickTime chapter images are, whose
+    /// pictures come through the packets of the stream.
+    Timed,
+}
+
+/// An embedded picture stream, which is not part of the presentation.
+pub struct Preview<'a> {
+    stream: Stream<'a>,
+    kind: Kind,
+}
+
+impl<'a> Preview<'a> {
+    pub fn stream(&self) -> &Stream<'a> {
+        &self.stream
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// The picture of a `Kind::Cover` preview, still encoded.
+    pub fn packet(&self) -> Option<Packet> {
+        unsafe {
+            let attached = &(*self.stream.as_ptr()).attached_pic;
+
+            if attached.size == 0 {
+                return None;
+            }
+
+            let mut packet = Packet::empty();
+
+            match av_packet_ref(packet.as_mut_ptr(), attached) {
+                0 => Some(packet),
+                _ => None,
+            }
+        }
+    }
+
+    /// Decode the picture of a `Kind::Cover` preview.
+    pub fn decode(&self) -> Result<frame::Video, Error> {
+        match self.packet() {
+            Some(packet) => self.decode_packet(&packet),
+            None => Err(Error::InvalidData),
+        }
+    }
+
+    /// Decode a picture of the preview, such as a packet of a `Kind::Timed`
+    /// preview read from the input.
+    pub fn decode_packet(&self, packet: &Packet) -> Result<frame::Video, Error> {
+        let mut context = codec::Context::new();
+        context.set_parameters(self.stream.parameters())?;
+
+        let codec = decoder::find(context.id()).ok_or(Error::DecoderNotFound)?;
+        let mut decoder = context.decoder().open_as(codec)?.video()?;
+
+        decoder.send_packet(packet)?;
+        decoder.send_eof()?;
+
+        let mut frame = frame::Video::empty();
+        decoder.receive_frame(&mut frame)?;
+
+        Ok(frame)
+    }
+}
+
+/// Iterator over the preview streams of an input, see `Input::previews`.
+pub struct PreviewIter<'a> {
+    streams: super::context::common::StreamIter<'a>,
+}
+
+impl<'a> PreviewIter<'a> {
+    pub fn new(input: &'a Input) -> Self {
+        PreviewIter {
+            streams: input.streams(),
+        }
+    }
+}
+
+impl<'a> Iterator for PreviewIter<'a> {
+    type Item = Preview<'a>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        for stream in &mut self.streams {
+            let disposition = stream.disposition();
+
+            if !disposition.contains(Disposition::ATTACHED_PIC) {
+                continue;
+            }
+
+            let kind = if disposition.contains(Disposition::TIMED_THUMBNAILS) {
+                Kind::Timed
+            } else {
+                Kind::Cover
+            };
+
+            return Some(Preview { stream, kind });
+        }
+
+        None
+    }
+}
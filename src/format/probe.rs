@@ -0,0 +1,117 @@
+use std::ffi::CString;
+use std::ptr;
+
+use super::io::Io;
+use super::Input;
+use ffi::*;
+use libc::c_int;
+use Error;
+
+/// Identify the container of `data`, the first bytes of a file or stream,
+/// from its contents alone.
+pub fn probe(data: &[u8]) -> Option<Input> {
+    Probe::new(data).run().map(|(format, _)| format)
+}
+
+/// Identify a container from a buffer, with optional hints.
+///
+/// Demuxers score how likely the data is theirs, up to `MAX_SCORE`. A file
+/// name lets formats that cannot be recognized from a few bytes, such as
+/// raw streams, match on their extension.
+#[derive(Clone, Copy, Debug)]
+pub struct Probe<'a> {
+    data: &'a [u8],
+    filename: Option<&'a str>,
+    mime_type: Option<&'a str>,
+    opened: bool,
+}
+
+/// Highest probe score, for data a demuxer is certain about.
+pub const MAX_SCORE: i32 = AVPROBE_SCORE_MAX as i32;
+
+impl<'a> Probe<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Probe {
+            data,
+            filename: None,
+            mime_type: None,
+            opened: true,
+        }
+    }
+
+    pub fn filename(mut self, value: &'a str) -> Self {
+        self.filename = Some(value);
+        self
+    }
+
+    pub fn mime_type(mut self, value: &'a str) -> Self {
+        self.mime_type = Some(value);
+        self
+    }
+
+    /// Also consider formats that do not read from a byte stream, such as
+    /// devices or image sequences, matching on the file name only.
+    pub fn unopened(mut self) -> Self {
+        self.opened = false;
+        self
+    }
+
+    /// The best matching format and its score, if any.
+    pub fn run(&self) -> Option<(Input, i32)> {
+        // Probing reads past the data, which must be followed by zeroed
+        // padding.
+        let mut buffer = Vec::with_capacity(self.data.len() + AVPROBE_PADDING_SIZE as usize);
+        buffer.extend_from_slice(self.data);
+        buffer.resize(self.data.len() + AVPROBE_PADDING_SIZE as usize, 0);
+
+        let filename = CString::new(self.filename.unwrap_or("")).unwrap();
+        let mime_type = self.mime_type.map(|value| CString::new(value).unwrap());
+
+        unsafe {
+            let mut data = AVProbeData {
+                filename: filename.as_ptr(),
+                buf: buffer.as_mut_ptr(),
+                buf_size: self.data.len() as c_int,
+                mime_type: mime_type
+                    .as_ref()
+                    .map_or(ptr::null(), |value| value.as_ptr()),
+            };
+            let mut score = 0;
+
+            let ptr = av_probe_input_format2(&mut data, self.opened as c_int, &mut score);
+
+            if ptr.is_null() {
+                None
+            } else {
+                Some((Input::wrap(ptr as *mut _), score))
+            }
+        }
+    }
+}
+
+/// Identify the container read by `io`, reading at most `max_size` bytes
+/// or the FFmpeg default when 0.
+///
+/// The bytes read are kept buffered, so `io` can still be opened with
+/// `format::input_from` afterwards.
+pub fn probe_io(io: &mut Io, filename: Option<&str>, max_size: u32) -> Result<(Input, i32), Error> {
+    let filename = filename.map(|value| CString::new(value).unwrap());
+
+    unsafe {
+        let mut ptr = ptr::null_mut();
+
+        match av_probe_input_buffer2(
+            io.as_mut_ptr(),
+            &mut ptr,
+            filename
+                .as_ref()
+                .map_or(ptr::null(), |value| value.as_ptr()),
+            ptr::null_mut(),
+            0,
+            max_size,
+        ) {
+            score if score >= 0 => Ok((Input::wrap(ptr as *mut _), score)),
+            e => Err(Error::from(e)),
+        }
+    }
+}
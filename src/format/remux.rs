@@ -0,0 +1,326 @@
+use super::context::{Input, Output};
+use super::side_data::Policy as SideDataPolicy;
+use cancel::{Policy, Token};
+use ffi::*;
+use rescale::{Delta, TIME_BASE};
+use {codec, encoder, media, Error, Rational, Rescale, Stream};
+
+/// Copy the streams of `input` for which `keep` returns `true` into
+/// `output`, in order and without re-encoding, writing the output header and
+/// trailer.
+///
+/// Stream parameters, dispositions and metadata are carried over along with
+/// the container metadata and chapters, and timestamps are rescaled to the
+/// output time bases. Codec tags are reset so that the output muxer picks
+/// its own.
+pub fn copy<F>(input: &mut Input, output: &mut Output, keep: F) -> Result<(), Error>
+where
+    F: FnMut(&Stream) -> bool,
+{
+    copy_with(input, output, keep, &SideDataPolicy::keep_all())
+}
+
+/// Like `copy`, with the stream and packet side data going through `policy`.
+pub fn copy_with<F>(
+    input: &mut Input,
+    output: &mut Output,
+    mut keep: F,
+    policy: &SideDataPolicy,
+) -> Result<(), Error>
+where
+    F: FnMut(&Stream) -> bool,
+{
+    let mut mapping = vec![None; input.nb_streams() as _];
+
+    for ist in input.streams() {
+        if !keep(&ist) {
+            continue;
+        }
+
+        let mut ost = output.add_stream(encoder::find(codec::Id::None))?;
+
+        ost.set_parameters(ist.parameters());
+        ost.set_time_base(ist.time_base());
+        ost.set_disposition(ist.disposition());
+        ost.set_metadata(ist.metadata().to_owned());
+        policy.streams(&ist, &mut ost)?;
+
+        unsafe {
+            (*ost.parameters().as_mut_ptr()).codec_tag = 0;
+        }
+
+        mapping[ist.index()] = Some(ost.index());
+    }
+
+    for chapter in input.chapters() {
+        let mut copy = output.add_chapter(
+            chapter.id(),
+            chapter.time_base(),
+            chapter.start(),
+            chapter.end(),
+            chapter.metadata().get("title").unwrap_or(""),
+        )?;
+
+        for (key, value) in chapter.metadata().iter() {
+            copy.set_metadata(key, value);
+        }
+    }
+
+    output.set_metadata(input.metadata().to_owned());
+    output.write_header()?;
+
+    let time_bases = output
+        .streams()
+        .map(|stream| stream.time_base())
+        .collect::<Vec<Rational>>();
+
+    for (stream, mut packet) in input.packets() {
+        let index = match mapping[stream.index()] {
+            Some(index) => index,
+            None => continue,
+        };
+
+        policy.packets(&mut packet)?;
+        packet.rescale_ts(stream.time_base(), time_bases[index]);
+        packet.set_position(-1);
+        packet.set_stream(index);
+        packet.write_interleaved(output)?;
+    }
+
+    output.write_trailer()
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum SeekMode {
+    /// Start the clip on the last video keyframe at or before the requested
+    /// start, which becomes timestamp zero.
+    Keyframe,
+
+    /// Keep the GOP leading up to the requested start with negative
+    /// timestamps, so players and edit-list aware muxers present the clip
+    /// from exactly the requested start.
+    Accurate,
+}
+
+/// Copy the `[start, start + duration)` range of `input` into `output`
+/// without re-encoding.
+///
+/// `start` and `duration` are in `AV_TIME_BASE` units, `start` being relative
+/// to the start time of `input`. Audio, video and subtitle streams are mapped
+/// in order; the output header and trailer are written.
+///
+/// Video always begins on a keyframe, while audio and subtitle packets are
+/// only dropped when they end before the clip start. Packets presented at or
+/// after the clip end are dropped, and reading stops once every audio and
+/// video stream has passed it.
+pub fn extract(
+    input: &mut Input,
+    output: &mut Output,
+    start: i64,
+    duration: i64,
+    mode: SeekMode,
+) -> Result<(), Error> {
+    extract_cancellable(
+        input,
+        output,
+        start,
+        duration,
+        mode,
+        &Token::new(),
+        Policy::Finalize,
+    )
+}
+
+/// Like `extract`, checking `token` before every packet.
+///
+/// Once cancelled, `Error::Exit` is returned after the trailer is written if
+/// `policy` is `Policy::Finalize`, or right away if it is `Policy::Discard`.
+pub fn extract_cancellable(
+    input: &mut Input,
+    output: &mut Output,
+    start: i64,
+    duration: i64,
+    mode: SeekMode,
+    token: &Token,
+    policy: Policy,
+) -> Result<(), Error> {
+    let mut origin = match input.start_time() {
+        AV_NOPTS_VALUE => start,
+        value => value + start,
+    };
+
+    let mut mapping = vec![None; input.nb_streams() as _];
+    let mut rates = vec![0; mapping.len()];
+    let mut video = None;
+
+    for ist in input.streams() {
+        let medium = ist.parameters().medium();
+
+        match medium {
+            media::Type::Audio | media::Type::Video | media::Type::Subtitle => (),
+            _ => continue,
+        }
+
+        if medium == media::Type::Video && video.is_none() {
+            video = Some(ist.index());
+        }
+
+        let mut ost = output.add_stream(encoder::find(codec::Id::None))?;
+        ost.set_parameters(ist.parameters());
+
+        unsafe {
+            (*ost.parameters().as_mut_ptr()).codec_tag = 0;
+
+            if medium == media::Type::Audio {
+                rates[ist.index()] = (*ist.parameters().as_ptr()).sample_rate;
+            }
+        }
+
+        mapping[ist.index()] = Some((ost.index(), medium));
+    }
+
+    // The clip ends `duration` after the requested start, even when it
+    // starts earlier, on the keyframe before it.
+    let end = origin + duration;
+
+    if let (SeekMode::Keyframe, Some(index)) = (mode, video) {
+        origin = keyframe(input, index, origin)?;
+    }
+
+    input.seek(origin, ..origin)?;
+
+    output.set_metadata(input.metadata().to_owned());
+    output.write_header()?;
+
+    let time_bases = output
+        .streams()
+        .map(|stream| stream.time_base())
+        .collect::<Vec<Rational>>();
+
+    // Audio timestamps are rescaled sample accurately, so that consecutive
+    // packets stay contiguous when the output time base is coarser.
+    let mut deltas = input
+        .streams()
+        .map(|stream| match mapping[stream.index()] {
+            Some((ost_index, media::Type::Audio)) if rates[stream.index()] > 0 => Some(Delta::new(
+                stream.time_base(),
+                time_bases[ost_index],
+                rates[stream.index()] as u32,
+            )),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut started = vec![false; mapping.len()];
+    let mut finished = vec![false; mapping.len()];
+    let mut remaining = mapping
+        .iter()
+        .filter(|m| match **m {
+            Some((_, media::Type::Subtitle)) | None => false,
+            _ => true,
+        })
+        .count();
+
+    for (stream, mut packet) in input.packets() {
+        if token.is_cancelled() {
+            if policy == Policy::Finalize {
+                output.write_trailer()?;
+            }
+
+            return Err(Error::Exit);
+        }
+
+        let index = stream.index();
+        let (ost_index, medium) = match mapping[index] {
+            Some(value) => value,
+            None => continue,
+        };
+
+        if finished[index] {
+            continue;
+        }
+
+        let time_base = stream.time_base();
+        let (pts, dts) = match (packet.pts(), packet.dts()) {
+            (Some(pts), dts) => (pts, dts.unwrap_or(pts)),
+            (None, Some(dts)) => (dts, dts),
+            (None, None) => continue,
+        };
+
+        if dts.rescale(time_base, TIME_BASE) >= end {
+            if medium != media::Type::Subtitle {
+                finished[index] = true;
+                remaining -= 1;
+
+                if remaining == 0 {
+                    break;
+                }
+            }
+
+            continue;
+        }
+
+        if pts.rescale(time_base, TIME_BASE) >= end {
+            continue;
+        }
+
+        if medium == media::Type::Video {
+            if !started[index] && !packet.is_key() {
+                continue;
+            }
+        } else if (pts + packet.duration()).rescale(time_base, TIME_BASE) <= origin {
+            continue;
+        }
+
+        started[index] = true;
+
+        let offset = origin.rescale(TIME_BASE, time_base);
+
+        packet.set_pts(packet.pts().map(|value| value - offset));
+        packet.set_dts(packet.dts().map(|value| value - offset));
+
+        match (deltas[index].as_mut(), packet.pts()) {
+            (Some(delta), Some(pts)) => {
+                let destination = time_bases[ost_index];
+                let samples = packet
+                    .duration()
+                    .rescale(time_base, (1, rates[index]))
+                    .max(0) as usize;
+                let rescaled = delta.rescale(pts, samples);
+                let dts = packet
+                    .dts()
+                    .map(|dts| rescaled - (pts - dts).rescale(time_base, destination));
+                let duration = packet.duration().rescale(time_base, destination);
+
+                packet.set_pts(Some(rescaled));
+                packet.set_dts(dts);
+                packet.set_duration(duration);
+            }
+
+            _ => packet.rescale_ts(time_base, time_bases[ost_index]),
+        }
+
+        packet.set_position(-1);
+        packet.set_stream(ost_index);
+        packet.write_interleaved(output)?;
+    }
+
+    output.write_trailer()
+}
+
+// Locate the presentation time of the keyframe a seek to `ts` lands on.
+fn keyframe(input: &mut Input, video: usize, ts: i64) -> Result<i64, Error> {
+    input.seek(ts, ..ts)?;
+
+    for (stream, packet) in input.packets() {
+        if stream.index() != video || !packet.is_key() {
+            continue;
+        }
+
+        if let Some(value) = packet.pts().or_else(|| packet.dts()) {
+            return Ok(value.rescale(stream.time_base(), TIME_BASE));
+        }
+    }
+
+    Err(Error::Eof)
+}
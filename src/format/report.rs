@@ -0,0 +1,457 @@
+use std::fmt;
+use std::path::Path;
+use std::ptr;
+
+use super::{context, from_path};
+use ffi::*;
+use {codec, Dictionary, DictionaryRef, Error, Rational};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Scope {
+    Format,
+    Stream(usize),
+    Program(i32),
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Scope::Format => f.write_str("format"),
+            Scope::Stream(index) => write!(f, "stream #{}", index),
+            Scope::Program(id) => write!(f, "program {}", id),
+        }
+    }
+}
+
+/// Something `avformat_find_stream_info` discovered or guessed.
+#[derive(PartialEq, Clone, Debug)]
+pub enum Change {
+    /// The stream was only found by reading packets, as in MPEG-TS.
+    StreamAdded(usize),
+
+    Codec {
+        stream: usize,
+        before: codec::Id,
+        after: codec::Id,
+    },
+
+    FrameRate {
+        stream: usize,
+        before: Rational,
+        after: Rational,
+    },
+
+    /// The guessed lowest frame rate all timestamps can be represented in.
+    RealFrameRate {
+        stream: usize,
+        before: Rational,
+        after: Rational,
+    },
+
+    Dimensions {
+        stream: usize,
+        before: (u32, u32),
+        after: (u32, u32),
+    },
+
+    SampleRate {
+        stream: usize,
+        before: u32,
+        after: u32,
+    },
+
+    Channels {
+        stream: usize,
+        before: u16,
+        after: u16,
+    },
+
+    StartTime {
+        scope: Scope,
+        before: i64,
+        after: i64,
+    },
+
+    Duration {
+        scope: Scope,
+        before: i64,
+        after: i64,
+    },
+
+    Metadata {
+        scope: Scope,
+        key: String,
+        before: Option<String>,
+        after: Option<String>,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Change::StreamAdded(index) => write!(f, "stream #{}: added", index),
+
+            Change::Codec {
+                stream,
+                before,
+                after,
+            } => write!(f, "stream #{}: codec {:?} -> {:?}", stream, before, after),
+
+            Change::FrameRate {
+                stream,
+                before,
+                after,
+            } => write!(f, "stream #{}: frame rate {} -> {}", stream, before, after),
+
+            Change::RealFrameRate {
+                stream,
+                before,
+                after,
+            } => write!(
+                f,
+                "stream #{}: real frame rate {} -> {}",
+                stream, before, after
+            ),
+
+            Change::Dimensions {
+                stream,
+                before,
+                after,
+            } => write!(
+                f,
+                "stream #{}: dimensions {}x{} -> {}x{}",
+                stream, before.0, before.1, after.0, after.1
+            ),
+
+            Change::SampleRate {
+                stream,
+                before,
+                after,
+            } => write!(f, "stream #{}: sample rate {} -> {}", stream, before, after),
+
+            Change::Channels {
+                stream,
+                before,
+                after,
+            } => write!(f, "stream #{}: channels {} -> {}", stream, before, after),
+
+            Change::StartTime {
+                scope,
+                before,
+                after,
+            } => write!(
+                f,
+                "{}: start time {} -> {}",
+                scope,
+                Timestamp(before),
+                Timestamp(after)
+            ),
+
+            Change::Duration {
+                scope,
+                before,
+                after,
+            } => write!(
+                f,
+                "{}: duration {} -> {}",
+                scope,
+                Timestamp(before),
+                Timestamp(after)
+            ),
+
+            Change::Metadata {
+                scope,
+                ref key,
+                ref before,
+                ref after,
+            } => write!(f, "{}: metadata {} {:?} -> {:?}", scope, key, before, after),
+        }
+    }
+}
+
+struct Timestamp(i64);
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            AV_NOPTS_VALUE => f.write_str("unset"),
+            value => write!(f, "{}", value),
+        }
+    }
+}
+
+/// What probing changed on an input, from `report::input`.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Report {
+    changes: Vec<Change>,
+}
+
+impl Report {
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Open an input like `format::input_with_dictionary`, reporting what
+/// `avformat_find_stream_info` changed over what the header declared.
+pub fn input<P: AsRef<Path>>(
+    path: &P,
+    options: Dictionary,
+) -> Result<(context::Input, Report), Error> {
+    unsafe {
+        let mut ps = ptr::null_mut();
+        let path = from_path(path);
+        let mut opts = options.disown();
+        let res = avformat_open_input(&mut ps, path.as_ptr(), ptr::null_mut(), &mut opts);
+
+        Dictionary::own(opts);
+
+        match res {
+            0 => {
+                let before = Snapshot::take(ps);
+
+                match avformat_find_stream_info(ps, ptr::null_mut()) {
+                    r if r >= 0 => {
+                        let report = before.diff(&Snapshot::take(ps));
+                        Ok((context::Input::wrap(ps), report))
+                    }
+
+                    e => {
+                        avformat_close_input(&mut ps);
+                        Err(Error::from(e))
+                    }
+                }
+            }
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
+type Metadata = Vec<(String, String)>;
+
+struct StreamSnapshot {
+    codec: codec::Id,
+    frame_rate: Rational,
+    real_frame_rate: Rational,
+    dimensions: (u32, u32),
+    sample_rate: u32,
+    channels: u16,
+    start_time: i64,
+    duration: i64,
+    metadata: Metadata,
+}
+
+struct Snapshot {
+    start_time: i64,
+    duration: i64,
+    metadata: Metadata,
+    streams: Vec<StreamSnapshot>,
+    programs: Vec<(i32, Metadata)>,
+}
+
+unsafe fn metadata(dictionary: *mut AVDictionary) -> Metadata {
+    DictionaryRef::wrap(dictionary)
+        .iter()
+        .map(|(k, v)| (k.to_owned(), v.to_owned()))
+        .collect()
+}
+
+impl Snapshot {
+    unsafe fn take(ctx: *const AVFormatContext) -> Self {
+        let streams = (0..(*ctx).nb_streams as usize)
+            .map(|i| {
+                let stream = *(*ctx).streams.add(i);
+                let par = (*stream).codecpar;
+
+                StreamSnapshot {
+                    codec: codec::Id::from((*par).codec_id),
+                    frame_rate: Rational::from((*stream).avg_frame_rate),
+                    real_frame_rate: Rational::from((*stream).r_frame_rate),
+                    dimensions: ((*par).width as u32, (*par).height as u32),
+                    sample_rate: (*par).sample_rate as u32,
+                    channels: (*par).channels as u16,
+                    start_time: (*stream).start_time,
+                    duration: (*stream).duration,
+                    metadata: metadata((*stream).metadata),
+                }
+            })
+            .collect();
+
+        let programs = (0..(*ctx).nb_programs as usize)
+            .map(|i| {
+                let program = *(*ctx).programs.add(i);
+                ((*program).id, metadata((*program).metadata))
+            })
+            .collect();
+
+        Snapshot {
+            start_time: (*ctx).start_time,
+            duration: (*ctx).duration,
+            metadata: metadata((*ctx).metadata),
+            streams,
+            programs,
+        }
+    }
+
+    fn diff(&self, after: &Snapshot) -> Report {
+        let mut changes = Vec::new();
+
+        timing(
+            &mut changes,
+            Scope::Format,
+            (self.start_time, self.duration),
+            (after.start_time, after.duration),
+        );
+        diff_metadata(&mut changes, Scope::Format, &self.metadata, &after.metadata);
+
+        for (index, new) in after.streams.iter().enumerate() {
+            let old = match self.streams.get(index) {
+                Some(old) => old,
+                None => {
+                    changes.push(Change::StreamAdded(index));
+                    continue;
+                }
+            };
+
+            if old.codec != new.codec {
+                changes.push(Change::Codec {
+                    stream: index,
+                    before: old.codec,
+                    after: new.codec,
+                });
+            }
+
+            if old.frame_rate != new.frame_rate {
+                changes.push(Change::FrameRate {
+                    stream: index,
+                    before: old.frame_rate,
+                    after: new.frame_rate,
+                });
+            }
+
+            if old.real_frame_rate != new.real_frame_rate {
+                changes.push(Change::RealFrameRate {
+                    stream: index,
+                    before: old.real_frame_rate,
+                    after: new.real_frame_rate,
+                });
+            }
+
+            if old.dimensions != new.dimensions {
+                changes.push(Change::Dimensions {
+                    stream: index,
+                    before: old.dimensions,
+                    after: new.dimensions,
+                });
+            }
+
+            if old.sample_rate != new.sample_rate {
+                changes.push(Change::SampleRate {
+                    stream: index,
+                    before: old.sample_rate,
+                    after: new.sample_rate,
+                });
+            }
+
+            if old.channels != new.channels {
+                changes.push(Change::Channels {
+                    stream: index,
+                    before: old.channels,
+                    after: new.channels,
+                });
+            }
+
+            timing(
+                &mut changes,
+                Scope::Stream(index),
+                (old.start_time, old.duration),
+                (new.start_time, new.duration),
+            );
+            diff_metadata(
+                &mut changes,
+                Scope::Stream(index),
+                &old.metadata,
+                &new.metadata,
+            );
+        }
+
+        for &(id, ref new) in &after.programs {
+            let empty = Vec::new();
+            let old = self
+                .programs
+                .iter()
+                .find(|&&(old, _)| old == id)
+                .map_or(&empty, |&(_, ref metadata)| metadata);
+
+            diff_metadata(&mut changes, Scope::Program(id), old, new);
+        }
+
+        Report { changes }
+    }
+}
+
+fn timing(changes: &mut Vec<Change>, scope: Scope, before: (i64, i64), after: (i64, i64)) {
+    if before.0 != after.0 {
+        changes.push(Change::StartTime {
+            scope,
+            before: before.0,
+            after: after.0,
+        });
+    }
+
+    if before.1 != after.1 {
+        changes.push(Change::Duration {
+            scope,
+            before: before.1,
+            after: after.1,
+        });
+    }
+}
+
+fn diff_metadata(changes: &mut Vec<Change>, scope: Scope, before: &Metadata, after: &Metadata) {
+    let lookup = |metadata: &Metadata, key: &str| {
+        metadata
+            .iter()
+            .find(|&&(ref k, _)| k == key)
+            .map(|&(_, ref v)| v.clone())
+    };
+
+    for &(ref key, ref value) in after {
+        let old = lookup(before, key);
+
+        if old.as_ref() != Some(value) {
+            changes.push(Change::Metadata {
+                scope,
+                key: key.clone(),
+                before: old,
+                after: Some(value.clone()),
+            });
+        }
+    }
+
+    for &(ref key, ref value) in before {
+        if lookup(after, key).is_none() {
+            changes.push(Change::Metadata {
+                scope,
+                key: key.clone(),
+                before: Some(value.clone()),
+                after: None,
+            });
+        }
+    }
+}
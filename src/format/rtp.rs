@@ -0,0 +1,227 @@
+//! RTP payload formats and the `rtp` muxer, to packetize encoded streams,
+//! one stream per muxer, and describe them in SDP.
+
+use std::ffi::CStr;
+use std::str::from_utf8_unchecked;
+
+use super::context::Output;
+use codec::Id;
+use ffi::*;
+use libc::{c_char, c_int, EINVAL};
+use {media, Dictionary, Error};
+
+/// First dynamic payload type. For codecs without a static one, the muxer
+/// picks it for video and the next one, 97, for audio.
+pub const DYNAMIC: u8 = 96;
+
+/// How a codec is carried over RTP, as it appears in the `a=rtpmap` line of
+/// an SDP.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Payload {
+    pub kind: u8,
+    pub encoding: &'static str,
+    pub clock_rate: u32,
+
+    /// For audio payloads with more than one channel.
+    pub channels: Option<u16>,
+}
+
+impl Payload {
+    /// Whether the payload type is assigned by RFC 3551 rather than
+    /// negotiated.
+    pub fn is_static(&self) -> bool {
+        self.kind < DYNAMIC
+    }
+}
+
+/// The static payload type of RFC 3551 for `id` at `rate` with `channels`,
+/// if any. H.263 is left out: the muxer packetizes it as H263-1998, which
+/// is dynamic.
+pub fn static_kind(id: Id, rate: u32, channels: u16) -> Option<u8> {
+    match (id, rate, channels) {
+        (Id::PCM_MULAW, 8000, 1) => Some(0),
+        (Id::GSM, 8000, 1) => Some(3),
+        (Id::G723_1, 8000, 1) => Some(4),
+        (Id::PCM_ALAW, 8000, 1) => Some(8),
+        (Id::ADPCM_G722, 16000, 1) => Some(9),
+        (Id::PCM_S16BE, 44100, 2) => Some(10),
+        (Id::PCM_S16BE, 44100, 1) => Some(11),
+        (Id::MP2, _, _) | (Id::MP3, _, _) => Some(14),
+        (Id::MJPEG, _, _) => Some(26),
+        (Id::H261, _, _) => Some(31),
+        (Id::MPEG1VIDEO, _, _) | (Id::MPEG2VIDEO, _, _) => Some(32),
+        (Id::MPEG2TS, _, _) => Some(33),
+        _ => None,
+    }
+}
+
+/// The encoding name the muxer and SDP use for `id`, `None` for codecs the
+/// `rtp` muxer cannot packetize.
+pub fn encoding(id: Id) -> Option<&'static str> {
+    match id {
+        Id::PCM_MULAW => Some("PCMU"),
+        Id::PCM_ALAW => Some("PCMA"),
+        Id::PCM_S16BE => Some("L16"),
+        Id::PCM_U8 => Some("L8"),
+        Id::GSM => Some("GSM"),
+        Id::G723_1 => Some("G723"),
+        Id::ADPCM_G722 => Some("G722"),
+        Id::MP2 | Id::MP3 => Some("MPA"),
+        Id::MJPEG => Some("JPEG"),
+        Id::H261 => Some("H261"),
+        Id::MPEG1VIDEO | Id::MPEG2VIDEO => Some("MPV"),
+        Id::MPEG2TS => Some("MP2T"),
+        Id::H263 => Some("H263-1998"),
+        Id::H264 => Some("H264"),
+        Id::HEVC => Some("H265"),
+        Id::MPEG4 => Some("MP4V-ES"),
+        Id::VP8 => Some("VP8"),
+        Id::VP9 => Some("VP9"),
+        Id::THEORA => Some("theora"),
+        Id::AAC => Some("MPEG4-GENERIC"),
+        Id::OPUS => Some("opus"),
+        Id::VORBIS => Some("vorbis"),
+        Id::SPEEX => Some("speex"),
+        Id::ILBC => Some("iLBC"),
+        Id::AMR_NB => Some("AMR"),
+        Id::AMR_WB => Some("AMR-WB"),
+        _ => None,
+    }
+}
+
+/// The payload the `rtp` muxer uses for `id` at `rate` with `channels`, 0
+/// for both with video, unless overridden with `Options::payload_type`.
+///
+/// Video, MPEG audio and transport streams are clocked at 90 kHz, Opus at
+/// 48 kHz and G.722 at 8 kHz whatever its actual rate, as their RFCs say,
+/// and other audio at its sample rate.
+pub fn payload(id: Id, rate: u32, channels: u16) -> Option<Payload> {
+    let encoding = encoding(id)?;
+
+    let kind = match static_kind(id, rate, channels) {
+        Some(kind) => kind,
+        None if id.medium() == media::Type::Audio => DYNAMIC + 1,
+        None => DYNAMIC,
+    };
+
+    let (clock_rate, channels) = match id {
+        Id::MP2 | Id::MP3 | Id::MPEG2TS => (90000, None),
+        Id::OPUS => (48000, Some(2)),
+        Id::ADPCM_G722 => (8000, None),
+        _ if rate == 0 => (90000, None),
+        _ if channels > 1 => (rate, Some(channels)),
+        _ => (rate, None),
+    };
+
+    Some(Payload {
+        kind,
+        encoding,
+        clock_rate,
+        channels,
+    })
+}
+
+/// Options of the `rtp` muxer, for `Output::write_header_with`.
+#[derive(Eq, PartialEq, Clone, Default, Debug)]
+pub struct Options {
+    /// Overrides the payload type picked by `payload`.
+    pub payload_type: Option<u8>,
+
+    /// Random by default.
+    pub ssrc: Option<u32>,
+
+    pub cname: Option<String>,
+
+    /// First sequence number, random by default.
+    pub sequence: Option<u16>,
+
+    /// Largest RTP packet in bytes, header included, which should stay
+    /// below the path MTU. The protocol maximum, 1472 bytes for UDP, by
+    /// default.
+    pub max_packet_size: Option<usize>,
+}
+
+impl Options {
+    pub fn to_dictionary<'a>(&self) -> Dictionary<'a> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(payload_type) = self.payload_type {
+            dictionary.set("payload_type", &payload_type.to_string());
+        }
+
+        if let Some(ssrc) = self.ssrc {
+            // The option is a signed int, as in the command line tools.
+            dictionary.set("ssrc", &(ssrc as i32).to_string());
+        }
+
+        if let Some(ref cname) = self.cname {
+            dictionary.set("cname", cname);
+        }
+
+        if let Some(sequence) = self.sequence {
+            dictionary.set("seq", &sequence.to_string());
+        }
+
+        if let Some(size) = self.max_packet_size {
+            dictionary.set("packetsize", &size.to_string());
+        }
+
+        dictionary
+    }
+}
+
+/// Open an `rtp` muxer sending to `url`, such as `rtp://host:port`, to which
+/// a single stream is to be added.
+pub fn output(url: &str) -> Result<Output, Error> {
+    super::output_as(&url, "rtp")
+}
+
+/// The SDP describing `outputs`, once their headers are written, as one
+/// session with a media section per output.
+pub fn sdp(outputs: &[&Output]) -> Result<String, Error> {
+    if outputs.is_empty() {
+        return Err(Error::Other { errno: EINVAL });
+    }
+
+    let mut contexts = outputs
+        .iter()
+        .map(|output| unsafe { output.as_ptr() as *mut AVFormatContext })
+        .collect::<Vec<_>>();
+    let mut buffer = vec![0 as c_char; 16384];
+
+    unsafe {
+        match av_sdp_create(
+            contexts.as_mut_ptr(),
+            contexts.len() as c_int,
+            buffer.as_mut_ptr(),
+            buffer.len() as c_int,
+        ) {
+            0 => Ok(from_utf8_unchecked(CStr::from_ptr(buffer.as_ptr()).to_bytes()).to_owned()),
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload() {
+        let pcmu = payload(Id::PCM_MULAW, 8000, 1).unwrap();
+        assert_eq!(
+            (pcmu.kind, pcmu.encoding, pcmu.clock_rate),
+            (0, "PCMU", 8000)
+        );
+        assert!(pcmu.is_static());
+
+        let h264 = payload(Id::H264, 0, 0).unwrap();
+        assert_eq!((h264.kind, h264.clock_rate), (96, 90000));
+
+        let opus = payload(Id::OPUS, 48000, 1).unwrap();
+        assert_eq!((opus.kind, opus.channels), (97, Some(2)));
+
+        assert_eq!(payload(Id::PCM_MULAW, 16000, 1).unwrap().kind, 97);
+        assert_eq!(payload(Id::FLAC, 44100, 2), None);
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::ops::{Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::time::Duration;
+
+use super::context::Output;
+use super::from_path;
+use ffi::*;
+use libc::{c_char, c_int, c_void};
+use Error;
+
+/// Rotation policy of the `segment` muxer.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Segment {
+    /// Target length of each file. Cuts happen on the next video keyframe,
+    /// so segments run longer when keyframes are sparse.
+    pub time: Duration,
+
+    /// Start the timestamps of every file at zero.
+    pub reset_timestamps: bool,
+
+    /// Expand the file pattern with `strftime` instead of numbering files
+    /// with a `%d` style placeholder, e.g. `rec-%Y%m%d-%H%M%S.ts`.
+    pub strftime: bool,
+
+    /// Maintain a playlist of finished segments, its type deduced from the
+    /// extension (`.m3u8`, `.csv`, `.ffconcat`, ...).
+    pub list: Option<PathBuf>,
+
+    /// Container of the segments, deduced from the pattern when `None`.
+    pub format: Option<String>,
+
+    /// Wrap the segment index around, reusing file names, after this many
+    /// segments.
+    pub wrap: Option<u32>,
+}
+
+impl Default for Segment {
+    fn default() -> Self {
+        Segment {
+            time: Duration::from_secs(2),
+            reset_timestamps: false,
+            strftime: false,
+            list: None,
+            format: None,
+            wrap: None,
+        }
+    }
+}
+
+impl Segment {
+    fn options(&self) -> Vec<(&'static str, String)> {
+        let time = self.time.as_secs() as f64 + f64::from(self.time.subsec_nanos()) / 1e9;
+
+        let mut options = vec![
+            ("segment_time", format!("{}", time)),
+            (
+                "reset_timestamps",
+                (self.reset_timestamps as i32).to_string(),
+            ),
+            ("strftime", (self.strftime as i32).to_string()),
+        ];
+
+        if let Some(ref list) = self.list {
+            options.push(("segment_list", list.to_string_lossy().into_owned()));
+        }
+
+        if let Some(ref format) = self.format {
+            options.push(("segment_format", format.clone()));
+        }
+
+        if let Some(wrap) = self.wrap {
+            options.push(("segment_wrap", wrap.to_string()));
+        }
+
+        options
+    }
+}
+
+struct State {
+    // The playlist, and the temporary file it is written to before being
+    // renamed over it with `use_rename`, neither of which is a segment.
+    lists: Vec<String>,
+    open: HashMap<usize, String>,
+    finished: Box<dyn FnMut(&Path) + Send>,
+}
+
+/// An `Output` writing rotating files through the `segment` muxer.
+pub struct Recorder {
+    // Dropped first, the muxer may still close files through `state`.
+    output: Output,
+    _state: Box<State>,
+}
+
+impl Deref for Recorder {
+    type Target = Output;
+
+    fn deref(&self) -> &Output {
+        &self.output
+    }
+}
+
+impl DerefMut for Recorder {
+    fn deref_mut(&mut self) -> &mut Output {
+        &mut self.output
+    }
+}
+
+/// Record to files named after `pattern`, calling `finished` with the path of
+/// each segment once the muxer closes it, the last one being closed by
+/// `write_trailer`.
+///
+/// Streams are added and packets written as with any `Output`.
+pub fn output<P, F>(pattern: &P, segment: &Segment, finished: F) -> Result<Recorder, Error>
+where
+    P: AsRef<Path>,
+    F: FnMut(&Path) + Send + 'static,
+{
+    let mut state = Box::new(State {
+        lists: segment
+            .list
+            .iter()
+            .map(|list| list.to_string_lossy().into_owned())
+            .flat_map(|list| vec![format!("{}.tmp", list), list])
+            .collect(),
+        open: HashMap::new(),
+        finished: Box::new(finished),
+    });
+
+    unsafe {
+        let mut ps = ptr::null_mut();
+        let path = from_path(pattern);
+        let format = CString::new("segment").unwrap();
+
+        match avformat_alloc_output_context2(
+            &mut ps,
+            ptr::null_mut(),
+            format.as_ptr(),
+            path.as_ptr(),
+        ) {
+            0 => (),
+            e => return Err(Error::from(e)),
+        }
+
+        let output = Output::wrap(ps);
+
+        for (key, value) in segment.options() {
+            let key = CString::new(key).unwrap();
+            let value = CString::new(value).map_err(|_| Error::InvalidData)?;
+
+            match av_opt_set((*ps).priv_data, key.as_ptr(), value.as_ptr(), 0) {
+                0 => (),
+                e => return Err(Error::from(e)),
+            }
+        }
+
+        // The muxer hands these to the context of each segment.
+        (*ps).opaque = &mut *state as *mut State as *mut c_void;
+        (*ps).io_open = Some(io_open);
+        (*ps).io_close = Some(io_close);
+
+        Ok(Recorder {
+            output,
+            _state: state,
+        })
+    }
+}
+
+unsafe extern "C" fn io_open(
+    s: *mut AVFormatContext,
+    pb: *mut *mut AVIOContext,
+    url: *const c_char,
+    flags: c_int,
+    options: *mut *mut AVDictionary,
+) -> c_int {
+    let state = &mut *((*s).opaque as *mut State);
+
+    match avio_open2(pb, url, flags, &(*s).interrupt_callback, options) {
+        e if e < 0 => e,
+        e => {
+            let url = CStr::from_ptr(url).to_string_lossy().into_owned();
+            state.open.insert(*pb as usize, url);
+
+            e
+        }
+    }
+}
+
+unsafe extern "C" fn io_close(s: *mut AVFormatContext, pb: *mut AVIOContext) {
+    let state = &mut *((*s).opaque as *mut State);
+    let url = state.open.remove(&(pb as usize));
+
+    avio_close(pb);
+
+    match url {
+        Some(ref url) if !state.lists.contains(url) => {
+            let finished = &mut state.finished;
+
+            // Unwinding into C is undefined behavior.
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| finished(Path::new(url))));
+        }
+
+        _ => (),
+    }
+}
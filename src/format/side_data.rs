@@ -0,0 +1,266 @@
+//! Which side data survives a transcode.
+//!
+//! Decoders, filters and encoders each copy some side data along and lose
+//! the rest, and remuxing copies whatever the packets carry. A `Policy`
+//! states once what is propagated, dropped or rewritten at every stage: run
+//! stream side data through `streams` when setting up the output, packet
+//! side data through `packets` before muxing, and frame side data through
+//! `frames` between decoding and encoding.
+//!
+//! `remux::copy_with` applies a policy to streams and packets, and
+//! `DecodedFrames::side_data` to decoded frames.
+
+use std::slice;
+
+use super::stream::{Stream, StreamMut};
+use ffi::*;
+use libc::ENOMEM;
+use packet::side_data::Type as PacketType;
+use util::frame::side_data::Type as FrameType;
+use {Error, Frame, Packet};
+
+type Transform = Box<dyn Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync>;
+
+enum Rule {
+    Keep,
+    Drop,
+    Transform(Transform),
+}
+
+/// Side data propagation rules, by type, with a default for the types not
+/// listed.
+///
+/// Packet types also apply to stream side data, which uses the same ones.
+pub struct Policy {
+    keep: bool,
+
+    packets: Vec<(PacketType, Rule)>,
+    frames: Vec<(FrameType, Rule)>,
+}
+
+impl Policy {
+    /// Propagate everything not listed.
+    pub fn keep_all() -> Self {
+        Policy {
+            keep: true,
+            packets: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Drop everything not listed.
+    pub fn drop_all() -> Self {
+        Policy {
+            keep: false,
+            packets: Vec::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Propagate everything but what no longer holds once re-encoded:
+    /// encryption info, since the output is not encrypted, extradata and
+    /// parameter changes, quantizer statistics, motion vectors and encoding
+    /// parameters of the source encoder, and skip samples, which encoders
+    /// produce for their own priming.
+    ///
+    /// HDR metadata, display matrices, captions and the like are kept.
+    pub fn transcode() -> Self {
+        let policy = Policy::keep_all()
+            .drop_packet(PacketType::NewExtraData)
+            .drop_packet(PacketType::ParamChange)
+            .drop_packet(PacketType::QualityStats)
+            .drop_packet(PacketType::SkipSamples)
+            .drop_packet(PacketType::CBPProperties)
+            .drop_frame(FrameType::MotionVectors)
+            .drop_frame(FrameType::SkipSamples);
+
+        #[cfg(feature = "ffmpeg_4_0")]
+        let policy = policy
+            .drop_packet(PacketType::EncryptionInitInfo)
+            .drop_packet(PacketType::EncryptionInfo)
+            .drop_frame(FrameType::QPTableProperties)
+            .drop_frame(FrameType::QPTableData);
+
+        #[cfg(feature = "ffmpeg_4_3")]
+        let policy = policy.drop_frame(FrameType::VIDEO_ENC_PARAMS);
+
+        policy
+    }
+
+    pub fn keep_packet(self, kind: PacketType) -> Self {
+        self.packet_rule(kind, Rule::Keep)
+    }
+
+    pub fn drop_packet(self, kind: PacketType) -> Self {
+        self.packet_rule(kind, Rule::Drop)
+    }
+
+    /// Replace the data of `kind` with what `f` returns, dropping it on
+    /// `None`.
+    pub fn transform_packet<F>(self, kind: PacketType, f: F) -> Self
+    where
+        F: Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.packet_rule(kind, Rule::Transform(Box::new(f)))
+    }
+
+    pub fn keep_frame(self, kind: FrameType) -> Self {
+        self.frame_rule(kind, Rule::Keep)
+    }
+
+    pub fn drop_frame(self, kind: FrameType) -> Self {
+        self.frame_rule(kind, Rule::Drop)
+    }
+
+    /// Replace the data of `kind` with what `f` returns, dropping it on
+    /// `None`.
+    pub fn transform_frame<F>(self, kind: FrameType, f: F) -> Self
+    where
+        F: Fn(&[u8]) -> Option<Vec<u8>> + Send + Sync + 'static,
+    {
+        self.frame_rule(kind, Rule::Transform(Box::new(f)))
+    }
+
+    /// What becomes of packet or stream side data of `kind` holding `data`.
+    pub fn packet_data(&self, kind: PacketType, data: &[u8]) -> Option<Vec<u8>> {
+        resolve(self.keep, &self.packets, kind, data)
+    }
+
+    /// What becomes of frame side data of `kind` holding `data`.
+    pub fn frame_data(&self, kind: FrameType, data: &[u8]) -> Option<Vec<u8>> {
+        resolve(self.keep, &self.frames, kind, data)
+    }
+
+    /// Rewrite the side data of `packet` in place.
+    pub fn packets(&self, packet: &mut Packet) -> Result<(), Error> {
+        if self.keep && self.packets.is_empty() {
+            return Ok(());
+        }
+
+        let entries = packet
+            .side_data()
+            .filter_map(|entry| {
+                self.packet_data(entry.kind(), entry.data())
+                    .map(|data| (entry.kind(), data))
+            })
+            .collect::<Vec<_>>();
+
+        unsafe {
+            av_packet_free_side_data(packet.as_mut_ptr());
+        }
+
+        for (kind, data) in entries {
+            packet.add_side_data(kind, &data)?;
+        }
+
+        Ok(())
+    }
+
+    /// Rewrite the side data of `frame` in place.
+    pub fn frames(&self, frame: &mut Frame) -> Result<(), Error> {
+        if self.keep && self.frames.is_empty() {
+            return Ok(());
+        }
+
+        let entries = unsafe {
+            let ptr = frame.as_ptr();
+            let list = slice::from_raw_parts((*ptr).side_data, (*ptr).nb_side_data as usize);
+
+            list.iter()
+                .map(|&entry| {
+                    let data = slice::from_raw_parts((*entry).data, (*entry).size as usize);
+                    let kind = FrameType::from((*entry).type_);
+
+                    (kind, self.frame_data(kind, data))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // Types such as unregistered SEI can appear more than once, so every
+        // entry is removed before the kept ones are added back.
+        for &(kind, _) in &entries {
+            frame.remove_side_data(kind);
+        }
+
+        for (kind, data) in entries {
+            if let Some(data) = data {
+                match frame.new_side_data(kind, data.len()) {
+                    Some(mut side_data) => side_data.data_mut().copy_from_slice(&data),
+                    None => return Err(Error::Other { errno: ENOMEM }),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Copy the side data of `source` onto `destination` as the policy says,
+    /// typically while adding the output streams.
+    pub fn streams(&self, source: &Stream, destination: &mut StreamMut) -> Result<(), Error> {
+        for entry in source.side_data() {
+            if let Some(data) = self.packet_data(entry.kind(), entry.data()) {
+                destination.add_side_data(entry.kind(), &data)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn packet_rule(mut self, kind: PacketType, rule: Rule) -> Self {
+        self.packets.retain(|&(k, _)| k != kind);
+        self.packets.push((kind, rule));
+        self
+    }
+
+    fn frame_rule(mut self, kind: FrameType, rule: Rule) -> Self {
+        self.frames.retain(|&(k, _)| k != kind);
+        self.frames.push((kind, rule));
+        self
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy::keep_all()
+    }
+}
+
+fn resolve<T: PartialEq>(keep: bool, rules: &[(T, Rule)], kind: T, data: &[u8]) -> Option<Vec<u8>> {
+    match rules.iter().find(|&&(ref k, _)| *k == kind).map(|r| &r.1) {
+        Some(&Rule::Keep) => Some(data.to_vec()),
+        Some(&Rule::Drop) => None,
+        Some(&Rule::Transform(ref f)) => f(data),
+        None if keep => Some(data.to_vec()),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let policy = Policy::drop_all()
+            .keep_packet(PacketType::DisplayMatrix)
+            .transform_packet(PacketType::ReplayGain, |data| Some(data[..1].to_vec()));
+
+        assert_eq!(
+            policy.packet_data(PacketType::DisplayMatrix, &[1, 2]),
+            Some(vec![1, 2])
+        );
+        assert_eq!(
+            policy.packet_data(PacketType::ReplayGain, &[1, 2]),
+            Some(vec![1])
+        );
+        assert_eq!(policy.packet_data(PacketType::Palette, &[1, 2]), None);
+
+        let policy = Policy::transcode();
+
+        assert_eq!(policy.packet_data(PacketType::SkipSamples, &[0]), None);
+        assert_eq!(
+            policy.frame_data(FrameType::MasteringDisplayMetadata, &[0]),
+            Some(vec![0])
+        );
+    }
+}
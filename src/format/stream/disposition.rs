@@ -17,5 +17,6 @@ bitflags! {
         const CAPTIONS         = AV_DISPOSITION_CAPTIONS;
         const DESCRIPTIONS     = AV_DISPOSITION_DESCRIPTIONS;
         const METADATA         = AV_DISPOSITION_METADATA;
+        const TIMED_THUMBNAILS = AV_DISPOSITION_TIMED_THUMBNAILS;
     }
 }
@@ -1,9 +1,13 @@
 use super::Disposition;
-use codec::{self, packet};
+use codec::{self, packet, parser};
 use ffi::*;
 use format::context::common::Context;
 use libc::c_int;
-use {DictionaryRef, Discard, Rational};
+use rescale::TIME_BASE;
+use std::ffi::CString;
+use std::time::Duration;
+use time::Timestamp;
+use {DictionaryRef, Discard, Error, Rational};
 
 #[derive(Debug)]
 pub struct Stream<'a> {
@@ -44,18 +48,52 @@ impl<'a> Stream<'a> {
         unsafe { Rational::from((*self.as_ptr()).time_base) }
     }
 
+    /// Presentation time of the first frame in the time base of the stream,
+    /// `AV_NOPTS_VALUE` when unknown.
     pub fn start_time(&self) -> i64 {
         unsafe { (*self.as_ptr()).start_time }
     }
 
+    /// Like `start_time`, along with the time base.
+    pub fn start(&self) -> Timestamp {
+        Timestamp::new(self.start_time(), self.time_base())
+    }
+
+    /// Duration in the time base of the stream, `AV_NOPTS_VALUE` when
+    /// unknown, as in many Matroska and MPEG-TS files.
     pub fn duration(&self) -> i64 {
         unsafe { (*self.as_ptr()).duration }
     }
 
+    /// The duration of the stream, or of the whole input when the stream
+    /// has none, as for most Matroska and MPEG-TS files. `None` when neither
+    /// is known, as for live inputs.
+    pub fn duration_time(&self) -> Option<Duration> {
+        self.time_base()
+            .duration(self.duration())
+            .or_else(|| unsafe { TIME_BASE.duration((*self.context.as_ptr()).duration) })
+    }
+
+    /// Number of frames as stored in the container, 0 when unknown.
     pub fn frames(&self) -> i64 {
         unsafe { (*self.as_ptr()).nb_frames }
     }
 
+    /// Number of frames as stored in the container, or else estimated from
+    /// `duration_time` and `frame_rate`, so only exact for constant frame
+    /// rate video.
+    pub fn estimated_frames(&self) -> Option<u64> {
+        if self.frames() > 0 {
+            return Some(self.frames() as u64);
+        }
+
+        let duration = self.duration_time()?;
+        let rate = self.frame_rate()?;
+        let seconds = duration.as_secs() as f64 + f64::from(duration.subsec_nanos()) / 1e9;
+
+        Some((seconds * f64::from(rate)).round() as u64)
+    }
+
     pub fn disposition(&self) -> Disposition {
         unsafe { Disposition::from_bits_truncate((*self.as_ptr()).disposition) }
     }
@@ -68,14 +106,108 @@ impl<'a> Stream<'a> {
         SideDataIter::new(self)
     }
 
+    /// The `r_frame_rate`: the lowest rate all timestamps can be represented
+    /// at, which is twice the frame rate for field coded video and can be
+    /// far off for variable frame rate video. 0/0 when unknown.
+    #[cfg(not(feature = "nonzero"))]
     pub fn rate(&self) -> Rational {
-        unsafe { Rational::from(av_stream_get_r_frame_rate(self.as_ptr())) }
+        self.raw_rate()
     }
 
+    /// The `r_frame_rate`: the lowest rate all timestamps can be represented
+    /// at, which is twice the frame rate for field coded video and can be
+    /// far off for variable frame rate video. `None` when unknown.
+    #[cfg(feature = "nonzero")]
+    pub fn rate(&self) -> Option<Rational> {
+        known(self.raw_rate())
+    }
+
+    /// Same as `rate`.
+    #[cfg(not(feature = "nonzero"))]
+    pub fn r_frame_rate(&self) -> Rational {
+        self.rate()
+    }
+
+    /// Same as `rate`.
+    #[cfg(feature = "nonzero")]
+    pub fn r_frame_rate(&self) -> Option<Rational> {
+        self.rate()
+    }
+
+    /// Average frame rate over what was probed, 0/0 when unknown.
+    #[cfg(not(feature = "nonzero"))]
     pub fn avg_frame_rate(&self) -> Rational {
+        self.raw_avg_frame_rate()
+    }
+
+    /// Average frame rate over what was probed, `None` when unknown.
+    #[cfg(feature = "nonzero")]
+    pub fn avg_frame_rate(&self) -> Option<Rational> {
+        known(self.raw_avg_frame_rate())
+    }
+
+    // The fields behind `rate` and `avg_frame_rate`, whatever type the
+    // `nonzero` feature makes them return.
+
+    #[doc(hidden)]
+    pub fn raw_rate(&self) -> Rational {
+        unsafe { Rational::from(av_stream_get_r_frame_rate(self.as_ptr())) }
+    }
+
+    #[doc(hidden)]
+    pub fn raw_avg_frame_rate(&self) -> Rational {
         unsafe { Rational::from((*self.as_ptr()).avg_frame_rate) }
     }
 
+    /// The average frame rate, or else `r_frame_rate`, or `None` when
+    /// neither is known, as for audio streams.
+    pub fn frame_rate(&self) -> Option<Rational> {
+        [self.raw_avg_frame_rate(), self.raw_rate()]
+            .iter()
+            .cloned()
+            .find(|rate| rate.numerator() > 0 && rate.denominator() > 0)
+    }
+
+    /// Check the stream against a specifier with the same syntax as the
+    /// `ffmpeg` command line, such as `"v:0"` or `"a:m:language:eng"`.
+    ///
+    /// An invalid specifier is reported as an error.
+    pub fn matches(&self, spec: &str) -> Result<bool, Error> {
+        let spec = CString::new(spec).map_err(|_| Error::InvalidData)?;
+
+        unsafe {
+            match avformat_match_stream_specifier(
+                self.context.as_ptr() as *mut _,
+                self.as_ptr() as *mut _,
+                spec.as_ptr(),
+            ) {
+                0 => Ok(false),
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(true),
+            }
+        }
+    }
+
+    /// The state of the parser libavformat runs on the stream, for the
+    /// demuxers that ask for one, such as MPEG-TS and raw bitstreams.
+    ///
+    /// The state is that of the last packet parsed, which cannot be tied to
+    /// a packet returned by `read`: libavformat parses ahead, and after
+    /// `avformat_find_stream_info` first returns the packets it buffered
+    /// while probing. For per-packet details, run a `codec::Parser` on
+    /// copies of the packets instead.
+    pub fn parser(&self) -> Option<parser::State> {
+        unsafe {
+            let ptr = av_stream_get_parser(self.as_ptr());
+
+            if ptr.is_null() {
+                None
+            } else {
+                Some(parser::State::wrap(ptr))
+            }
+        }
+    }
+
     pub fn metadata(&self) -> DictionaryRef {
         unsafe { DictionaryRef::wrap((*self.as_ptr()).metadata) }
     }
@@ -132,3 +264,12 @@ impl<'a> Iterator for SideDataIter<'a> {
 }
 
 impl<'a> ExactSizeIterator for SideDataIter<'a> {}
+
+#[cfg(feature = "nonzero")]
+fn known(rate: Rational) -> Option<Rational> {
+    if rate.numerator() > 0 && rate.denominator() > 0 {
+        Some(rate)
+    } else {
+        None
+    }
+}
@@ -1,10 +1,11 @@
 use std::mem;
 use std::ops::Deref;
 
-use super::Stream;
+use super::{Disposition, Stream};
 use ffi::*;
 use format::context::common::Context;
-use {codec, Dictionary, Rational};
+use libc::ENOMEM;
+use {codec, packet, Dictionary, Error, Rational};
 
 pub struct StreamMut<'a> {
     context: &'a mut Context,
@@ -47,6 +48,12 @@ impl<'a> StreamMut<'a> {
         }
     }
 
+    pub fn set_disposition(&mut self, value: Disposition) {
+        unsafe {
+            (*self.as_mut_ptr()).disposition = value.bits();
+        }
+    }
+
     pub fn set_parameters<P: Into<codec::Parameters>>(&mut self, parameters: P) {
         let parameters = parameters.into();
 
@@ -58,9 +65,40 @@ impl<'a> StreamMut<'a> {
     pub fn set_metadata(&mut self, metadata: Dictionary) {
         unsafe {
             let metadata = metadata.disown();
+            av_dict_free(&mut (*self.as_mut_ptr()).metadata);
             (*self.as_mut_ptr()).metadata = metadata;
         }
     }
+
+    /// Set a single metadata entry, such as `language` or `title`, keeping
+    /// the others.
+    pub fn add_metadata<K: AsRef<str>, V: AsRef<str>>(&mut self, key: K, value: V) {
+        unsafe {
+            let mut dictionary = Dictionary::own((*self.as_mut_ptr()).metadata);
+            dictionary.set(key.as_ref(), value.as_ref());
+            (*self.as_mut_ptr()).metadata = dictionary.disown();
+        }
+    }
+
+    /// Attach a copy of `data` as side data of the given kind, replacing any
+    /// existing entry of that kind.
+    pub fn add_side_data(
+        &mut self,
+        kind: packet::side_data::Type,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        unsafe {
+            let ptr = av_stream_new_side_data(self.as_mut_ptr(), kind.into(), data.len() as _);
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            ptr.copy_from_nonoverlapping(data.as_ptr(), data.len());
+        }
+
+        Ok(())
+    }
 }
 
 impl<'a> Deref for StreamMut<'a> {
@@ -6,14 +6,25 @@
 #[macro_use]
 extern crate bitflags;
 pub extern crate ffmpeg_sys_next as sys;
+#[cfg(feature = "async")]
+extern crate futures_core;
 #[cfg(feature = "image")]
 extern crate image;
 extern crate libc;
+#[cfg(feature = "log")]
+extern crate log as rust_log;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "async")]
+extern crate tokio;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 
 pub use sys as ffi;
 
 #[macro_use]
 pub mod util;
+pub use util::cancel;
 pub use util::channel_layout::{self, ChannelLayout};
 pub use util::chroma;
 pub use util::color;
@@ -21,11 +32,15 @@ pub use util::dictionary;
 pub use util::dictionary::Mut as DictionaryMut;
 pub use util::dictionary::Owned as Dictionary;
 pub use util::dictionary::Ref as DictionaryRef;
+pub use util::dimensions;
 pub use util::error::{self, Error};
 pub use util::frame::{self, Frame};
+pub use util::hdr;
+pub use util::hwcontext;
 pub use util::log;
 pub use util::mathematics::{self, rescale, Rescale, Rounding};
 pub use util::media;
+pub use util::memory;
 pub use util::option;
 pub use util::picture;
 pub use util::rational::{self, Rational};
@@ -59,7 +74,7 @@ pub use codec::subtitle::{self, Subtitle};
 #[cfg(feature = "codec")]
 pub use codec::threading;
 #[cfg(feature = "codec")]
-pub use codec::{decoder, encoder};
+pub use codec::{bsf, decoder, encoder};
 
 #[cfg(feature = "device")]
 pub mod device;
@@ -71,6 +86,9 @@ pub use filter::Filter;
 
 pub mod software;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
 fn init_error() {
     util::error::register_all();
 }
@@ -0,0 +1,356 @@
+//! Editing decoded audio at the sample level.
+
+use std::f32::consts::FRAC_PI_2;
+use std::path::Path;
+use std::time::Duration;
+
+use format::context::Output;
+use format::{self, sample};
+use libc::{EAGAIN, EINVAL};
+use software::resampling;
+use {encoder, frame, media, ChannelLayout, Error, Packet, Rational, Rescale};
+
+/// Gain ramps of a crossfade.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Curve {
+    /// Gains summing to 1, which dips in loudness midway between
+    /// uncorrelated material.
+    Linear,
+
+    /// Gains whose squares sum to 1, keeping the loudness of uncorrelated
+    /// material, such as different songs, steady.
+    EqualPower,
+}
+
+impl Curve {
+    /// Gains of the outgoing and incoming audio at `t`, from 0 to 1 over the
+    /// crossfade.
+    pub fn gains(&self, t: f32) -> (f32, f32) {
+        match *self {
+            Curve::Linear => (1.0 - t, t),
+            Curve::EqualPower => ((t * FRAC_PI_2).cos(), (t * FRAC_PI_2).sin()),
+        }
+    }
+}
+
+/// Joins consecutive inputs into one stream, fading the end of each input
+/// out while the start of the next one fades in.
+///
+/// Frames are pushed in planar `f32` at the layout and rate the crossfade is
+/// made for, so inputs are to be resampled first. Output is held back by the
+/// crossfade length until the next input or the end is known.
+pub struct Crossfade {
+    layout: ChannelLayout,
+    rate: u32,
+    overlap: usize,
+    curve: Curve,
+
+    pending: Vec<Vec<f32>>,
+    tail: Vec<Vec<f32>>,
+    mixed: usize,
+
+    started: bool,
+    finished: bool,
+    pts: i64,
+}
+
+impl Crossfade {
+    pub fn new(layout: ChannelLayout, rate: u32, duration: Duration, curve: Curve) -> Self {
+        let channels = layout.channels().max(1) as usize;
+        let overlap = Rational(1, rate as i32).timestamp(duration).max(0) as usize;
+
+        Crossfade {
+            layout,
+            rate,
+            overlap,
+            curve,
+
+            pending: vec![Vec::new(); channels],
+            tail: vec![Vec::new(); channels],
+            mixed: 0,
+
+            started: false,
+            finished: false,
+            pts: 0,
+        }
+    }
+
+    /// Length of the crossfade in samples. Shorter inputs shorten the
+    /// crossfades around them.
+    pub fn overlap(&self) -> usize {
+        self.overlap
+    }
+
+    /// Start the next input, under which the end of the previous one fades
+    /// out.
+    pub fn next_input(&mut self) {
+        if !self.started {
+            self.started = true;
+            return;
+        }
+
+        self.fade_out_tail();
+
+        for (pending, tail) in self.pending.iter_mut().zip(&mut self.tail) {
+            let start = pending.len().saturating_sub(self.overlap);
+            *tail = pending.split_off(start);
+        }
+
+        self.mixed = 0;
+    }
+
+    /// Append a frame of the current input.
+    ///
+    /// Returns `Error::Other { errno: EINVAL }` if the frame is not planar
+    /// `f32` at the layout and rate of the crossfade.
+    pub fn push(&mut self, frame: &frame::Audio) -> Result<(), Error> {
+        if frame.format() != format::Sample::F32(sample::Type::Planar)
+            || frame.channels() as usize != self.pending.len()
+            || frame.raw_rate() != self.rate
+        {
+            return Err(Error::Other { errno: EINVAL });
+        }
+
+        self.started = true;
+
+        let length = self.tail[0].len();
+        let start = self.mixed;
+
+        for (index, (pending, tail)) in self.pending.iter_mut().zip(&self.tail).enumerate() {
+            let mut position = start;
+
+            for &sample in frame.plane::<f32>(index) {
+                if position < length {
+                    let t = (position as f32 + 0.5) / length as f32;
+                    let (out, into) = self.curve.gains(t);
+
+                    pending.push(tail[position] * out + sample * into);
+                    position += 1;
+                } else {
+                    pending.push(sample);
+                }
+            }
+        }
+
+        self.mixed = (start + frame.samples()).min(length);
+
+        Ok(())
+    }
+
+    /// End the last input, letting the rest of the output through.
+    pub fn finish(&mut self) {
+        self.fade_out_tail();
+        self.finished = true;
+    }
+
+    /// Take the next `samples` samples of output, or what is left once
+    /// finished, with timestamps counting samples from 0.
+    pub fn receive(&mut self, samples: usize) -> Option<frame::Audio> {
+        let held = if self.finished { 0 } else { self.overlap };
+        let available = self.pending[0].len().saturating_sub(held);
+
+        if available == 0 || (available < samples && !self.finished) {
+            return None;
+        }
+
+        let count = available.min(samples.max(1));
+        let mut frame = frame::Audio::new(
+            format::Sample::F32(sample::Type::Planar),
+            count,
+            self.layout,
+        );
+
+        frame.set_rate(self.rate);
+        frame.set_pts(Some(self.pts));
+
+        for (index, pending) in self.pending.iter_mut().enumerate() {
+            frame
+                .plane_mut::<f32>(index)
+                .copy_from_slice(&pending[..count]);
+            pending.drain(..count);
+        }
+
+        self.pts += count as i64;
+
+        Some(frame)
+    }
+
+    // An input shorter than the crossfade ends before the previous one has
+    // faded out, which then fades out over silence.
+    fn fade_out_tail(&mut self) {
+        let length = self.tail[0].len();
+
+        for (pending, tail) in self.pending.iter_mut().zip(&mut self.tail) {
+            for position in self.mixed..length {
+                let t = (position as f32 + 0.5) / length as f32;
+                pending.push(tail[position] * self.curve.gains(t).0);
+            }
+
+            tail.clear();
+        }
+
+        self.mixed = 0;
+    }
+}
+
+/// Decode the best audio stream of each of `inputs` and encode them one
+/// after the other with `encoder`, crossfading for `duration` between them.
+///
+/// Packets are written to `stream` of `output`, whose header must be written
+/// already, and the encoder is drained at the end; the trailer is left to
+/// the caller. Inputs are resampled to the rate and layout of the encoder.
+pub fn concat_with_crossfade<P: AsRef<Path>>(
+    inputs: &[P],
+    duration: Duration,
+    curve: Curve,
+    encoder: &mut encoder::audio::Encoder,
+    output: &mut Output,
+    stream: usize,
+) -> Result<(), Error> {
+    let planar = format::Sample::F32(sample::Type::Planar);
+    let layout = encoder.channel_layout();
+    let rate = encoder.raw_rate();
+    let time_base = encoder.time_base();
+    let frame_size = match encoder.frame_size() {
+        0 => 1024,
+        size => size as usize,
+    };
+
+    let mut crossfade = Crossfade::new(layout, rate, duration, curve);
+    let mut converter =
+        resampling::Context::get(planar, layout, rate, encoder.format(), layout, rate)?;
+
+    for path in inputs {
+        let mut input = format::input(path)?;
+        let index = input
+            .streams()
+            .best(media::Type::Audio)
+            .map(|stream| stream.index())
+            .ok_or(Error::StreamNotFound)?;
+
+        crossfade.next_input();
+
+        let mut resampler: Option<resampling::Context> = None;
+
+        for decoded in input.decoded_audio(index)? {
+            let mut decoded = decoded?;
+
+            if decoded.channel_layout().is_empty() {
+                let layout = ChannelLayout::default(i32::from(decoded.channels()));
+                decoded.set_channel_layout(layout);
+            }
+
+            if resampler.is_none() {
+                resampler = Some(resampling::Context::get(
+                    decoded.format(),
+                    decoded.channel_layout(),
+                    decoded.raw_rate(),
+                    planar,
+                    layout,
+                    rate,
+                )?);
+            }
+
+            if let Some(ref mut resampler) = resampler {
+                let mut resampled = frame::Audio::empty();
+                resampler.run(&decoded, &mut resampled)?;
+                crossfade.push(&resampled)?;
+            }
+
+            encode(
+                &mut crossfade,
+                frame_size,
+                &mut converter,
+                encoder,
+                time_base,
+                output,
+                stream,
+            )?;
+        }
+
+        if let Some(ref mut resampler) = resampler {
+            loop {
+                let mut resampled = frame::Audio::empty();
+                resampler.flush(&mut resampled)?;
+
+                if resampled.samples() == 0 {
+                    break;
+                }
+
+                crossfade.push(&resampled)?;
+            }
+        }
+    }
+
+    crossfade.finish();
+    encode(
+        &mut crossfade,
+        frame_size,
+        &mut converter,
+        encoder,
+        time_base,
+        output,
+        stream,
+    )?;
+
+    encoder.send_eof()?;
+    drain(encoder, time_base, output, stream)
+}
+
+fn encode(
+    crossfade: &mut Crossfade,
+    frame_size: usize,
+    converter: &mut resampling::Context,
+    encoder: &mut encoder::audio::Encoder,
+    time_base: Rational,
+    output: &mut Output,
+    stream: usize,
+) -> Result<(), Error> {
+    while let Some(frame) = crossfade.receive(frame_size) {
+        let mut converted = frame::Audio::empty();
+        converter.run(&frame, &mut converted)?;
+
+        let rate = frame.raw_rate() as i32;
+        converted.set_pts(frame.pts().map(|pts| pts.rescale((1, rate), time_base)));
+
+        encoder.send_frame(&converted)?;
+        drain(encoder, time_base, output, stream)?;
+    }
+
+    Ok(())
+}
+
+fn drain(
+    encoder: &mut encoder::audio::Encoder,
+    time_base: Rational,
+    output: &mut Output,
+    stream: usize,
+) -> Result<(), Error> {
+    let mut packet = Packet::empty();
+
+    loop {
+        match encoder.receive_packet(&mut packet) {
+            Ok(()) => {
+                packet.set_stream(stream);
+                output.write_packet(&mut packet, time_base)?;
+            }
+
+            Err(Error::Eof) | Err(Error::Other { errno: EAGAIN }) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curve() {
+        assert_eq!(Curve::Linear.gains(0.25), (0.75, 0.25));
+
+        let (out, into) = Curve::EqualPower.gains(0.5);
+        assert!((out * out + into * into - 1.0).abs() < 1e-6);
+        assert!((out - into).abs() < 1e-6);
+    }
+}
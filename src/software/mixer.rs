@@ -0,0 +1,334 @@
+//! Mixing decoded audio at the sample level.
+
+use std::time::Duration;
+
+use libc::EINVAL;
+use util::format::{self, sample};
+use {frame, ChannelLayout, Error};
+
+/// Lowers every other source while one of them is audible, as music under
+/// a voice-over.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Ducking {
+    /// The source whose signal lowers the others.
+    pub trigger: usize,
+
+    /// Peak level, as a linear amplitude, above which the trigger counts as
+    /// audible.
+    pub threshold: f32,
+
+    /// Gain of the other sources while the trigger is audible.
+    pub gain: f32,
+
+    /// How quickly the other sources are lowered once the trigger starts.
+    pub attack: Duration,
+
+    /// How quickly they come back once it stops, which also bridges short
+    /// pauses such as between words.
+    pub release: Duration,
+}
+
+struct Rule {
+    ducking: Ducking,
+    attack: f32,
+    release: f32,
+
+    peak: f32,
+    factor: f32,
+}
+
+struct Source {
+    gain: f32,
+    pan: f32,
+
+    // Samples from the output position on, per channel.
+    pending: Vec<Vec<f32>>,
+    end: Option<i64>,
+    finished: bool,
+}
+
+/// Sums any number of sources into one stream, lining them up by timestamp.
+///
+/// Frames are pushed in planar `f32` at the layout and rate the mixer is
+/// made for, with timestamps counting samples at that rate, so inputs are to
+/// be resampled and rescaled first. Gaps between frames of a source are
+/// filled with silence and overlaps dropped. Output is held back until every
+/// source still going has pushed up to it, so sources starting late should
+/// push their first frame early or be added later.
+///
+/// Sources are summed as they are: gains should add up to 1 or less for the
+/// output to stay within range.
+pub struct Mixer {
+    layout: ChannelLayout,
+    rate: u32,
+
+    sources: Vec<Source>,
+    rules: Vec<Rule>,
+
+    pts: i64,
+}
+
+impl Mixer {
+    pub fn new(layout: ChannelLayout, rate: u32) -> Self {
+        Mixer {
+            layout,
+            rate,
+
+            sources: Vec::new(),
+            rules: Vec::new(),
+
+            pts: 0,
+        }
+    }
+
+    /// Add a source, returning its index. It starts from the current output
+    /// position, whatever the timestamps of its frames before that.
+    pub fn add_source(&mut self, gain: f32) -> usize {
+        let channels = self.layout.channels().max(1) as usize;
+
+        self.sources.push(Source {
+            gain,
+            pan: 0.0,
+
+            pending: vec![Vec::new(); channels],
+            end: None,
+            finished: false,
+        });
+
+        self.sources.len() - 1
+    }
+
+    pub fn set_gain(&mut self, source: usize, gain: f32) -> Result<(), Error> {
+        self.source(source)?.gain = gain;
+        Ok(())
+    }
+
+    /// Balance of a source between -1 (left only) and 1 (right only), for
+    /// stereo layouts. The side it moves towards stays at full level.
+    pub fn set_pan(&mut self, source: usize, pan: f32) -> Result<(), Error> {
+        self.source(source)?.pan = pan.max(-1.0).min(1.0);
+        Ok(())
+    }
+
+    pub fn duck(&mut self, ducking: Ducking) -> Result<(), Error> {
+        if ducking.trigger >= self.sources.len() {
+            return Err(Error::Other { errno: EINVAL });
+        }
+
+        let rate = self.rate;
+        let coefficient = |duration: Duration| {
+            let samples = duration.as_secs_f32() * rate as f32;
+
+            if samples < 1.0 {
+                0.0
+            } else {
+                (-1.0 / samples).exp()
+            }
+        };
+
+        self.rules.push(Rule {
+            ducking,
+            attack: coefficient(ducking.attack),
+            release: coefficient(ducking.release),
+
+            peak: 0.0,
+            factor: 1.0,
+        });
+
+        Ok(())
+    }
+
+    /// Append a frame of `source`.
+    ///
+    /// Returns `Error::Other { errno: EINVAL }` if the frame is not planar
+    /// `f32` at the layout and rate of the mixer, or the source is finished.
+    pub fn push(&mut self, source: usize, frame: &frame::Audio) -> Result<(), Error> {
+        let (pts, rate) = (self.pts, self.rate);
+        let source = self.source(source)?;
+
+        if frame.format() != format::Sample::F32(sample::Type::Planar)
+            || frame.channels() as usize != source.pending.len()
+            || frame.raw_rate() != rate
+            || source.finished
+        {
+            return Err(Error::Other { errno: EINVAL });
+        }
+
+        let end = source.end.unwrap_or(pts);
+        let at = frame.pts().unwrap_or(end);
+
+        let skip = (end - at).max(0) as usize;
+        let gap = (at - end).max(0) as usize;
+
+        for (index, pending) in source.pending.iter_mut().enumerate() {
+            let samples = frame.plane::<f32>(index);
+
+            pending.resize(pending.len() + gap, 0.0);
+            pending.extend_from_slice(&samples[skip.min(samples.len())..]);
+        }
+
+        source.end = Some(end.max(at + frame.samples() as i64));
+
+        Ok(())
+    }
+
+    /// End `source`, which is silent from then on.
+    pub fn finish(&mut self, source: usize) -> Result<(), Error> {
+        self.source(source)?.finished = true;
+        Ok(())
+    }
+
+    /// Mix the next `samples` samples, or what is left once every source is
+    /// finished, with timestamps counting samples at the mixer rate.
+    pub fn receive(&mut self, samples: usize) -> Option<frame::Audio> {
+        let finished = self.sources.iter().all(|source| source.finished);
+        let available = if finished {
+            self.sources.iter().map(|s| s.pending[0].len()).max()
+        } else {
+            self.sources
+                .iter()
+                .filter(|source| !source.finished)
+                .map(|source| match source.end {
+                    Some(_) => source.pending[0].len(),
+                    None => 0,
+                })
+                .min()
+        };
+
+        let available = available.unwrap_or(0);
+
+        if available == 0 || (available < samples && !finished) {
+            return None;
+        }
+
+        let count = available.min(samples.max(1));
+        let channels = self.layout.channels().max(1) as usize;
+        let mut frame = frame::Audio::new(
+            format::Sample::F32(sample::Type::Planar),
+            count,
+            self.layout,
+        );
+
+        frame.set_rate(self.rate);
+        frame.set_pts(Some(self.pts));
+
+        let mut factors = vec![1.0; self.sources.len()];
+
+        for position in 0..count {
+            self.ducking(position, &mut factors);
+
+            for channel in 0..channels {
+                let mut sum = 0.0;
+
+                for (index, source) in self.sources.iter().enumerate() {
+                    if let Some(&sample) = source.pending[channel].get(position) {
+                        sum += sample
+                            * source.gain
+                            * factors[index]
+                            * balance(source.pan, channel, channels);
+                    }
+                }
+
+                frame.plane_mut::<f32>(channel)[position] = sum;
+            }
+        }
+
+        for source in &mut self.sources {
+            for pending in &mut source.pending {
+                let length = count.min(pending.len());
+                pending.drain(..length);
+            }
+        }
+
+        self.pts += count as i64;
+
+        Some(frame)
+    }
+
+    // Set the gain factor of every source at `position` from the output
+    // position, advancing the ducking envelopes by one sample.
+    fn ducking(&mut self, position: usize, factors: &mut [f32]) {
+        for factor in factors.iter_mut() {
+            *factor = 1.0;
+        }
+
+        for rule in &mut self.rules {
+            let trigger = &self.sources[rule.ducking.trigger];
+            let level = trigger
+                .pending
+                .iter()
+                .filter_map(|pending| pending.get(position))
+                .fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+
+            rule.peak = level.max(rule.peak * rule.release);
+
+            let target = if rule.peak > rule.ducking.threshold {
+                rule.ducking.gain
+            } else {
+                1.0
+            };
+
+            let coefficient = if target < rule.factor {
+                rule.attack
+            } else {
+                rule.release
+            };
+
+            rule.factor = target + (rule.factor - target) * coefficient;
+
+            for (index, factor) in factors.iter_mut().enumerate() {
+                if index != rule.ducking.trigger {
+                    *factor *= rule.factor;
+                }
+            }
+        }
+    }
+
+    fn source(&mut self, index: usize) -> Result<&mut Source, Error> {
+        self.sources
+            .get_mut(index)
+            .ok_or(Error::Other { errno: EINVAL })
+    }
+}
+
+// Gain of `channel` for a balance of `pan`, only applied in stereo.
+fn balance(pan: f32, channel: usize, channels: usize) -> f32 {
+    match (channels, channel) {
+        (2, 0) => (1.0 - pan).min(1.0),
+        (2, 1) => (1.0 + pan).min(1.0),
+        _ => 1.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mixer() {
+        let planar = format::Sample::F32(sample::Type::Planar);
+        let frame = |pts: i64, samples: usize| {
+            let mut frame = frame::Audio::new(planar, samples, ChannelLayout::MONO);
+            frame.set_rate(48000);
+            frame.set_pts(Some(pts));
+            frame.plane_mut::<f32>(0).iter_mut().for_each(|s| *s = 1.0);
+            frame
+        };
+
+        let mut mixer = Mixer::new(ChannelLayout::MONO, 48000);
+        let music = mixer.add_source(0.5);
+        let voice = mixer.add_source(1.0);
+
+        mixer.push(music, &frame(0, 4)).unwrap();
+        assert!(mixer.receive(4).is_none());
+
+        mixer.push(voice, &frame(2, 2)).unwrap();
+        mixer.finish(music).unwrap();
+        mixer.finish(voice).unwrap();
+
+        let mixed = mixer.receive(4).unwrap();
+        assert_eq!(mixed.pts(), Some(0));
+        assert_eq!(mixed.plane::<f32>(0), &[0.5, 0.5, 1.5, 1.5]);
+        assert!(mixer.receive(4).is_none());
+    }
+}
@@ -35,6 +35,11 @@ pub fn converter(
 #[cfg(feature = "software-resampling")]
 pub mod resampling;
 
+#[cfg(all(feature = "software-resampling", feature = "codec", feature = "format"))]
+pub mod audio;
+
+pub mod mixer;
+
 #[cfg(feature = "software-resampling")]
 #[inline]
 pub fn resampler(
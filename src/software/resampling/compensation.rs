@@ -0,0 +1,74 @@
+use Dictionary;
+
+/// Timestamp driven drift compensation, the typed form of the `async`,
+/// `min_comp`, `min_hard_comp`, `max_soft_comp`, `comp_duration` and
+/// `first_pts` resampler options.
+///
+/// Compensation acts on the timestamps passed to `Context::next_pts`, which
+/// must be called before running each input frame. The gap between where a
+/// frame is expected and its timestamp is corrected by stretching or
+/// squeezing the output when at least `min_comp` seconds, and by inserting
+/// silence or dropping samples when at least `min_hard_comp` seconds. Soft
+/// corrections go through `Context::set_compensation`, so calling it by hand
+/// on a compensated resampler is overridden at the next timestamp.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Compensation {
+    /// The `async` option, as `-async` in the ffmpeg tool: `1` only fills
+    /// and trims, larger values also allow stretching or squeezing by at most
+    /// that many samples per second. Any non-zero value lowers an unset
+    /// `min_comp` to a millisecond.
+    pub sync: f32,
+
+    /// Smallest gap in seconds corrected by stretching or squeezing, disabled
+    /// when `None`.
+    pub min_comp: Option<f32>,
+
+    /// Smallest gap in seconds corrected by filling or trimming.
+    pub min_hard_comp: f32,
+
+    /// Largest stretch or squeeze, as a fraction of the output.
+    pub max_soft_comp: f32,
+
+    /// Duration in seconds over which soft corrections are spread.
+    pub comp_duration: f32,
+
+    /// Timestamp of the first output sample, in samples at the output rate.
+    /// When set, a gap before the first input is filled or trimmed too.
+    pub first_pts: Option<i64>,
+}
+
+impl Default for Compensation {
+    /// The resampler defaults, compensating nothing.
+    fn default() -> Self {
+        Compensation {
+            sync: 0.0,
+            min_comp: None,
+            min_hard_comp: 0.1,
+            max_soft_comp: 0.0,
+            comp_duration: 1.0,
+            first_pts: None,
+        }
+    }
+}
+
+impl Compensation {
+    /// Options to create a `Context` with through `Context::get_with`.
+    pub fn options(&self) -> Dictionary {
+        let mut options = Dictionary::new();
+
+        options.set("async", &self.sync.to_string());
+        options.set("min_hard_comp", &self.min_hard_comp.to_string());
+        options.set("max_soft_comp", &self.max_soft_comp.to_string());
+        options.set("comp_duration", &self.comp_duration.to_string());
+
+        if let Some(value) = self.min_comp {
+            options.set("min_comp", &value.to_string());
+        }
+
+        if let Some(value) = self.first_pts {
+            options.set("first_pts", &value.to_string());
+        }
+
+        options
+    }
+}
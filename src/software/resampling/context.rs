@@ -4,7 +4,7 @@ use super::Delay;
 use ffi::*;
 use libc::c_int;
 use std::ffi::c_void;
-use util::format;
+use util::{format, option};
 use Dictionary;
 use {frame, ChannelLayout, Error};
 
@@ -134,6 +134,59 @@ impl Context {
         }
     }
 
+    /// Get the remaining delay in units of `1 / base`, such as samples at the
+    /// input rate when `base` is that rate.
+    pub fn delay_in(&self, base: i64) -> i64 {
+        unsafe { swr_get_delay(self.as_ptr() as *mut _, base) }
+    }
+
+    /// Convert the timestamp of the next input into the timestamp of the
+    /// next output, compensating drift along the way if enabled.
+    ///
+    /// Both are in units of `1 / (input rate * output rate)`; pass
+    /// `i64::min_value()` to get the next output timestamp without giving an
+    /// input one. Compensation only happens when the context was created
+    /// with options allowing it, see `Compensation`.
+    pub fn next_pts(&mut self, pts: i64) -> i64 {
+        unsafe { swr_next_pts(self.as_mut_ptr(), pts) }
+    }
+
+    /// Stretch or squeeze the output by `delta` samples, spread over the next
+    /// `distance` output samples, to correct clock drift.
+    pub fn set_compensation(&mut self, delta: i32, distance: i32) -> Result<(), Error> {
+        unsafe {
+            match swr_set_compensation(self.as_mut_ptr(), delta as c_int, distance as c_int) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Initialize the resampler again, applying options set since, and
+    /// dropping any buffered samples.
+    ///
+    /// Formats, layouts and rates changed through options are not reflected
+    /// by `input` and `output`.
+    pub fn reinit(&mut self) -> Result<(), Error> {
+        unsafe {
+            match swr_init(self.as_mut_ptr()) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Insert `count` samples of silence ahead of the buffered input, to fill
+    /// a gap in the input.
+    pub fn inject_silence(&mut self, count: usize) -> Result<(), Error> {
+        unsafe {
+            match swr_inject_silence(self.as_mut_ptr(), count as c_int) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
     /// Run the resampler from the given input to the given output.
     ///
     /// When there are internal frames to process it will return `Ok(Some(Delay { .. }))`.
@@ -177,6 +230,20 @@ impl Context {
     }
 }
 
+// Options shaping the conversion only take effect on `reinit`.
+unsafe impl option::Target for Context {
+    fn as_ptr(&self) -> *const c_void {
+        self.ptr as *const _
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.ptr as *mut _
+    }
+}
+
+impl option::Settable for Context {}
+impl option::Gettable for Context {}
+
 impl Drop for Context {
     fn drop(&mut self) {
         unsafe {
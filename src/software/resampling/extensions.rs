@@ -13,7 +13,7 @@ impl frame::Audio {
         Context::get(
             self.format(),
             self.channel_layout(),
-            self.rate(),
+            self.raw_rate(),
             format,
             channel_layout,
             rate,
@@ -32,7 +32,7 @@ impl decoder::Audio {
         Context::get(
             self.format(),
             self.channel_layout(),
-            self.rate(),
+            self.raw_rate(),
             format,
             channel_layout,
             rate,
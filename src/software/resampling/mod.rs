@@ -13,9 +13,18 @@ pub use self::filter::Filter;
 pub mod delay;
 pub use self::delay::Delay;
 
+pub mod compensation;
+pub use self::compensation::Compensation;
+
 pub mod context;
 pub use self::context::Context;
 
+pub mod pcm;
+pub use self::pcm::Pcm;
+
+pub mod negotiate;
+pub use self::negotiate::{negotiate, Negotiated};
+
 mod extensions;
 
 use std::ffi::CStr;
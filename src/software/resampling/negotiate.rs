@@ -0,0 +1,147 @@
+//! Choosing what an audio encoder is fed, and the resampler that gets it
+//! there from what a decoder or filter graph produces.
+
+use super::context::{Context, Definition};
+use codec;
+use libc::EINVAL;
+use {ChannelLayout, Dictionary, Error};
+
+/// Which target layouts are acceptable when the encoder does not take the
+/// source layout as is.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Policy {
+    /// The supported layout with the most channels not above the source
+    /// channel count, as `codec::Audio::pick_best_channel_layout`.
+    Nearest,
+
+    /// Only downmix: the supported layout with the most channels not above
+    /// the source channel count, preferring the one sharing the most
+    /// channels with the source.
+    DownmixOnly,
+
+    /// Never drop channels: the supported layout with the fewest channels
+    /// not below the source channel count, preferring the one sharing the
+    /// most channels with the source.
+    NoDownmix,
+
+    /// Exactly this layout, which the encoder must support.
+    Fixed(ChannelLayout),
+}
+
+/// Levels of the channels folded into others when downmixing, as linear
+/// gains, `None` leaving the libswresample default of -3 dB.
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+pub struct Mix {
+    pub center: Option<f64>,
+    pub surround: Option<f64>,
+    pub lfe: Option<f64>,
+}
+
+impl Mix {
+    fn to_dictionary(&self) -> Dictionary<'static> {
+        let mut dictionary = Dictionary::new();
+
+        if let Some(center) = self.center {
+            dictionary.set("clev", &center.to_string());
+        }
+
+        if let Some(surround) = self.surround {
+            dictionary.set("slev", &surround.to_string());
+        }
+
+        if let Some(lfe) = self.lfe {
+            dictionary.set("lfe_mix_level", &lfe.to_string());
+        }
+
+        dictionary
+    }
+}
+
+/// Pick the channel layout `encoder` should be fed for `source` under
+/// `policy`, without looking at sample formats or rates.
+///
+/// Returns `Error::Other { errno: EINVAL }` when no supported layout is
+/// acceptable.
+pub fn channel_layout(
+    source: ChannelLayout,
+    encoder: &codec::Audio,
+    policy: Policy,
+) -> Result<ChannelLayout, Error> {
+    if let Policy::Fixed(layout) = policy {
+        return if encoder.supports_channel_layout(layout) {
+            Ok(layout)
+        } else {
+            Err(Error::Other { errno: EINVAL })
+        };
+    }
+
+    let layouts = match encoder.channel_layouts() {
+        Some(_) if encoder.supports_channel_layout(source) => return Ok(source),
+        Some(layouts) => layouts,
+        None => return Ok(source),
+    };
+
+    let channels = source.channels();
+    let shared = |layout: ChannelLayout| (layout & source).channels();
+
+    let chosen = match policy {
+        Policy::Nearest => Some(layouts.best(channels)),
+
+        Policy::DownmixOnly => layouts
+            .filter(|layout| layout.channels() <= channels)
+            .max_by_key(|&layout| (layout.channels(), shared(layout))),
+
+        Policy::NoDownmix => layouts
+            .filter(|layout| layout.channels() >= channels)
+            .min_by_key(|&layout| (layout.channels(), -shared(layout))),
+
+        Policy::Fixed(_) => unreachable!(),
+    };
+
+    chosen.ok_or(Error::Other { errno: EINVAL })
+}
+
+/// The outcome of `negotiate`.
+pub struct Negotiated {
+    /// What the encoder is to be opened with.
+    pub output: Definition,
+
+    /// Converts `source` into `output`.
+    pub resampler: Context,
+}
+
+/// Choose the sample format, rate and channel layout `encoder` should be fed
+/// for audio in `source`, and set up the resampler converting to them.
+///
+/// The channel layout follows `policy`, folding channels with the levels in
+/// `mix` when downmixing; the format and rate are the closest supported
+/// ones. A source with an unknown layout, as some decoders report, should be
+/// given `ChannelLayout::default(channels)` first.
+pub fn negotiate(
+    source: &Definition,
+    encoder: &codec::Audio,
+    policy: Policy,
+    mix: &Mix,
+) -> Result<Negotiated, Error> {
+    if source.channel_layout.is_empty() {
+        return Err(Error::Other { errno: EINVAL });
+    }
+
+    let output = Definition {
+        format: encoder.pick_best_format(source.format),
+        channel_layout: channel_layout(source.channel_layout, encoder, policy)?,
+        rate: encoder.pick_best_rate(source.rate as i32) as u32,
+    };
+
+    let resampler = Context::get_with(
+        source.format,
+        source.channel_layout,
+        source.rate,
+        output.format,
+        output.channel_layout,
+        output.rate,
+        mix.to_dictionary(),
+    )?;
+
+    Ok(Negotiated { output, resampler })
+}
@@ -0,0 +1,167 @@
+use std::{ptr, slice};
+
+use super::{Context, Dither};
+use ffi::SwrDitherType;
+use util::format;
+use util::format::sample::Type::Packed;
+use {frame, ChannelLayout, Dictionary, Error};
+
+/// Interleaved sample types a frame can be exported to or imported from.
+///
+/// # Safety
+///
+/// Samples are copied to and from frame buffers as raw memory, so the type
+/// must have the exact size and representation of one sample of `format()`,
+/// which must be a packed format.
+pub unsafe trait Pcm: Copy {
+    fn format() -> format::Sample;
+}
+
+unsafe impl Pcm for u8 {
+    #[inline(always)]
+    fn format() -> format::Sample {
+        format::Sample::U8(Packed)
+    }
+}
+
+unsafe impl Pcm for i16 {
+    #[inline(always)]
+    fn format() -> format::Sample {
+        format::Sample::I16(Packed)
+    }
+}
+
+unsafe impl Pcm for i32 {
+    #[inline(always)]
+    fn format() -> format::Sample {
+        format::Sample::I32(Packed)
+    }
+}
+
+unsafe impl Pcm for f32 {
+    #[inline(always)]
+    fn format() -> format::Sample {
+        format::Sample::F32(Packed)
+    }
+}
+
+unsafe impl Pcm for f64 {
+    #[inline(always)]
+    fn format() -> format::Sample {
+        format::Sample::F64(Packed)
+    }
+}
+
+impl frame::Audio {
+    /// Export the samples as an interleaved `Vec`, converting the sample
+    /// format with the given dithering.
+    ///
+    /// Channel layout and sample rate are preserved; conversions to integer
+    /// formats are clipped by the resampler.
+    pub fn to_pcm<T: Pcm>(&self, dither: Dither) -> Result<Vec<T>, Error> {
+        let layout = layout(self.channel_layout(), self.channels());
+
+        if self.samples() == 0 {
+            return Ok(Vec::new());
+        }
+
+        if self.format() == T::format() {
+            return Ok(unsafe { interleaved(self).to_vec() });
+        }
+
+        let mut context = converter(
+            (self.format(), layout),
+            (T::format(), layout),
+            self.raw_rate(),
+            dither,
+        )?;
+
+        let mut input = frame::Audio::from(self.clone_shallow()?);
+        input.set_channel_layout(layout);
+
+        let mut output = frame::Audio::empty();
+        context.run(&input, &mut output)?;
+
+        Ok(unsafe { interleaved(&output).to_vec() })
+    }
+
+    /// Build a frame in `format` from interleaved samples, converting the
+    /// sample format with the given dithering.
+    pub fn from_pcm<T: Pcm>(
+        data: &[T],
+        layout: ChannelLayout,
+        rate: u32,
+        format: format::Sample,
+        dither: Dither,
+    ) -> Result<Self, Error> {
+        let channels = layout.channels() as usize;
+
+        if channels == 0 || data.len() % channels != 0 {
+            return Err(Error::InvalidData);
+        }
+
+        // An empty frame has no buffer to copy into.
+        if data.is_empty() {
+            let mut frame = frame::Audio::empty();
+            frame.set_format(format);
+            frame.set_channel_layout(layout);
+            frame.set_channels(channels as u16);
+            frame.set_rate(rate);
+
+            return Ok(frame);
+        }
+
+        let mut input = frame::Audio::new(T::format(), data.len() / channels, layout);
+        input.set_rate(rate);
+
+        unsafe {
+            ptr::copy_nonoverlapping(
+                data.as_ptr(),
+                (*input.as_mut_ptr()).data[0] as *mut T,
+                data.len(),
+            );
+        }
+
+        if format == T::format() {
+            return Ok(input);
+        }
+
+        let mut context = converter((T::format(), layout), (format, layout), rate, dither)?;
+        let mut output = frame::Audio::empty();
+        context.run(&input, &mut output)?;
+
+        Ok(output)
+    }
+}
+
+fn layout(layout: ChannelLayout, channels: u16) -> ChannelLayout {
+    if layout.is_empty() {
+        ChannelLayout::default(i32::from(channels))
+    } else {
+        layout
+    }
+}
+
+fn converter(
+    (in_format, in_layout): (format::Sample, ChannelLayout),
+    (out_format, out_layout): (format::Sample, ChannelLayout),
+    rate: u32,
+    dither: Dither,
+) -> Result<Context, Error> {
+    let mut options = Dictionary::new();
+    options.set(
+        "dither_method",
+        &(SwrDitherType::from(dither) as i32).to_string(),
+    );
+
+    Context::get_with(
+        in_format, in_layout, rate, out_format, out_layout, rate, options,
+    )
+}
+
+unsafe fn interleaved<T: Pcm>(frame: &frame::Audio) -> &[T] {
+    slice::from_raw_parts(
+        (*frame.as_ptr()).data[0] as *const T,
+        frame.samples() * frame.channels() as usize,
+    )
+}
@@ -1,5 +1,6 @@
 use ffi::*;
 use libc::c_int;
+use util::color;
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
 pub enum ColorSpace {
@@ -11,6 +12,7 @@ pub enum ColorSpace {
     ITU624,
     SMPTE170M,
     SMPTE240M,
+    BT2020,
 }
 
 impl From<c_int> for ColorSpace {
@@ -20,6 +22,7 @@ impl From<c_int> for ColorSpace {
             SWS_CS_FCC => ColorSpace::FCC,
             SWS_CS_DEFAULT => ColorSpace::Default,
             SWS_CS_SMPTE240M => ColorSpace::SMPTE240M,
+            SWS_CS_BT2020 => ColorSpace::BT2020,
 
             _ => ColorSpace::Default,
         }
@@ -36,6 +39,21 @@ impl From<ColorSpace> for c_int {
             ColorSpace::ITU624 => SWS_CS_ITU624,
             ColorSpace::SMPTE170M => SWS_CS_SMPTE170M,
             ColorSpace::SMPTE240M => SWS_CS_SMPTE240M,
+            ColorSpace::BT2020 => SWS_CS_BT2020,
+        }
+    }
+}
+
+impl From<color::Space> for ColorSpace {
+    fn from(value: color::Space) -> ColorSpace {
+        match value {
+            color::Space::BT709 => ColorSpace::ITU709,
+            color::Space::FCC => ColorSpace::FCC,
+            color::Space::BT470BG | color::Space::SMPTE170M => ColorSpace::ITU601,
+            color::Space::SMPTE240M => ColorSpace::SMPTE240M,
+            color::Space::BT2020NCL | color::Space::BT2020CL => ColorSpace::BT2020,
+
+            _ => ColorSpace::Default,
         }
     }
 }
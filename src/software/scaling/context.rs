@@ -1,9 +1,10 @@
+use std::cmp;
 use std::ptr;
 
-use super::Flags;
+use super::{rows, ColorSpace, Flags};
 use ffi::*;
-use libc::c_int;
-use util::format;
+use libc::{c_int, c_void, EINVAL};
+use util::{color, format, option};
 use {frame, Error};
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
@@ -119,6 +120,52 @@ impl Context {
         }
     }
 
+    /// Set the YUV coefficients and ranges of both sides, which otherwise
+    /// default to BT.601 limited range whatever the frames are tagged with.
+    ///
+    /// Unspecified ranges are taken as limited for YUV formats. Fails when
+    /// the conversion does not involve YUV, where the details do not apply.
+    pub fn set_colorspace(
+        &mut self,
+        (src_space, src_range): (color::Space, color::Range),
+        (dst_space, dst_range): (color::Space, color::Range),
+    ) -> Result<(), Error> {
+        unsafe {
+            let mut inv_table = ptr::null_mut();
+            let mut table = ptr::null_mut();
+            let (mut in_full, mut out_full) = (0, 0);
+            let (mut brightness, mut contrast, mut saturation) = (0, 0, 0);
+
+            if sws_getColorspaceDetails(
+                self.as_mut_ptr(),
+                &mut inv_table,
+                &mut in_full,
+                &mut table,
+                &mut out_full,
+                &mut brightness,
+                &mut contrast,
+                &mut saturation,
+            ) < 0
+            {
+                return Err(Error::InvalidData);
+            }
+
+            match sws_setColorspaceDetails(
+                self.as_mut_ptr(),
+                sws_getCoefficients(ColorSpace::from(src_space).into()),
+                full(src_range, self.input.format),
+                sws_getCoefficients(ColorSpace::from(dst_space).into()),
+                full(dst_range, self.output.format),
+                brightness,
+                contrast,
+                saturation,
+            ) {
+                e if e < 0 => Err(Error::InvalidData),
+                _ => Ok(()),
+            }
+        }
+    }
+
     #[inline]
     pub fn input(&self) -> &Definition {
         &self.input
@@ -131,32 +178,70 @@ impl Context {
 
     pub fn run(&mut self, input: &frame::Video, output: &mut frame::Video) -> Result<(), Error> {
         if input.format() != self.input.format
-            || input.width() != self.input.width
-            || input.height() != self.input.height
+            || input.raw_width() != self.input.width
+            || input.raw_height() != self.input.height
         {
             return Err(Error::InputChanged);
         }
 
+        self.prepare(output)?;
+
         unsafe {
-            if output.is_empty() {
-                output.alloc(self.output.format, self.output.width, self.output.height);
-            }
+            sws_scale(
+                self.as_mut_ptr(),
+                (*input.as_ptr()).data.as_ptr() as *const *const _,
+                (*input.as_ptr()).linesize.as_ptr() as *const _,
+                0,
+                self.input.height as c_int,
+                (*output.as_mut_ptr()).data.as_ptr() as *const *mut _,
+                (*output.as_mut_ptr()).linesize.as_ptr() as *mut _,
+            );
         }
 
-        if output.format() != self.output.format
-            || output.width() != self.output.width
-            || output.height() != self.output.height
+        Ok(())
+    }
+
+    /// Scale rows `y` to `y + height` of `input`, for inputs arriving
+    /// progressively, such as from a decoder exporting slices.
+    ///
+    /// Slices have to be passed in order, from top to bottom, and `y` has to
+    /// be a multiple of the vertical chroma subsampling of the input format.
+    /// The output is complete once the last row went through.
+    pub fn run_slice(
+        &mut self,
+        input: &frame::Video,
+        y: u32,
+        height: u32,
+        output: &mut frame::Video,
+    ) -> Result<(), Error> {
+        if input.format() != self.input.format
+            || input.raw_width() != self.input.width
+            || input.raw_height() != self.input.height
         {
-            return Err(Error::OutputChanged);
+            return Err(Error::InputChanged);
         }
 
+        let align = self
+            .input
+            .format
+            .descriptor()
+            .map_or(1, |d| 1 << d.log2_chroma_h());
+
+        if y % align != 0 || height == 0 || y + height > self.input.height {
+            return Err(Error::Other { errno: EINVAL });
+        }
+
+        self.prepare(output)?;
+
         unsafe {
+            let (data, linesize) = rows(input, y);
+
             sws_scale(
                 self.as_mut_ptr(),
-                (*input.as_ptr()).data.as_ptr() as *const *const _,
-                (*input.as_ptr()).linesize.as_ptr() as *const _,
-                0,
-                self.input.height as c_int,
+                data.as_ptr() as *const *const _,
+                linesize.as_ptr(),
+                y as c_int,
+                height as c_int,
                 (*output.as_mut_ptr()).data.as_ptr() as *const *mut _,
                 (*output.as_mut_ptr()).linesize.as_ptr() as *mut _,
             );
@@ -164,8 +249,117 @@ impl Context {
 
         Ok(())
     }
+
+    /// Scale the input in horizontal bands of `rows` lines, so it never has to
+    /// be held in memory at once.
+    ///
+    /// `source` is called top to bottom with the first row of each band and a
+    /// frame in the input format to fill, whose height is the number of rows
+    /// in the band. `rows` is rounded up to the vertical chroma subsampling of
+    /// the input format.
+    pub fn run_slices<F>(
+        &mut self,
+        rows: u32,
+        mut source: F,
+        output: &mut frame::Video,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(u32, &mut frame::Video) -> Result<(), Error>,
+    {
+        self.prepare(output)?;
+
+        let align = self
+            .input
+            .format
+            .descriptor()
+            .map_or(1, |d| 1 << d.log2_chroma_h());
+        let rows = (cmp::max(rows, 1) + align - 1) / align * align;
+
+        let mut band = frame::Video::new(self.input.format, self.input.width, rows);
+        let mut y = 0;
+
+        while y < self.input.height {
+            let height = cmp::min(rows, self.input.height - y);
+
+            band.set_height(height);
+            source(y, &mut band)?;
+
+            if band.format() != self.input.format
+                || band.raw_width() != self.input.width
+                || band.raw_height() != height
+            {
+                return Err(Error::InputChanged);
+            }
+
+            unsafe {
+                sws_scale(
+                    self.as_mut_ptr(),
+                    (*band.as_ptr()).data.as_ptr() as *const *const _,
+                    (*band.as_ptr()).linesize.as_ptr() as *const _,
+                    y as c_int,
+                    height as c_int,
+                    (*output.as_mut_ptr()).data.as_ptr() as *const *mut _,
+                    (*output.as_mut_ptr()).linesize.as_ptr() as *mut _,
+                );
+            }
+
+            y += height;
+        }
+
+        Ok(())
+    }
+
+    fn prepare(&self, output: &mut frame::Video) -> Result<(), Error> {
+        unsafe {
+            if output.is_empty() {
+                output.alloc(self.output.format, self.output.width, self.output.height);
+            }
+        }
+
+        if output.format() != self.output.format
+            || output.raw_width() != self.output.width
+            || output.raw_height() != self.output.height
+        {
+            return Err(Error::OutputChanged);
+        }
+
+        Ok(())
+    }
+}
+
+// RGB and the deprecated YUVJ formats are full range unless tagged otherwise.
+fn full(range: color::Range, format: format::Pixel) -> c_int {
+    match range {
+        color::Range::JPEG => 1,
+        color::Range::MPEG => 0,
+        color::Range::Unspecified => match format {
+            format::Pixel::YUVJ420P
+            | format::Pixel::YUVJ422P
+            | format::Pixel::YUVJ444P
+            | format::Pixel::YUVJ440P
+            | format::Pixel::YUVJ411P => 1,
+
+            _ => format.descriptor().map_or(0, |d| unsafe {
+                ((*d.as_ptr()).flags & AV_PIX_FMT_FLAG_RGB as u64 != 0) as c_int
+            }),
+        },
+    }
+}
+
+// Options shaping the conversion are read when the context is created, so
+// they can be inspected but not set afterwards.
+unsafe impl option::Target for Context {
+    fn as_ptr(&self) -> *const c_void {
+        self.ptr as *const _
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.ptr as *mut _
+    }
 }
 
+impl option::Gettable for Context {}
+
 impl Drop for Context {
     fn drop(&mut self) {
         unsafe {
@@ -1,5 +1,5 @@
 use super::{Context, Flags};
-use util::format;
+use util::{color, format};
 use {decoder, frame, Error, Picture};
 
 impl<'a> Picture<'a> {
@@ -31,12 +31,35 @@ impl<'a> Picture<'a> {
 }
 
 impl frame::Video {
+    /// A converter to `format` honoring the colorspace and range the frame is
+    /// tagged with, and producing the given ones.
+    ///
+    /// Untagged YUV is assumed to be BT.709 above 576 lines and BT.601 below,
+    /// as players do.
+    pub fn color_converter(
+        &self,
+        format: format::Pixel,
+        (space, range): (color::Space, color::Range),
+    ) -> Result<Context, Error> {
+        let mut context = self.converter(format)?;
+
+        let source = match self.color_space() {
+            color::Space::Unspecified if self.raw_height() > 576 => color::Space::BT709,
+            color::Space::Unspecified => color::Space::BT470BG,
+            value => value,
+        };
+
+        context.set_colorspace((source, self.color_range()), (space, range))?;
+
+        Ok(context)
+    }
+
     #[inline]
     pub fn scaler(&self, width: u32, height: u32, flags: Flags) -> Result<Context, Error> {
         Context::get(
             self.format(),
-            self.width(),
-            self.height(),
+            self.raw_width(),
+            self.raw_height(),
             self.format(),
             width,
             height,
@@ -48,11 +71,11 @@ impl frame::Video {
     pub fn converter(&self, format: format::Pixel) -> Result<Context, Error> {
         Context::get(
             self.format(),
-            self.width(),
-            self.height(),
+            self.raw_width(),
+            self.raw_height(),
             format,
-            self.width(),
-            self.height(),
+            self.raw_width(),
+            self.raw_height(),
             Flags::FAST_BILINEAR,
         )
     }
@@ -63,8 +86,8 @@ impl decoder::Video {
     pub fn scaler(&self, width: u32, height: u32, flags: Flags) -> Result<Context, Error> {
         Context::get(
             self.format(),
-            self.width(),
-            self.height(),
+            self.raw_width(),
+            self.raw_height(),
             self.format(),
             width,
             height,
@@ -76,11 +99,11 @@ impl decoder::Video {
     pub fn converter(&self, format: format::Pixel) -> Result<Context, Error> {
         Context::get(
             self.format(),
-            self.width(),
-            self.height(),
+            self.raw_width(),
+            self.raw_height(),
             format,
-            self.width(),
-            self.height(),
+            self.raw_width(),
+            self.raw_height(),
             Flags::FAST_BILINEAR,
         )
     }
@@ -15,12 +15,17 @@ pub use self::filter::Filter;
 pub mod context;
 pub use self::context::Context;
 
+pub mod parallel;
+pub use self::parallel::Parallel;
+
 mod extensions;
 
 use std::ffi::CStr;
 use std::str::from_utf8_unchecked;
 
 use ffi::*;
+use libc::c_int;
+use util::frame;
 
 pub fn version() -> u32 {
     unsafe { swscale_version() }
@@ -33,3 +38,23 @@ pub fn configuration() -> &'static str {
 pub fn license() -> &'static str {
     unsafe { from_utf8_unchecked(CStr::from_ptr(swscale_license()).to_bytes()) }
 }
+
+// Pointers to row `y` of every plane of `frame`, which has to be a multiple of
+// the vertical chroma subsampling. Non-plane pointers, such as palettes, are
+// passed through as is.
+unsafe fn rows(frame: &frame::Video, y: u32) -> ([*mut u8; 8], [c_int; 8]) {
+    let mut data = (*frame.as_ptr()).data;
+    let linesize = (*frame.as_ptr()).linesize;
+    let shift = frame.format().descriptor().map_or(0, |d| d.log2_chroma_h());
+
+    for (index, pointer) in data.iter_mut().enumerate().take(frame.planes()) {
+        let row = match index {
+            1 | 2 => y >> shift,
+            _ => y,
+        };
+
+        *pointer = pointer.offset(row as isize * linesize[index] as isize);
+    }
+
+    (data, linesize)
+}
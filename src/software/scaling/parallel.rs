@@ -0,0 +1,324 @@
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use super::context::Definition;
+use super::{rows, Context, Flags};
+use ffi::*;
+use libc::c_int;
+use util::{color, format};
+use {frame, Error};
+
+/// Scales frames on several threads, each converting its own horizontal band
+/// of the picture with a context of its own.
+///
+/// Bands are cut where input and output rows line up, so a band never needs
+/// rows of another, and scaled independently: vertical filters see the band
+/// edges as picture edges, which can leave faint seams when scaling
+/// vertically with a filter wider than bilinear. Sizes without enough
+/// matching rows get fewer bands, down to a single one.
+pub struct Parallel {
+    input: Definition,
+    output: Definition,
+
+    bands: Vec<Band>,
+    done: Receiver<Result<(), Error>>,
+}
+
+struct Band {
+    input: u32,
+    output: u32,
+
+    jobs: Option<Sender<Command>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+enum Command {
+    Scale {
+        src: [*mut u8; 8],
+        src_stride: [c_int; 8],
+        dst: [*mut u8; 8],
+        dst_stride: [c_int; 8],
+    },
+
+    Colorspace((color::Space, color::Range), (color::Space, color::Range)),
+}
+
+// The frames behind the pointers outlive the command, `run` waiting for
+// every band to be done before returning.
+unsafe impl Send for Command {}
+
+impl Parallel {
+    pub fn get(
+        src_format: format::Pixel,
+        src_w: u32,
+        src_h: u32,
+        dst_format: format::Pixel,
+        dst_w: u32,
+        dst_h: u32,
+        flags: Flags,
+        threads: usize,
+    ) -> Result<Self, Error> {
+        let (sender, done) = channel();
+        let mut bands = Vec::new();
+
+        for (input, output, input_h, output_h) in
+            split((src_format, src_h), (dst_format, dst_h), threads.max(1))
+        {
+            let mut context = Context::get(
+                src_format, src_w, input_h, dst_format, dst_w, output_h, flags,
+            )?;
+
+            let (jobs, receiver) = channel::<Command>();
+            let sender = sender.clone();
+
+            let thread = thread::spawn(move || {
+                for command in receiver {
+                    let result = match command {
+                        Command::Scale {
+                            src,
+                            src_stride,
+                            dst,
+                            dst_stride,
+                        } => unsafe {
+                            sws_scale(
+                                context.as_mut_ptr(),
+                                src.as_ptr() as *const *const _,
+                                src_stride.as_ptr(),
+                                0,
+                                input_h as c_int,
+                                dst.as_ptr(),
+                                dst_stride.as_ptr(),
+                            );
+
+                            Ok(())
+                        },
+
+                        Command::Colorspace(source, destination) => {
+                            context.set_colorspace(source, destination)
+                        }
+                    };
+
+                    if sender.send(result).is_err() {
+                        break;
+                    }
+                }
+            });
+
+            bands.push(Band {
+                input,
+                output,
+
+                jobs: Some(jobs),
+                thread: Some(thread),
+            });
+        }
+
+        Ok(Parallel {
+            input: Definition {
+                format: src_format,
+                width: src_w,
+                height: src_h,
+            },
+
+            output: Definition {
+                format: dst_format,
+                width: dst_w,
+                height: dst_h,
+            },
+
+            bands,
+            done,
+        })
+    }
+
+    #[inline]
+    pub fn input(&self) -> &Definition {
+        &self.input
+    }
+
+    #[inline]
+    pub fn output(&self) -> &Definition {
+        &self.output
+    }
+
+    /// Number of bands, and so of threads, the picture is split in.
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.bands.len()
+    }
+
+    pub fn run(&mut self, input: &frame::Video, output: &mut frame::Video) -> Result<(), Error> {
+        if input.format() != self.input.format
+            || input.raw_width() != self.input.width
+            || input.raw_height() != self.input.height
+        {
+            return Err(Error::InputChanged);
+        }
+
+        unsafe {
+            if output.is_empty() {
+                output.alloc(self.output.format, self.output.width, self.output.height);
+            }
+        }
+
+        if output.format() != self.output.format
+            || output.raw_width() != self.output.width
+            || output.raw_height() != self.output.height
+        {
+            return Err(Error::OutputChanged);
+        }
+
+        let commands = self
+            .bands
+            .iter()
+            .map(|band| unsafe {
+                let (src, src_stride) = rows(input, band.input);
+                let (dst, dst_stride) = rows(output, band.output);
+
+                Command::Scale {
+                    src,
+                    src_stride,
+                    dst,
+                    dst_stride,
+                }
+            })
+            .collect();
+
+        self.broadcast(commands)
+    }
+
+    /// Set the colorspace details of every band, as with
+    /// `Context::set_colorspace`.
+    pub fn set_colorspace(
+        &mut self,
+        source: (color::Space, color::Range),
+        destination: (color::Space, color::Range),
+    ) -> Result<(), Error> {
+        let commands = self
+            .bands
+            .iter()
+            .map(|_| Command::Colorspace(source, destination))
+            .collect();
+
+        self.broadcast(commands)
+    }
+
+    // Hand a command to every band and wait for all of them, even after a
+    // failure, since the others may still be writing to the output.
+    fn broadcast(&mut self, commands: Vec<Command>) -> Result<(), Error> {
+        let mut sent = 0;
+
+        for (band, command) in self.bands.iter().zip(commands) {
+            if let Some(ref jobs) = band.jobs {
+                if jobs.send(command).is_ok() {
+                    sent += 1;
+                }
+            }
+        }
+
+        let mut result = if sent < self.bands.len() {
+            Err(Error::Bug)
+        } else {
+            Ok(())
+        };
+
+        for _ in 0..sent {
+            match self.done.recv() {
+                Ok(Ok(())) => (),
+                Ok(Err(error)) => result = result.and(Err(error)),
+                Err(_) => return Err(Error::Bug),
+            }
+        }
+
+        result
+    }
+}
+
+impl Drop for Parallel {
+    fn drop(&mut self) {
+        for band in &mut self.bands {
+            band.jobs.take();
+
+            if let Some(thread) = band.thread.take() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+// Cut both pictures in up to `threads` bands, returning the first input and
+// output rows of each band along with their heights.
+//
+// Bands start on rows where the scaling ratio is exact and the chroma
+// subsampling of both formats is respected.
+fn split(
+    (src_format, src_h): (format::Pixel, u32),
+    (dst_format, dst_h): (format::Pixel, u32),
+    threads: usize,
+) -> Vec<(u32, u32, u32, u32)> {
+    let align = |format: format::Pixel| format.descriptor().map_or(1, |d| 1 << d.log2_chroma_h());
+    let (src_align, dst_align) = (align(src_format), align(dst_format));
+
+    let common = gcd(src_h, dst_h).max(1);
+    let (src_unit, dst_unit) = (src_h / common, dst_h / common);
+
+    let mut step = 1;
+
+    while (step * src_unit) % src_align != 0 || (step * dst_unit) % dst_align != 0 {
+        step += 1;
+    }
+
+    let units = (common / step) as usize;
+    let count = threads.min(units).max(1);
+
+    (0..count)
+        .map(|i| {
+            let first = (i * units / count) as u32 * step;
+            let last = ((i + 1) * units / count) as u32 * step;
+
+            let (input, output) = (first * src_unit, first * dst_unit);
+            let (input_end, output_end) = if i + 1 == count {
+                (src_h, dst_h)
+            } else {
+                (last * src_unit, last * dst_unit)
+            };
+
+            (input, output, input_end - input, output_end - output)
+        })
+        .collect()
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let bands = split(
+            (format::Pixel::YUV420P, 2160),
+            (format::Pixel::YUV420P, 1080),
+            4,
+        );
+
+        assert_eq!(bands.len(), 4);
+        assert_eq!(bands[1], (540, 270, 540, 270));
+        assert_eq!(bands[3].0 + bands[3].2, 2160);
+
+        let bands = split(
+            (format::Pixel::YUV420P, 1080),
+            (format::Pixel::YUV420P, 720),
+            8,
+        );
+
+        assert!(bands.iter().all(|b| b.0 % 2 == 0 && b.1 % 2 == 0));
+        assert!(bands.iter().all(|b| b.0 * 2 == b.1 * 3));
+    }
+}
@@ -0,0 +1,249 @@
+//! Deterministic media fixtures and golden-file comparison for tests.
+//!
+//! Everything is generated in-process through `lavfi` sources, so no sample
+//! files need to be checked in. Functions that depend on optional FFmpeg
+//! components report their absence instead of failing, letting callers skip.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use ffi::*;
+use libc::EAGAIN;
+use util::format::sample;
+use {codec, encoder, filter, format, frame, ChannelLayout, Error, Frame, Packet, Rational};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Fixture {
+    pub width: u32,
+    pub height: u32,
+    pub frame_rate: i32,
+
+    pub sample_rate: u32,
+    pub frequency: u32,
+
+    /// Length of both streams in seconds.
+    pub duration: u32,
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Fixture {
+            width: 64,
+            height: 48,
+            frame_rate: 10,
+
+            sample_rate: 8000,
+            frequency: 440,
+
+            duration: 1,
+        }
+    }
+}
+
+/// Generate SMPTE color bars as YUV420P frames with pts in `1/frame_rate`.
+pub fn color_bars(fixture: &Fixture) -> Result<Vec<frame::Video>, Error> {
+    let spec = format!(
+        "smptebars=size={}x{}:rate={}:duration={},format=yuv420p",
+        fixture.width, fixture.height, fixture.frame_rate, fixture.duration
+    );
+
+    Ok(generate(&spec, "buffersink")?
+        .into_iter()
+        .map(frame::Video::from)
+        .collect())
+}
+
+/// Generate a mono signed 16-bit sine tone with pts in `1/sample_rate`.
+pub fn sine(fixture: &Fixture) -> Result<Vec<frame::Audio>, Error> {
+    let spec = format!(
+        "sine=frequency={}:sample_rate={}:duration={}:samples_per_frame=1024,\
+         aformat=sample_fmts=s16:channel_layouts=mono",
+        fixture.frequency, fixture.sample_rate, fixture.duration
+    );
+
+    Ok(generate(&spec, "abuffersink")?
+        .into_iter()
+        .map(frame::Audio::from)
+        .collect())
+}
+
+fn generate(spec: &str, sink: &str) -> Result<Vec<Frame>, Error> {
+    let mut graph = filter::Graph::new();
+
+    graph.add(&filter::find(sink).ok_or(Error::FilterNotFound)?, "out", "")?;
+    graph.input("out", 0)?.parse(spec)?;
+    graph.validate()?;
+
+    let mut frames = Vec::new();
+
+    loop {
+        let mut frame = unsafe { Frame::empty() };
+
+        match graph.get("out").unwrap().sink().frame(&mut frame) {
+            Ok(()) => frames.push(frame),
+            Err(Error::Eof) => return Ok(frames),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Write a bit-exact NUT file holding color bars (FFV1, or rawvideo as a
+/// fallback) and a sine tone (PCM).
+///
+/// Returns `Ok(false)` without touching `path` when the linked FFmpeg lacks
+/// any of the required filters or encoders.
+pub fn write<P: AsRef<Path>>(path: &P, fixture: &Fixture) -> Result<bool, Error> {
+    let video = match encoder::find_by_name("ffv1").or_else(|| encoder::find_by_name("rawvideo")) {
+        Some(codec) => codec,
+        None => return Ok(false),
+    };
+
+    let audio = match encoder::find_by_name("pcm_s16le") {
+        Some(codec) => codec,
+        None => return Ok(false),
+    };
+
+    if filter::find("smptebars").is_none() || filter::find("sine").is_none() {
+        return Ok(false);
+    }
+
+    let mut octx = format::output_as(path, "nut")?;
+    octx.set_bitexact(true);
+
+    let mut flags = codec::Flags::BITEXACT;
+
+    if octx.format().flags().contains(format::Flags::GLOBAL_HEADER) {
+        flags |= codec::Flags::GLOBAL_HEADER;
+    }
+
+    let video_time_base = Rational(1, fixture.frame_rate);
+    let mut video_encoder = {
+        let mut stream = octx.add_stream(video)?;
+        let mut encoder = stream.codec().encoder().video()?;
+
+        encoder.set_width(fixture.width);
+        encoder.set_height(fixture.height);
+        encoder.set_format(format::Pixel::YUV420P);
+        encoder.set_time_base(video_time_base);
+        encoder.set_flags(flags);
+
+        stream.set_time_base(video_time_base);
+
+        let encoder = encoder.open_as(video)?;
+        stream.set_parameters(&encoder);
+
+        encoder
+    };
+
+    let audio_time_base = Rational(1, fixture.sample_rate as i32);
+    let mut audio_encoder = {
+        let mut stream = octx.add_stream(audio)?;
+        let mut encoder = stream.codec().encoder().audio()?;
+
+        encoder.set_rate(fixture.sample_rate as i32);
+        encoder.set_format(format::Sample::I16(sample::Type::Packed));
+        encoder.set_channel_layout(ChannelLayout::MONO);
+        encoder.set_channels(1);
+        encoder.set_time_base(audio_time_base);
+        encoder.set_flags(flags);
+
+        stream.set_time_base(audio_time_base);
+
+        let encoder = encoder.open_as(audio)?;
+        stream.set_parameters(&encoder);
+
+        encoder
+    };
+
+    octx.write_header()?;
+
+    for frame in color_bars(fixture)? {
+        video_encoder.send_frame(&frame)?;
+        drain(&mut video_encoder, 0, video_time_base, &mut octx)?;
+    }
+
+    video_encoder.send_eof()?;
+    drain(&mut video_encoder, 0, video_time_base, &mut octx)?;
+
+    for frame in sine(fixture)? {
+        audio_encoder.send_frame(&frame)?;
+        drain(&mut audio_encoder, 1, audio_time_base, &mut octx)?;
+    }
+
+    audio_encoder.send_eof()?;
+    drain(&mut audio_encoder, 1, audio_time_base, &mut octx)?;
+
+    octx.write_trailer()?;
+
+    Ok(true)
+}
+
+fn drain(
+    encoder: &mut encoder::Encoder,
+    index: usize,
+    time_base: Rational,
+    octx: &mut format::context::Output,
+) -> Result<(), Error> {
+    let mut packet = Packet::empty();
+
+    loop {
+        match encoder.receive_packet(&mut packet) {
+            Ok(()) => {
+                packet.set_stream(index);
+                octx.write_packet(&mut packet, time_base)?;
+            }
+
+            Err(Error::Eof) | Err(Error::Other { errno: EAGAIN }) => return Ok(()),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Summarize every packet of `path` as a line in the spirit of the
+/// `framecrc` muxer: `stream, dts, pts, duration, size, adler32`.
+pub fn framecrc<P: AsRef<Path>>(path: &P) -> Result<Vec<String>, Error> {
+    let mut ictx = format::input(path)?;
+    let mut lines = Vec::new();
+
+    for (stream, packet) in ictx.packets() {
+        let crc = packet.data().map_or(0, |data| unsafe {
+            av_adler32_update(0, data.as_ptr(), data.len() as _)
+        });
+
+        lines.push(format!(
+            "{}, {}, {}, {}, {}, 0x{:08x}",
+            stream.index(),
+            packet.dts().unwrap_or(AV_NOPTS_VALUE),
+            packet.pts().unwrap_or(AV_NOPTS_VALUE),
+            packet.duration(),
+            packet.size(),
+            crc
+        ));
+    }
+
+    Ok(lines)
+}
+
+/// Environment variable which, when set, makes `compare_golden` write the
+/// current output to the golden file instead of comparing against it.
+pub const BLESS: &str = "FFMPEG_BLESS_GOLDEN";
+
+/// Compare the `framecrc` summary of `path` against the `golden` file.
+///
+/// A missing golden file is an error. Running with the `BLESS` environment
+/// variable set creates or overwrites it from the current output, and the
+/// comparison succeeds.
+pub fn compare_golden<P: AsRef<Path>, G: AsRef<Path>>(path: &P, golden: &G) -> Result<bool, Error> {
+    let mut actual = framecrc(path)?.join("\n");
+    actual.push('\n');
+
+    if env::var_os(BLESS).is_some() {
+        fs::write(golden, actual).map_err(Error::from)?;
+        return Ok(true);
+    }
+
+    let expected = fs::read_to_string(golden).map_err(Error::from)?;
+
+    Ok(expected == actual)
+}
@@ -0,0 +1,103 @@
+//! Cooperative cancellation of long running operations.
+//!
+//! Helpers accepting a `Token` check it between packets or frames, so a
+//! cancelled or timed out operation returns `Error::Exit` after finishing its
+//! current step instead of being killed halfway. A blocked read is only
+//! abandoned if the context was opened with an interrupt callback checking
+//! the same token, as with
+//! `format::input_with_interrupt(&path, move || token.is_cancelled())`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use Error;
+
+/// What to do with an output when the operation writing it is cancelled.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Policy {
+    /// Flush pending data and write the trailer, leaving a playable but
+    /// shorter output.
+    Finalize,
+
+    /// Return right away, leaving the output without its trailer for the
+    /// caller to delete.
+    Discard,
+}
+
+/// A cancellation flag shared between its clones, with an optional deadline.
+#[derive(Clone, Debug)]
+pub struct Token {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl Token {
+    pub fn new() -> Self {
+        Token {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            deadline: None,
+        }
+    }
+
+    /// A clone that also expires `timeout` from now. Cancelling either token
+    /// cancels both, the deadline only applies to the returned one.
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let deadline = Instant::now() + timeout;
+
+        Token {
+            cancelled: self.cancelled.clone(),
+            deadline: Some(match self.deadline {
+                Some(current) if current < deadline => current,
+                _ => deadline,
+            }),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .map_or(false, |deadline| Instant::now() >= deadline)
+    }
+
+    /// A checkpoint: `Err(Error::Exit)` once cancelled or expired.
+    pub fn check(&self) -> Result<(), Error> {
+        if self.is_cancelled() {
+            Err(Error::Exit)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for Token {
+    fn default() -> Self {
+        Token::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token() {
+        let token = Token::new();
+        let expired = token.with_timeout(Duration::from_secs(0));
+        let child = token.with_timeout(Duration::from_secs(3600));
+
+        assert_eq!(token.check(), Ok(()));
+        assert_eq!(expired.check(), Err(Error::Exit));
+        assert!(!child.is_cancelled());
+
+        child.cancel();
+
+        assert!(token.is_cancelled());
+        assert_eq!(child.check(), Err(Error::Exit));
+    }
+}
@@ -1,5 +1,16 @@
+//! Channel layouts as bit masks of channels, in the FFmpeg 4 fashion.
+//!
+//! FFmpeg 5.1 replaced masks with `AVChannelLayout`, which adds a channel
+//! order for custom maps and ambisonics. A mask is what the new API calls a
+//! native order layout, so `bits()` is what `av_channel_layout_from_mask`
+//! takes when moving to it.
+
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::str::from_utf8_unchecked;
+
 use ffi::*;
-use libc::c_ulonglong;
+use libc::{c_char, c_int, c_ulonglong};
 
 bitflags! {
     pub struct ChannelLayout: c_ulonglong {
@@ -72,4 +83,150 @@ impl ChannelLayout {
             ChannelLayout::from_bits_truncate(av_get_default_channel_layout(number) as c_ulonglong)
         }
     }
+
+    /// The usual layout for `number` channels, if there is one.
+    pub fn from_channels(number: u16) -> Option<ChannelLayout> {
+        match ChannelLayout::default(i32::from(number)) {
+            layout if layout.is_empty() => None,
+            layout => Some(layout),
+        }
+    }
+
+    /// Parse a layout as FFmpeg names it, such as `stereo`, `5.1(side)`,
+    /// `FL+FR+LFE` or a channel count like `6c`.
+    pub fn from_name(name: &str) -> Option<ChannelLayout> {
+        let name = CString::new(name).ok()?;
+
+        unsafe {
+            match av_get_channel_layout(name.as_ptr()) {
+                0 => None,
+                bits => Some(ChannelLayout::from_bits_truncate(bits as c_ulonglong)),
+            }
+        }
+    }
+
+    /// The name of the layout, such as `5.1(side)`, or its channels joined
+    /// with `+` when it has none.
+    pub fn name(&self) -> String {
+        let mut buffer = [0 as c_char; 128];
+
+        unsafe {
+            av_get_channel_layout_string(
+                buffer.as_mut_ptr(),
+                buffer.len() as c_int,
+                self.channels(),
+                self.bits(),
+            );
+
+            CStr::from_ptr(buffer.as_ptr())
+                .to_string_lossy()
+                .into_owned()
+        }
+    }
+
+    /// The individual channels of the layout, in order.
+    pub fn iter(&self) -> ChannelIter {
+        ChannelIter {
+            layout: *self,
+            index: 0,
+        }
+    }
+
+    /// Position of `channel` in the layout.
+    pub fn index(&self, channel: ChannelLayout) -> Option<usize> {
+        unsafe {
+            match av_get_channel_layout_channel_index(self.bits(), channel.bits()) {
+                index if index < 0 => None,
+                index => Some(index as usize),
+            }
+        }
+    }
+
+    /// Short name of a single channel, such as `FL`.
+    pub fn channel_name(&self) -> Option<&'static str> {
+        unsafe {
+            let ptr = av_get_channel_name(self.bits());
+
+            if ptr.is_null() {
+                None
+            } else {
+                Some(from_utf8_unchecked(CStr::from_ptr(ptr).to_bytes()))
+            }
+        }
+    }
+
+    /// Description of a single channel, such as `front left`.
+    pub fn channel_description(&self) -> Option<&'static str> {
+        unsafe {
+            let ptr = av_get_channel_description(self.bits());
+
+            if ptr.is_null() {
+                None
+            } else {
+                Some(from_utf8_unchecked(CStr::from_ptr(ptr).to_bytes()))
+            }
+        }
+    }
+}
+
+impl fmt::Display for ChannelLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name())
+    }
+}
+
+/// Iterator over the channels of a layout, each as a single channel layout.
+pub struct ChannelIter {
+    layout: ChannelLayout,
+    index: c_int,
+}
+
+impl Iterator for ChannelIter {
+    type Item = ChannelLayout;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            match av_channel_layout_extract_channel(self.layout.bits(), self.index) {
+                0 => None,
+                bits => {
+                    self.index += 1;
+                    Some(ChannelLayout::from_bits_truncate(bits as c_ulonglong))
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.layout.channels() - self.index).max(0) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for ChannelIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(
+            ChannelLayout::from_name("5.1(side)"),
+            Some(ChannelLayout::_5POINT1)
+        );
+        assert_eq!(ChannelLayout::_5POINT1.name(), "5.1(side)");
+        assert_eq!(ChannelLayout::from_channels(2), Some(ChannelLayout::STEREO));
+        assert_eq!(ChannelLayout::from_name("nonsense"), None);
+
+        let channels = ChannelLayout::STEREO
+            .iter()
+            .map(|channel| channel.channel_name().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(channels, vec!["FL", "FR"]);
+        assert_eq!(
+            ChannelLayout::STEREO.index(ChannelLayout::FRONT_RIGHT),
+            Some(1)
+        );
+    }
 }
@@ -6,6 +6,8 @@ use std::str::from_utf8_unchecked;
 
 use super::{Iter, Owned};
 use ffi::*;
+use libc::{c_char, c_void};
+use Error;
 
 pub struct Ref<'a> {
     ptr: *const AVDictionary,
@@ -49,6 +51,35 @@ impl<'a> Ref<'a> {
     pub fn to_owned<'b>(&self) -> Owned<'b> {
         self.iter().collect()
     }
+
+    /// Serialize the entries so that `Dictionary::parse` with the same
+    /// separators reads them back, escaping separators and backslashes found
+    /// in keys and values.
+    pub fn to_string(&self, key_separator: char, pair_separator: char) -> Result<String, Error> {
+        if !key_separator.is_ascii() || !pair_separator.is_ascii() {
+            return Err(Error::InvalidData);
+        }
+
+        unsafe {
+            let mut buffer = ptr::null_mut();
+
+            match av_dict_get_string(
+                self.as_ptr(),
+                &mut buffer,
+                key_separator as c_char,
+                pair_separator as c_char,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => {
+                    let string =
+                        String::from_utf8_lossy(CStr::from_ptr(buffer).to_bytes()).into_owned();
+                    av_freep(&mut buffer as *mut _ as *mut c_void);
+
+                    Ok(string)
+                }
+            }
+        }
+    }
 }
 
 impl<'a> IntoIterator for &'a Ref<'a> {
@@ -10,6 +10,9 @@ pub use self::owned::Owned;
 mod iter;
 pub use self::iter::Iter;
 
+#[cfg(feature = "serde")]
+mod serialize;
+
 #[macro_export]
 macro_rules! dict {
 	( $($key:expr => $value:expr),* $(,)*) => ({
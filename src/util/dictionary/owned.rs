@@ -1,10 +1,13 @@
+use std::ffi::CString;
 use std::fmt;
 use std::iter::FromIterator;
 use std::ops::{Deref, DerefMut};
 use std::ptr;
+use std::vec;
 
-use super::mutable;
+use super::{mutable, Iter};
 use ffi::*;
+use Error;
 
 pub struct Owned<'a> {
     inner: mutable::Ref<'a>,
@@ -39,6 +42,34 @@ impl<'a> Owned<'a> {
             }
         }
     }
+
+    /// Parse entries the way FFmpeg reads option strings, such as
+    /// `"k=v:k2=v2"` with `"="` and `":"` as separators. Each separator
+    /// argument lists the characters accepted as such, and values may be
+    /// quoted or escaped with a backslash.
+    pub fn parse(string: &str, key_separators: &str, pair_separators: &str) -> Result<Self, Error> {
+        let string = CString::new(string).map_err(|_| Error::InvalidData)?;
+        let key_separators = CString::new(key_separators).map_err(|_| Error::InvalidData)?;
+        let pair_separators = CString::new(pair_separators).map_err(|_| Error::InvalidData)?;
+
+        unsafe {
+            let mut ptr = ptr::null_mut();
+
+            match av_dict_parse_string(
+                &mut ptr,
+                string.as_ptr(),
+                key_separators.as_ptr(),
+                pair_separators.as_ptr(),
+                0,
+            ) {
+                0 => Ok(Owned::own(ptr)),
+                e => {
+                    av_dict_free(&mut ptr);
+                    Err(Error::from(e))
+                }
+            }
+        }
+    }
 }
 
 impl<'a, 'b> FromIterator<(&'b str, &'b str)> for Owned<'a> {
@@ -89,6 +120,43 @@ impl<'a, 'b> FromIterator<&'b (String, String)> for Owned<'a> {
     }
 }
 
+impl<'a, 'b> Extend<(&'b str, &'b str)> for Owned<'a> {
+    fn extend<T: IntoIterator<Item = (&'b str, &'b str)>>(&mut self, iterator: T) {
+        for (key, value) in iterator {
+            self.set(key, value);
+        }
+    }
+}
+
+impl<'a> Extend<(String, String)> for Owned<'a> {
+    fn extend<T: IntoIterator<Item = (String, String)>>(&mut self, iterator: T) {
+        for (key, value) in iterator {
+            self.set(&key, &value);
+        }
+    }
+}
+
+impl<'a> IntoIterator for Owned<'a> {
+    type Item = (String, String);
+    type IntoIter = vec::IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b Owned<'a> {
+    type Item = (&'b str, &'b str);
+    type IntoIter = Iter<'b>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl<'a> Deref for Owned<'a> {
     type Target = mutable::Ref<'a>;
 
@@ -133,3 +201,40 @@ impl<'a> fmt::Debug for Owned<'a> {
         self.inner.fmt(fmt)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_roundtrip() {
+        let dictionary =
+            Owned::parse("preset=fast:x264-params='keyint=60:bframes=2'", "=", ":").unwrap();
+
+        assert_eq!(dictionary.get("preset"), Some("fast"));
+        assert_eq!(dictionary.get("x264-params"), Some("keyint=60:bframes=2"));
+
+        let string = dictionary.to_string('=', ':').unwrap();
+        assert_eq!(string, "preset=fast:x264-params=keyint\\=60\\:bframes\\=2");
+
+        let parsed = Owned::parse(&string, "=", ":").unwrap();
+        assert_eq!(parsed.get("x264-params"), Some("keyint=60:bframes=2"));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut dictionary = vec![("preset", "fast"), ("crf", "23")]
+            .into_iter()
+            .collect::<Owned>();
+
+        dictionary.extend(vec![("crf".to_owned(), "18".to_owned())]);
+
+        assert_eq!(
+            dictionary.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("preset".to_owned(), "fast".to_owned()),
+                ("crf".to_owned(), "18".to_owned())
+            ]
+        );
+    }
+}
@@ -0,0 +1,82 @@
+//! Dictionaries as string maps, so options can come from configuration
+//! files. Keys repeated with `AV_DICT_MULTIKEY` are serialized as repeated
+//! map entries, and repeated entries are kept when deserializing.
+
+use std::ffi::CString;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ptr;
+
+use ffi::*;
+use serde::de::{Error, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{Iter, Mut, Owned, Ref};
+
+impl<'a> Serialize for Ref<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        entries(self.iter(), serializer)
+    }
+}
+
+impl<'a> Serialize for Mut<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        entries(self.iter(), serializer)
+    }
+}
+
+impl<'a> Serialize for Owned<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        entries(self.iter(), serializer)
+    }
+}
+
+fn entries<S: Serializer>(iter: Iter, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(None)?;
+
+    for (key, value) in iter {
+        map.serialize_entry(key, value)?;
+    }
+
+    map.end()
+}
+
+impl<'de, 'a> Deserialize<'de> for Owned<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(OwnedVisitor(PhantomData))
+    }
+}
+
+struct OwnedVisitor<'a>(PhantomData<Owned<'a>>);
+
+impl<'de, 'a> Visitor<'de> for OwnedVisitor<'a> {
+    type Value = Owned<'a>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of strings")
+    }
+
+    fn visit_map<M: MapAccess<'de>>(self, mut access: M) -> Result<Self::Value, M::Error> {
+        let mut entries = Vec::new();
+
+        while let Some((key, value)) = access.next_entry::<String, String>()? {
+            match (CString::new(key), CString::new(value)) {
+                (Ok(key), Ok(value)) => entries.push((key, value)),
+                _ => return Err(M::Error::custom("nul byte in dictionary entry")),
+            }
+        }
+
+        let mut ptr = ptr::null_mut();
+
+        for (key, value) in entries {
+            unsafe {
+                if av_dict_set(&mut ptr, key.as_ptr(), value.as_ptr(), AV_DICT_MULTIKEY) < 0 {
+                    panic!("out of memory");
+                }
+            }
+        }
+
+        Ok(unsafe { Owned::own(ptr) })
+    }
+}
@@ -0,0 +1,86 @@
+//! Pre-flight validation of picture dimensions, for sizes coming from
+//! untrusted input before anything is allocated.
+
+use std::ptr;
+
+use ffi::*;
+use libc::c_int;
+use util::error::Limit;
+use util::format;
+use {Error, Rational};
+
+/// Check that a `width` by `height` picture can be allocated and addressed,
+/// whatever its format.
+pub fn check_size(width: u32, height: u32) -> Result<(), Error> {
+    check_size_for(width, height, format::Pixel::None, None)
+}
+
+/// Check that a `width` by `height` picture in `format` can be allocated
+/// and addressed, with at most `max_pixels` pixels if given.
+///
+/// Fails with `Error::Limit` naming the dimension that is zero or too
+/// large, or whether the picture has too many pixels or bytes.
+pub fn check_size_for(
+    width: u32,
+    height: u32,
+    format: format::Pixel,
+    max_pixels: Option<u64>,
+) -> Result<(), Error> {
+    if width == 0 || width > c_int::max_value() as u32 {
+        return Err(Error::Limit(Limit::Width));
+    }
+
+    if height == 0 || height > c_int::max_value() as u32 {
+        return Err(Error::Limit(Limit::Height));
+    }
+
+    let max_pixels = max_pixels.map_or(i64::max_value(), |max| {
+        max.min(i64::max_value() as u64) as i64
+    });
+
+    unsafe {
+        match av_image_check_size2(width, height, max_pixels, format.into(), 0, ptr::null_mut()) {
+            0 => Ok(()),
+            _ if u64::from(width) * u64::from(height) > max_pixels as u64 => {
+                Err(Error::Limit(Limit::Pixels))
+            }
+            _ => Err(Error::Limit(Limit::Size)),
+        }
+    }
+}
+
+/// Check that `aspect_ratio` is a sane sample aspect ratio for a `width` by
+/// `height` picture: positive, finite and not making the displayed picture
+/// absurdly wide or tall. An unknown `0/1` ratio is accepted.
+///
+/// Fails with `Error::Limit(Limit::AspectRatio)`.
+pub fn check_aspect_ratio(width: u32, height: u32, aspect_ratio: Rational) -> Result<(), Error> {
+    unsafe {
+        match av_image_check_sar(width, height, aspect_ratio.into()) {
+            0 => Ok(()),
+            _ => Err(Error::Limit(Limit::AspectRatio)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_size() {
+        assert!(check_size(1920, 1080).is_ok());
+        assert_eq!(check_size(0, 1080), Err(Error::Limit(Limit::Width)));
+        assert_eq!(
+            check_size(2, u32::max_value()),
+            Err(Error::Limit(Limit::Height))
+        );
+        assert_eq!(check_size(65536, 65536), Err(Error::Limit(Limit::Size)));
+        assert_eq!(
+            check_size_for(1920, 1080, format::Pixel::YUV420P, Some(1280 * 720)),
+            Err(Error::Limit(Limit::Pixels))
+        );
+        assert!(check_aspect_ratio(720, 576, Rational(16, 15)).is_ok());
+        assert!(check_aspect_ratio(720, 576, Rational(1, 0)).is_err());
+    }
+}
@@ -61,6 +61,183 @@ pub enum Error {
     Other {
         errno: c_int,
     },
+
+    /// An I/O error without an OS error number, such as one raised by a Rust
+    /// reader or writer. Only its kind is kept, so that `Error` stays `Copy`.
+    Io(io::ErrorKind),
+
+    /// A size or count over a limit, such as picture dimensions checked
+    /// before allocating. Its code is `AVERROR(EINVAL)`.
+    Limit(Limit),
+}
+
+/// The limit behind `Error::Limit`.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Limit {
+    /// A width of zero, or too large to be addressed.
+    Width,
+    /// A height of zero, or too large to be addressed.
+    Height,
+    /// More pixels than allowed, such as by `Decoder::set_max_pixels`.
+    Pixels,
+    /// More samples than allowed by `Decoder::set_max_samples`.
+    Samples,
+    /// A picture too large in bytes, whatever the pixel limit.
+    Size,
+    /// A sample aspect ratio that is not positive and finite, or makes the
+    /// displayed picture absurdly wide or tall.
+    AspectRatio,
+    /// A buffer alignment that is not a power of two up to 64.
+    Alignment,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Limit::Width => "Width out of range",
+            Limit::Height => "Height out of range",
+            Limit::Pixels => "Too many pixels",
+            Limit::Samples => "Too many samples",
+            Limit::Size => "Picture too large",
+            Limit::AspectRatio => "Invalid sample aspect ratio",
+            Limit::Alignment => "Invalid alignment",
+        })
+    }
+}
+
+/// Broad classes of errors, to decide how to react without matching every
+/// code.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum ErrorKind {
+    /// The end of a stream, or a drained codec or filter.
+    Eof,
+    /// No output until more input is given, or a non-blocking operation
+    /// that would block.
+    Again,
+    /// A missing codec, format, filter, protocol, option or stream.
+    NotFound,
+    InvalidData,
+    /// A feature FFmpeg or the platform does not implement.
+    Unsupported,
+    /// Aborted by an interrupt callback, a signal or a cancellation.
+    Interrupted,
+    OutOfMemory,
+    PermissionDenied,
+    /// A connection failure or timeout.
+    Network,
+    /// An HTTP error status, see `Error::http_status`.
+    Http,
+    /// A bug in FFmpeg, or a failure of an external library.
+    Internal,
+    Other,
+}
+
+impl Error {
+    /// The raw `AVERROR` code.
+    pub fn code(&self) -> c_int {
+        (*self).into()
+    }
+
+    /// The POSIX error number of `Error::Other`.
+    pub fn errno(&self) -> Option<c_int> {
+        match *self {
+            Error::Other { errno } => Some(errno),
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            Error::Eof => ErrorKind::Eof,
+
+            Error::BsfNotFound
+            | Error::DecoderNotFound
+            | Error::DemuxerNotFound
+            | Error::EncoderNotFound
+            | Error::OptionNotFound
+            | Error::MuxerNotFound
+            | Error::FilterNotFound
+            | Error::ProtocolNotFound
+            | Error::StreamNotFound => ErrorKind::NotFound,
+
+            Error::InvalidData => ErrorKind::InvalidData,
+            Error::PatchWelcome | Error::Experimental => ErrorKind::Unsupported,
+            Error::Exit => ErrorKind::Interrupted,
+
+            Error::HttpBadRequest
+            | Error::HttpUnauthorized
+            | Error::HttpForbidden
+            | Error::HttpNotFound
+            | Error::HttpOther4xx
+            | Error::HttpServerError => ErrorKind::Http,
+
+            Error::Bug | Error::Bug2 | Error::Unknown | Error::External => ErrorKind::Internal,
+
+            Error::BufferTooSmall | Error::InputChanged | Error::OutputChanged => ErrorKind::Other,
+
+            Error::Other { errno } => match errno {
+                EAGAIN => ErrorKind::Again,
+                #[allow(unreachable_patterns)]
+                EWOULDBLOCK => ErrorKind::Again,
+                ENOENT | ENODEV | ENXIO => ErrorKind::NotFound,
+                EINVAL | EILSEQ | EBADMSG | EPROTO => ErrorKind::InvalidData,
+                ENOSYS | ENOTSUP | EPROTONOSUPPORT | EAFNOSUPPORT => ErrorKind::Unsupported,
+                #[allow(unreachable_patterns)]
+                EOPNOTSUPP => ErrorKind::Unsupported,
+                EINTR | ECANCELED => ErrorKind::Interrupted,
+                ENOMEM | ENOBUFS => ErrorKind::OutOfMemory,
+                EACCES | EPERM | EROFS => ErrorKind::PermissionDenied,
+                ECONNREFUSED | ECONNRESET | ECONNABORTED | ENETDOWN | ENETUNREACH | ENETRESET
+                | EHOSTUNREACH | ENOTCONN | ETIMEDOUT | EPIPE => ErrorKind::Network,
+                _ => ErrorKind::Other,
+            },
+
+            Error::Limit(_) => ErrorKind::InvalidData,
+
+            Error::Io(kind) => match kind {
+                io::ErrorKind::UnexpectedEof => ErrorKind::Eof,
+                io::ErrorKind::WouldBlock => ErrorKind::Again,
+                io::ErrorKind::NotFound => ErrorKind::NotFound,
+                io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput => ErrorKind::InvalidData,
+                io::ErrorKind::Interrupted => ErrorKind::Interrupted,
+                io::ErrorKind::PermissionDenied => ErrorKind::PermissionDenied,
+                io::ErrorKind::ConnectionRefused
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::NotConnected
+                | io::ErrorKind::BrokenPipe
+                | io::ErrorKind::TimedOut => ErrorKind::Network,
+                _ => ErrorKind::Other,
+            },
+        }
+    }
+
+    /// The HTTP status behind an HTTP error, 499 standing for the 4xx codes
+    /// FFmpeg does not distinguish and 500 for all 5xx ones.
+    pub fn http_status(&self) -> Option<u16> {
+        match *self {
+            Error::HttpBadRequest => Some(400),
+            Error::HttpUnauthorized => Some(401),
+            Error::HttpForbidden => Some(403),
+            Error::HttpNotFound => Some(404),
+            Error::HttpOther4xx => Some(499),
+            Error::HttpServerError => Some(500),
+            _ => None,
+        }
+    }
+
+    /// Whether the same operation may succeed if attempted again: `EAGAIN`,
+    /// an interrupted system call, a network failure or an HTTP 5xx status.
+    ///
+    /// `EAGAIN` from a codec or filter means more input must be sent first.
+    pub fn is_retryable(&self) -> bool {
+        match self.kind() {
+            ErrorKind::Again | ErrorKind::Network => true,
+            ErrorKind::Interrupted => *self == Error::Other { errno: EINTR },
+            ErrorKind::Http => *self == Error::HttpServerError,
+            _ => false,
+        }
+    }
 }
 
 impl From<c_int> for Error {
@@ -131,20 +308,57 @@ impl From<Error> for c_int {
             Error::HttpOther4xx => AVERROR_HTTP_OTHER_4XX,
             Error::HttpServerError => AVERROR_HTTP_SERVER_ERROR,
             Error::Other { errno } => AVERROR(errno),
+            Error::Limit(_) => AVERROR(EINVAL),
+            Error::Io(kind) => match kind {
+                io::ErrorKind::UnexpectedEof => AVERROR_EOF,
+                io::ErrorKind::InvalidData => AVERROR_INVALIDDATA,
+                io::ErrorKind::Interrupted => AVERROR(EINTR),
+                io::ErrorKind::WouldBlock => AVERROR(EAGAIN),
+                _ => AVERROR_EXTERNAL,
+            },
         }
     }
 }
 
 impl error::Error for Error {}
 
+// POSIX errors keep their number and I/O errors their kind, others are
+// wrapped and available through `io::Error::get_ref`.
 impl From<Error> for io::Error {
     fn from(value: Error) -> io::Error {
-        io::Error::new(io::ErrorKind::Other, value)
+        let kind = match value.kind() {
+            ErrorKind::Eof => io::ErrorKind::UnexpectedEof,
+            ErrorKind::InvalidData => io::ErrorKind::InvalidData,
+            ErrorKind::NotFound => io::ErrorKind::NotFound,
+            ErrorKind::Interrupted => io::ErrorKind::Interrupted,
+            _ => io::ErrorKind::Other,
+        };
+
+        match value {
+            Error::Other { errno } => io::Error::from_raw_os_error(errno),
+            Error::Io(kind) => io::Error::from(kind),
+            _ => io::Error::new(kind, value),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Error {
+        match value.raw_os_error() {
+            Some(errno) => Error::Other { errno },
+            None => Error::Io(value.kind()),
+        }
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Error::Io(kind) => return fmt::Display::fmt(&io::Error::from(kind), f),
+            Error::Limit(ref limit) => return fmt::Display::fmt(limit, f),
+            _ => (),
+        }
+
         f.write_str(unsafe {
             from_utf8_unchecked(
                 CStr::from_ptr(match *self {
@@ -160,7 +374,7 @@ impl fmt::Display for Error {
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         f.write_str("ffmpeg::Error(")?;
-        f.write_str(&format!("{}: ", AVUNERROR((*self).into())))?;
+        f.write_str(&format!("{}: ", AVUNERROR(self.code())))?;
         fmt::Display::fmt(self, f)?;
         f.write_str(")")
     }
@@ -196,7 +410,7 @@ fn index(error: &Error) -> usize {
         Error::HttpNotFound => 24,
         Error::HttpOther4xx => 25,
         Error::HttpServerError => 26,
-        Error::Other { errno: _ } => (-1isize) as usize,
+        Error::Other { errno: _ } | Error::Io(_) | Error::Limit(_) => (-1isize) as usize,
     }
 }
 
@@ -360,6 +574,30 @@ mod tests {
         assert_eq!(Error::from(AVERROR(EAGAIN)), Error::Other { errno: EAGAIN });
     }
 
+    #[test]
+    fn test_error_kind() {
+        assert_eq!(Error::from(AVERROR(EAGAIN)).kind(), ErrorKind::Again);
+        assert_eq!(Error::BsfNotFound.kind(), ErrorKind::NotFound);
+        assert_eq!(Error::HttpForbidden.http_status(), Some(403));
+
+        assert!(Error::Other { errno: ETIMEDOUT }.is_retryable());
+        assert!(Error::HttpServerError.is_retryable());
+        assert!(!Error::HttpNotFound.is_retryable());
+        assert!(!Error::Exit.is_retryable());
+
+        let error = io::Error::from(Error::Other { errno: ENOENT });
+        assert_eq!(Error::from(error), Error::Other { errno: ENOENT });
+    }
+
+    #[test]
+    fn test_io_error_kind() {
+        let failure = Error::from(io::Error::new(io::ErrorKind::TimedOut, "no answer"));
+
+        assert_eq!(failure, Error::Io(io::ErrorKind::TimedOut));
+        assert_eq!(failure.kind(), ErrorKind::Network);
+        assert_eq!(io::Error::from(failure).kind(), io::ErrorKind::TimedOut);
+    }
+
     #[cfg(any(target_os = "linux", target_os = "macos"))]
     #[test]
     fn test_posix_error_string() {
@@ -1,12 +1,15 @@
 use std::mem;
+#[cfg(feature = "nonzero")]
+use std::num::NonZeroU32;
 use std::ops::{Deref, DerefMut};
 use std::slice;
 
 use super::Frame;
 use ffi::*;
 use libc::{c_int, c_ulonglong};
+use util::error::Limit;
 use util::format;
-use ChannelLayout;
+use {ChannelLayout, Error};
 
 #[derive(PartialEq, Eq)]
 pub struct Audio(Frame);
@@ -43,6 +46,31 @@ impl Audio {
         }
     }
 
+    /// Allocate a frame whose planes start on an `align` byte boundary, a
+    /// power of two up to 64, reporting failures.
+    pub fn new_aligned(
+        format: format::Sample,
+        samples: usize,
+        layout: ChannelLayout,
+        align: usize,
+    ) -> Result<Self, Error> {
+        if align == 0 || align > 64 || !align.is_power_of_two() {
+            return Err(Error::Limit(Limit::Alignment));
+        }
+
+        unsafe {
+            let mut frame = Audio::empty();
+            frame.set_format(format);
+            frame.set_samples(samples);
+            frame.set_channel_layout(layout);
+
+            match av_frame_get_buffer(frame.as_mut_ptr(), align as c_int) {
+                0 => Ok(frame),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
     #[inline]
     pub fn format(&self) -> format::Sample {
         unsafe {
@@ -89,8 +117,24 @@ impl Audio {
         }
     }
 
+    #[cfg(not(feature = "nonzero"))]
     #[inline]
     pub fn rate(&self) -> u32 {
+        self.raw_rate()
+    }
+
+    /// `None` until the frame is given a sample rate.
+    #[cfg(feature = "nonzero")]
+    #[inline]
+    pub fn rate(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_rate())
+    }
+
+    // The field behind `rate`, whatever type the `nonzero` feature makes it
+    // return.
+    #[doc(hidden)]
+    #[inline]
+    pub fn raw_rate(&self) -> u32 {
         unsafe { av_frame_get_sample_rate(self.as_ptr()) as u32 }
     }
 
@@ -490,3 +534,27 @@ unsafe impl Sample for (f64, f64, f64, f64, f64, f64, f64) {
         channels == 7 && format == format::Sample::F64(format::sample::Type::Packed)
     }
 }
+
+/// Predict the size of each plane `Audio::new_aligned` allocates for
+/// `samples` samples of `channels` channels in `format`.
+pub fn linesize(
+    format: format::Sample,
+    channels: u16,
+    samples: usize,
+    align: usize,
+) -> Result<usize, Error> {
+    let mut linesize = 0;
+
+    unsafe {
+        match av_samples_get_buffer_size(
+            &mut linesize,
+            c_int::from(channels),
+            samples as c_int,
+            format.into(),
+            align as c_int,
+        ) {
+            e if e < 0 => Err(Error::from(e)),
+            _ => Ok(linesize as usize),
+        }
+    }
+}
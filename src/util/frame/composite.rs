@@ -0,0 +1,300 @@
+use std::cmp;
+
+use super::Video;
+use util::format::Pixel;
+use Error;
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Alpha {
+    /// Color components are independent of the alpha value.
+    Straight,
+
+    /// Color components are already multiplied by the alpha value.
+    Premultiplied,
+}
+
+#[derive(Clone, Copy)]
+enum Layout {
+    Packed {
+        size: usize,
+        color: [usize; 3],
+        alpha: Option<usize>,
+    },
+
+    Yuv420 {
+        alpha: bool,
+    },
+}
+
+fn layout(format: Pixel) -> Option<Layout> {
+    let packed = |size, color, alpha| Some(Layout::Packed { size, color, alpha });
+
+    match format {
+        Pixel::RGBA => packed(4, [0, 1, 2], Some(3)),
+        Pixel::BGRA => packed(4, [2, 1, 0], Some(3)),
+        Pixel::ARGB => packed(4, [1, 2, 3], Some(0)),
+        Pixel::ABGR => packed(4, [3, 2, 1], Some(0)),
+        Pixel::RGB24 => packed(3, [0, 1, 2], None),
+        Pixel::BGR24 => packed(3, [2, 1, 0], None),
+        Pixel::YUVA420P => Some(Layout::Yuv420 { alpha: true }),
+        Pixel::YUV420P => Some(Layout::Yuv420 { alpha: false }),
+        _ => None,
+    }
+}
+
+/// Blend `foreground` over `background` with its top left corner at `(x, y)`,
+/// clipping whatever falls outside of `background`.
+///
+/// `foreground` must carry alpha: RGBA, BGRA, ARGB and ABGR can be blended
+/// over any of them as well as RGB24 and BGR24, and YUVA420P over YUV420P and
+/// YUVA420P, at even coordinates. When `background` has alpha, it is updated
+/// as if its color were opaque.
+pub fn composite(
+    background: &mut Video,
+    foreground: &Video,
+    x: i32,
+    y: i32,
+    alpha: Alpha,
+) -> Result<(), Error> {
+    let (bg, fg) = match (layout(background.format()), layout(foreground.format())) {
+        (Some(bg), Some(fg)) => (bg, fg),
+        _ => return Err(Error::InvalidData),
+    };
+
+    let x0 = cmp::max(x, 0) as i64;
+    let y0 = cmp::max(y, 0) as i64;
+    let x1 = cmp::min(
+        i64::from(x) + i64::from(foreground.raw_width()),
+        i64::from(background.raw_width()),
+    );
+    let y1 = cmp::min(
+        i64::from(y) + i64::from(foreground.raw_height()),
+        i64::from(background.raw_height()),
+    );
+
+    let region = Region {
+        x: x0 as usize,
+        y: y0 as usize,
+        fx: (x0 - i64::from(x)) as usize,
+        fy: (y0 - i64::from(y)) as usize,
+        width: cmp::max(x1 - x0, 0) as usize,
+        height: cmp::max(y1 - y0, 0) as usize,
+    };
+
+    match (bg, fg) {
+        (
+            Layout::Packed {
+                size: bsize,
+                color: bcolor,
+                alpha: balpha,
+            },
+            Layout::Packed {
+                size: fsize,
+                color: fcolor,
+                alpha: Some(falpha),
+            },
+        ) => {
+            background.make_writable()?;
+
+            if region.width == 0 || region.height == 0 {
+                return Ok(());
+            }
+
+            let fstride = foreground.stride(0);
+            let bstride = background.stride(0);
+            let fdata = foreground.data(0);
+            let bdata = background.data_mut(0);
+
+            for row in 0..region.height {
+                let f = &fdata[(region.fy + row) * fstride + region.fx * fsize..];
+                let b = &mut bdata[(region.y + row) * bstride + region.x * bsize..];
+
+                for (fp, bp) in f[..region.width * fsize]
+                    .chunks(fsize)
+                    .zip(b[..region.width * bsize].chunks_mut(bsize))
+                {
+                    let a = u32::from(fp[falpha]);
+
+                    for c in 0..3 {
+                        bp[bcolor[c]] = blend(fp[fcolor[c]], bp[bcolor[c]], a, alpha);
+                    }
+
+                    if let Some(balpha) = balpha {
+                        bp[balpha] = over(a, bp[balpha]);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        (Layout::Yuv420 { alpha: balpha }, Layout::Yuv420 { alpha: true }) => {
+            if x % 2 != 0 || y % 2 != 0 {
+                return Err(Error::InvalidData);
+            }
+
+            background.make_writable()?;
+
+            if region.width == 0 || region.height == 0 {
+                return Ok(());
+            }
+
+            composite_yuv420(background, foreground, &region, balpha, alpha);
+
+            Ok(())
+        }
+
+        _ => Err(Error::InvalidData),
+    }
+}
+
+struct Region {
+    x: usize,
+    y: usize,
+    fx: usize,
+    fy: usize,
+    width: usize,
+    height: usize,
+}
+
+fn composite_yuv420(
+    background: &mut Video,
+    foreground: &Video,
+    region: &Region,
+    balpha: bool,
+    mode: Alpha,
+) {
+    let fa = foreground.data(3);
+    let fa_stride = foreground.stride(3);
+
+    // Luma, and the alpha plane of the background if any.
+    for row in 0..region.height {
+        let a = &fa[(region.fy + row) * fa_stride + region.fx..][..region.width];
+
+        let fstride = foreground.stride(0);
+        let f = &foreground.data(0)[(region.fy + row) * fstride + region.fx..][..region.width];
+
+        let bstride = background.stride(0);
+        let b =
+            &mut background.data_mut(0)[(region.y + row) * bstride + region.x..][..region.width];
+
+        for ((b, &f), &a) in b.iter_mut().zip(f).zip(a) {
+            *b = blend(f, *b, u32::from(a), mode);
+        }
+
+        if balpha {
+            let bstride = background.stride(3);
+            let b = &mut background.data_mut(3)[(region.y + row) * bstride + region.x..]
+                [..region.width];
+
+            for (b, &a) in b.iter_mut().zip(a) {
+                *b = over(u32::from(a), *b);
+            }
+        }
+    }
+
+    // Chroma, weighted by the average alpha of the luma samples it covers.
+    let width = cmp::min(
+        (region.width + 1) / 2,
+        background.plane_width(1) as usize - region.x / 2,
+    );
+    let height = cmp::min(
+        (region.height + 1) / 2,
+        background.plane_height(1) as usize - region.y / 2,
+    );
+    let (fw, fh) = (
+        foreground.raw_width() as usize,
+        foreground.raw_height() as usize,
+    );
+
+    let mut alphas = vec![0u32; width];
+
+    for row in 0..height {
+        let ly = region.fy + row * 2;
+
+        for (i, value) in alphas.iter_mut().enumerate() {
+            let lx = region.fx + i * 2;
+            let sample = |x: usize, y: usize| {
+                u32::from(fa[cmp::min(y, fh - 1) * fa_stride + cmp::min(x, fw - 1)])
+            };
+
+            *value = (sample(lx, ly)
+                + sample(lx + 1, ly)
+                + sample(lx, ly + 1)
+                + sample(lx + 1, ly + 1)
+                + 2)
+                / 4;
+        }
+
+        for plane in 1..3 {
+            let fstride = foreground.stride(plane);
+            let f =
+                &foreground.data(plane)[(region.fy / 2 + row) * fstride + region.fx / 2..][..width];
+
+            let bstride = background.stride(plane);
+            let b = &mut background.data_mut(plane)
+                [(region.y / 2 + row) * bstride + region.x / 2..][..width];
+
+            for ((b, &f), &a) in b.iter_mut().zip(f).zip(&alphas) {
+                *b = blend_chroma(f, *b, a, mode);
+            }
+        }
+    }
+}
+
+// Exact `(value + 127) / 255` for `value <= 255 * 255`, without a division.
+#[inline(always)]
+fn div255(value: u32) -> u32 {
+    (value + 128 + ((value + 128) >> 8)) >> 8
+}
+
+#[inline(always)]
+fn blend(fg: u8, bg: u8, alpha: u32, mode: Alpha) -> u8 {
+    let (fg, bg) = (u32::from(fg), u32::from(bg));
+
+    match mode {
+        Alpha::Straight => div255(fg * alpha + bg * (255 - alpha)) as u8,
+        Alpha::Premultiplied => cmp::min(fg + div255(bg * (255 - alpha)), 255) as u8,
+    }
+}
+
+#[inline(always)]
+fn blend_chroma(fg: u8, bg: u8, alpha: u32, mode: Alpha) -> u8 {
+    match mode {
+        Alpha::Straight => blend(fg, bg, alpha, mode),
+
+        // Premultiplied chroma is scaled around the neutral value.
+        Alpha::Premultiplied => {
+            let scaled = (i32::from(bg) - 128) * (255 - alpha as i32);
+            let value = i32::from(fg) + (scaled + scaled.signum() * 127) / 255;
+
+            cmp::max(cmp::min(value, 255), 0) as u8
+        }
+    }
+}
+
+#[inline(always)]
+fn over(alpha: u32, bg: u8) -> u8 {
+    (alpha + div255(u32::from(bg) * (255 - alpha))) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_div255() {
+        for value in 0..255 * 255 + 1 {
+            assert_eq!(div255(value), (value + 127) / 255);
+        }
+    }
+
+    #[test]
+    fn test_blend() {
+        assert_eq!(blend(200, 100, 255, Alpha::Straight), 200);
+        assert_eq!(blend(200, 100, 0, Alpha::Straight), 100);
+        assert_eq!(blend(200, 100, 128, Alpha::Straight), 150);
+        assert_eq!(blend(100, 100, 128, Alpha::Premultiplied), 150);
+        assert_eq!(over(128, 255), 255);
+    }
+}
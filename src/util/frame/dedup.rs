@@ -0,0 +1,210 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+
+use super::Video;
+use ffi::*;
+use util::format::Pixel;
+use Error;
+
+/// How consecutive frames are compared.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Method {
+    /// Bit-exact matches, found by hashing each frame.
+    Exact,
+
+    /// Frames whose mean absolute difference per byte, over every plane, is
+    /// at most `threshold`, absorbing capture noise at the cost of keeping a
+    /// copy of the last unique frame.
+    Difference { threshold: f64 },
+}
+
+/// Detects frames identical to the one before them, as screen captures
+/// produce when nothing moves.
+///
+/// `check` only reports duplicates, leaving it up to the caller to skip or
+/// flag them, while `push` drops them and extends the duration of the frame
+/// they repeat, for variable frame rate outputs.
+pub struct Dedup {
+    method: Method,
+    max_run: Option<usize>,
+
+    reference: Option<Reference>,
+    run: usize,
+
+    pending: Option<Video>,
+
+    frames: u64,
+    duplicates: u64,
+}
+
+enum Reference {
+    Hash(Key, u64),
+    Copy(Key, Vec<u8>),
+}
+
+#[derive(PartialEq, Clone, Copy)]
+struct Key {
+    format: Pixel,
+    width: u32,
+    height: u32,
+}
+
+impl Dedup {
+    pub fn new(method: Method) -> Self {
+        Dedup {
+            method,
+            max_run: None,
+
+            reference: None,
+            run: 0,
+
+            pending: None,
+
+            frames: 0,
+            duplicates: 0,
+        }
+    }
+
+    /// Let through one frame after `value` consecutive duplicates, so that
+    /// live outputs keep receiving frames now and then.
+    pub fn max_run(mut self, value: usize) -> Self {
+        self.max_run = Some(value);
+        self
+    }
+
+    /// Frames checked so far.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Frames found to be duplicates so far.
+    pub fn duplicates(&self) -> u64 {
+        self.duplicates
+    }
+
+    /// Compare `frame` to the last unique one, returning whether it is a
+    /// duplicate. Frames changing format or size are always unique.
+    pub fn check(&mut self, frame: &Video) -> Result<bool, Error> {
+        let key = Key {
+            format: frame.format(),
+            width: frame.raw_width(),
+            height: frame.raw_height(),
+        };
+
+        self.frames += 1;
+
+        let duplicate = match (self.method, self.reference.as_ref()) {
+            (Method::Exact, Some(&Reference::Hash(k, hash))) if k == key => {
+                hash == self::hash(frame)?
+            }
+
+            (Method::Difference { threshold }, Some(&Reference::Copy(k, ref data))) if k == key => {
+                difference(frame, data)? <= threshold
+            }
+
+            _ => false,
+        };
+
+        let forced = duplicate && self.max_run.map_or(false, |max| self.run >= max);
+
+        if duplicate && !forced {
+            self.run += 1;
+            self.duplicates += 1;
+
+            return Ok(true);
+        }
+
+        self.run = 0;
+        self.reference = Some(match self.method {
+            Method::Exact => Reference::Hash(key, hash(frame)?),
+            Method::Difference { .. } => Reference::Copy(key, copy(frame)?),
+        });
+
+        Ok(false)
+    }
+
+    /// Feed the next frame, returning the previous unique one once it is
+    /// known how long it lasts.
+    ///
+    /// Duplicates are dropped and the duration of the frame they repeat,
+    /// `pkt_duration` in the time base of the pts, covers them.
+    pub fn push(&mut self, frame: Video) -> Result<Option<Video>, Error> {
+        if self.check(&frame)? {
+            return Ok(None);
+        }
+
+        let mut previous = match self.pending.take() {
+            Some(previous) => previous,
+            None => {
+                self.pending = Some(frame);
+                return Ok(None);
+            }
+        };
+
+        if let (Some(start), Some(end)) = (previous.pts(), frame.pts()) {
+            unsafe {
+                av_frame_set_pkt_duration(previous.as_mut_ptr(), end - start);
+            }
+        }
+
+        self.pending = Some(frame);
+
+        Ok(Some(previous))
+    }
+
+    /// Take the last unique frame, once the input is over. Its duration is
+    /// left as is.
+    pub fn flush(&mut self) -> Option<Video> {
+        self.pending.take()
+    }
+}
+
+// Visit the visible bytes of every row of every plane.
+fn rows<F: FnMut(&[u8])>(frame: &Video, mut f: F) -> Result<(), Error> {
+    if frame.is_hardware() {
+        return Err(Error::InvalidData);
+    }
+
+    for index in 0..frame.planes() {
+        frame.row_bytes(index).for_each(&mut f);
+    }
+
+    Ok(())
+}
+
+fn hash(frame: &Video) -> Result<u64, Error> {
+    let mut hasher = DefaultHasher::new();
+    rows(frame, |row| hasher.write(row))?;
+
+    Ok(hasher.finish())
+}
+
+fn copy(frame: &Video) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    rows(frame, |row| data.extend_from_slice(row))?;
+
+    Ok(data)
+}
+
+fn difference(frame: &Video, reference: &[u8]) -> Result<f64, Error> {
+    let mut offset = 0;
+    let mut sum = 0u64;
+
+    rows(frame, |row| {
+        let previous = &reference[offset..offset + row.len()];
+
+        sum += row
+            .iter()
+            .zip(previous)
+            .map(|(&a, &b)| u64::from((i16::from(a) - i16::from(b)).abs() as u16))
+            .sum::<u64>();
+
+        offset += row.len();
+    })?;
+
+    if offset == 0 {
+        return Ok(0.0);
+    }
+
+    Ok(sum as f64 / offset as f64)
+}
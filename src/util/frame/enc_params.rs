@@ -0,0 +1,178 @@
+use std::{mem, ptr};
+
+use super::side_data::Type;
+use super::Frame;
+use libc::c_int;
+
+/// Codec the quantizers of `EncParams` are expressed for, which decides
+/// their range and meaning.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Kind {
+    None,
+    /// `base_q_idx` and the segment deltas, from 0 to 255.
+    VP9,
+    /// The slice QP and the `mb_qp_delta` of each macroblock, from 0 to 51
+    /// (more above 8 bits).
+    H264,
+    /// `quantiser_scale_code` from 1 to 31, with no per block delta.
+    MPEG2,
+}
+
+impl From<c_int> for Kind {
+    fn from(value: c_int) -> Self {
+        match value {
+            0 => Kind::VP9,
+            1 => Kind::H264,
+            2 => Kind::MPEG2,
+            _ => Kind::None,
+        }
+    }
+}
+
+/// A block of the picture sharing a quantizer.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Block {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+
+    /// Difference between the quantizer of the block and the frame one.
+    pub delta_qp: i32,
+}
+
+/// Quantizers a decoder used for a frame, as exported in
+/// `AV_FRAME_DATA_VIDEO_ENC_PARAMS` side data when decoding with
+/// `export_side_data` set to `venc_params`.
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct EncParams {
+    pub kind: Kind,
+
+    /// Base quantizer of the frame.
+    pub qp: i32,
+
+    /// Quantizer offsets per plane, for the AC and DC coefficients
+    /// respectively.
+    pub delta_qp: [[i32; 2]; 4],
+
+    /// Blocks with their own quantizer, possibly not covering the whole
+    /// picture, in which case the rest uses `qp`.
+    pub blocks: Vec<Block>,
+}
+
+// Mirrors of `AVVideoEncParams` and `AVVideoBlockParams`.
+#[repr(C)]
+struct Header {
+    nb_blocks: u32,
+    blocks_offset: usize,
+    block_size: usize,
+    kind: c_int,
+    qp: i32,
+    delta_qp: [[i32; 2]; 4],
+}
+
+#[repr(C)]
+struct BlockParams {
+    src_x: c_int,
+    src_y: c_int,
+    w: c_int,
+    h: c_int,
+    delta_qp: i32,
+}
+
+impl EncParams {
+    /// Absolute quantizer of `block`.
+    pub fn block_qp(&self, block: &Block) -> i32 {
+        self.qp + block.delta_qp
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < mem::size_of::<Header>() {
+            return None;
+        }
+
+        let header = unsafe { ptr::read_unaligned(bytes.as_ptr() as *const Header) };
+        let mut blocks = Vec::with_capacity(header.nb_blocks as usize);
+
+        for i in 0..header.nb_blocks as usize {
+            let offset = header.blocks_offset + i * header.block_size;
+
+            if offset + mem::size_of::<BlockParams>() > bytes.len() {
+                return None;
+            }
+
+            let block =
+                unsafe { ptr::read_unaligned(bytes[offset..].as_ptr() as *const BlockParams) };
+
+            blocks.push(Block {
+                x: block.src_x,
+                y: block.src_y,
+                width: block.w,
+                height: block.h,
+                delta_qp: block.delta_qp,
+            });
+        }
+
+        Some(EncParams {
+            kind: Kind::from(header.kind),
+            qp: header.qp,
+            delta_qp: header.delta_qp,
+            blocks,
+        })
+    }
+}
+
+impl Frame {
+    pub fn enc_params(&self) -> Option<EncParams> {
+        self.side_data(Type::VIDEO_ENC_PARAMS)
+            .and_then(|data| EncParams::from_bytes(data.data()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes() {
+        let header = mem::size_of::<Header>();
+        let size = mem::size_of::<BlockParams>();
+        let mut bytes = vec![0u8; header + 2 * size];
+
+        unsafe {
+            ptr::write_unaligned(
+                bytes.as_mut_ptr() as *mut Header,
+                Header {
+                    nb_blocks: 2,
+                    blocks_offset: header,
+                    block_size: size,
+                    kind: 1,
+                    qp: 26,
+                    delta_qp: [[0; 2]; 4],
+                },
+            );
+
+            for i in 0..2 {
+                ptr::write_unaligned(
+                    bytes[header + i * size..].as_mut_ptr() as *mut BlockParams,
+                    BlockParams {
+                        src_x: 16 * i as c_int,
+                        src_y: 0,
+                        w: 16,
+                        h: 16,
+                        delta_qp: i as i32 * 3 - 2,
+                    },
+                );
+            }
+        }
+
+        let params = EncParams::from_bytes(&bytes).unwrap();
+
+        assert_eq!(params.kind, Kind::H264);
+        assert_eq!(params.blocks.len(), 2);
+        assert_eq!(params.blocks[1].x, 16);
+        assert_eq!(params.block_qp(&params.blocks[0]), 24);
+        assert_eq!(params.block_qp(&params.blocks[1]), 27);
+        assert_eq!(EncParams::from_bytes(&bytes[..header + size]), None);
+    }
+}
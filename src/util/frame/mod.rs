@@ -7,12 +7,29 @@ pub use self::video::Video;
 pub mod audio;
 pub use self::audio::Audio;
 
+pub mod composite;
+
+pub mod dedup;
+pub use self::dedup::Dedup;
+
+pub mod motion_vector;
+pub use self::motion_vector::MotionVector;
+
+#[cfg(feature = "ffmpeg_4_3")]
+pub mod enc_params;
+#[cfg(feature = "ffmpeg_4_3")]
+pub use self::enc_params::EncParams;
+
+pub mod pool;
+pub use self::pool::Pool;
+
 pub mod flag;
 pub use self::flag::Flags;
 
 use ffi::*;
-use libc::c_int;
-use {Dictionary, DictionaryRef};
+use libc::{c_int, ENOMEM};
+use time::Timestamp;
+use {Dictionary, DictionaryRef, Error, Rational, Rounding};
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub struct Packet {
@@ -70,6 +87,13 @@ impl Frame {
         unsafe { (*self.as_ptr()).key_frame == 1 }
     }
 
+    #[inline]
+    pub fn set_key(&mut self, value: bool) {
+        unsafe {
+            (*self.as_mut_ptr()).key_frame = value as c_int;
+        }
+    }
+
     #[inline]
     pub fn is_corrupt(&self) -> bool {
         self.flags().contains(Flags::CORRUPT)
@@ -106,6 +130,25 @@ impl Frame {
         }
     }
 
+    /// The presentation timestamp, given the time base it is expressed in.
+    #[inline]
+    pub fn pts_in(&self, time_base: Rational) -> Timestamp {
+        unsafe { Timestamp::new((*self.as_ptr()).pts, time_base) }
+    }
+
+    /// Set the presentation timestamp from `value`, rescaled to `time_base`.
+    #[inline]
+    pub fn set_pts_in(&mut self, value: Timestamp, time_base: Rational) {
+        self.set_pts(value.rescale_to(time_base, Rounding::NearInfinity).value());
+    }
+
+    /// The decoding timestamp of the packet the frame was decoded from,
+    /// given the time base it is expressed in.
+    #[inline]
+    pub fn dts_in(&self, time_base: Rational) -> Timestamp {
+        unsafe { Timestamp::new((*self.as_ptr()).pkt_dts, time_base) }
+    }
+
     #[inline]
     pub fn timestamp(&self) -> Option<i64> {
         unsafe {
@@ -116,11 +159,27 @@ impl Frame {
         }
     }
 
+    /// Quality as a lagrange multiplier, between 1 (good) and
+    /// `FF_LAMBDA_MAX` (bad). Encoders take it as the quantizer of the frame
+    /// when encoding with `codec::Flags::QSCALE`.
     #[inline]
     pub fn quality(&self) -> usize {
         unsafe { (*self.as_ptr()).quality as usize }
     }
 
+    #[inline]
+    pub fn set_quality(&mut self, value: usize) {
+        unsafe {
+            (*self.as_mut_ptr()).quality = value as c_int;
+        }
+    }
+
+    /// The quantizer `quality` corresponds to.
+    #[inline]
+    pub fn qp(&self) -> f64 {
+        self.quality() as f64 / f64::from(FF_QP2LAMBDA)
+    }
+
     #[inline]
     pub fn flags(&self) -> Flags {
         unsafe { Flags::from_bits_truncate((*self.as_ptr()).flags) }
@@ -164,12 +223,100 @@ impl Frame {
         }
     }
 
+    /// Attach a copy of `data` as side data of the given kind, replacing any
+    /// existing entry of that kind.
+    pub fn add_side_data(&mut self, kind: side_data::Type, data: &[u8]) -> Result<(), Error> {
+        self.remove_side_data(kind);
+
+        match self.new_side_data(kind, data.len()) {
+            Some(mut side_data) => {
+                side_data.data_mut().copy_from_slice(data);
+                Ok(())
+            }
+
+            None => Err(Error::Other { errno: ENOMEM }),
+        }
+    }
+
     #[inline]
     pub fn remove_side_data(&mut self, kind: side_data::Type) {
         unsafe {
             av_frame_remove_side_data(self.as_mut_ptr(), kind.into());
         }
     }
+
+    /// Whether the frame data can be modified in place, i.e. every buffer is
+    /// reference counted and this frame holds the only reference.
+    #[inline]
+    pub fn is_writable(&self) -> bool {
+        unsafe { av_frame_is_writable(self.as_ptr() as *mut _) != 0 }
+    }
+
+    /// Ensure the frame data is writable, copying it only if it is shared or
+    /// not reference counted.
+    #[inline]
+    pub fn make_writable(&mut self) -> Result<(), Error> {
+        unsafe {
+            match av_frame_make_writable(self.as_mut_ptr()) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Number of references to the first data buffer, `None` if the frame is
+    /// not reference counted.
+    #[inline]
+    pub fn ref_count(&self) -> Option<usize> {
+        unsafe {
+            let buf = (*self.as_ptr()).buf[0];
+
+            if buf.is_null() {
+                None
+            } else {
+                Some(av_buffer_get_ref_count(buf) as usize)
+            }
+        }
+    }
+
+    /// Create a new frame referencing the same data buffers, without copying
+    /// them.
+    #[inline]
+    pub fn clone_shallow(&self) -> Result<Frame, Error> {
+        unsafe {
+            let mut frame = Frame::empty();
+
+            match av_frame_ref(frame.as_mut_ptr(), self.as_ptr()) {
+                0 => Ok(frame),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Whether the data lives in a hardware surface rather than system memory.
+    #[inline]
+    pub fn is_hardware(&self) -> bool {
+        unsafe { !(*self.as_ptr()).hw_frames_ctx.is_null() }
+    }
+
+    /// Copy the data of `source` between a hardware surface and system memory,
+    /// along with its properties such as timestamps.
+    ///
+    /// When downloading into an empty frame, the first format reported by the
+    /// device for the transfer is used.
+    pub fn transfer_data_from(&mut self, source: &Frame) -> Result<(), Error> {
+        unsafe {
+            match av_hwframe_transfer_data(self.as_mut_ptr(), source.as_ptr(), 0) {
+                0 => (),
+                e => return Err(Error::from(e)),
+            }
+
+            match av_frame_copy_props(self.as_mut_ptr(), source.as_ptr()) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
 }
 
 impl Drop for Frame {
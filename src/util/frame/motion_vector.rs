@@ -0,0 +1,95 @@
+use std::{mem, ptr};
+
+use super::side_data::Type;
+use super::Frame;
+use ffi::*;
+use Error;
+
+/// A block motion vector, as exported by decoders with the `+export_mvs`
+/// flag set.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct MotionVector {
+    /// Whether the reference is in the past (negative) or the future
+    /// (positive).
+    pub source: i32,
+    pub width: u8,
+    pub height: u8,
+    pub source_x: i16,
+    pub source_y: i16,
+    pub destination_x: i16,
+    pub destination_y: i16,
+    pub flags: u64,
+    /// Motion in `1 / motion_scale` pixel units.
+    pub motion_x: i32,
+    pub motion_y: i32,
+    pub motion_scale: u16,
+}
+
+impl From<AVMotionVector> for MotionVector {
+    fn from(value: AVMotionVector) -> Self {
+        MotionVector {
+            source: value.source,
+            width: value.w,
+            height: value.h,
+            source_x: value.src_x,
+            source_y: value.src_y,
+            destination_x: value.dst_x,
+            destination_y: value.dst_y,
+            flags: value.flags,
+            motion_x: value.motion_x,
+            motion_y: value.motion_y,
+            motion_scale: value.motion_scale,
+        }
+    }
+}
+
+impl From<MotionVector> for AVMotionVector {
+    fn from(value: MotionVector) -> Self {
+        AVMotionVector {
+            source: value.source,
+            w: value.width,
+            h: value.height,
+            src_x: value.source_x,
+            src_y: value.source_y,
+            dst_x: value.destination_x,
+            dst_y: value.destination_y,
+            flags: value.flags,
+            motion_x: value.motion_x,
+            motion_y: value.motion_y,
+            motion_scale: value.motion_scale,
+        }
+    }
+}
+
+impl Frame {
+    pub fn motion_vectors(&self) -> Vec<MotionVector> {
+        let data = match self.side_data(Type::MotionVectors) {
+            Some(data) => data,
+            None => return Vec::new(),
+        };
+
+        let data = data.data();
+        let count = data.len() / mem::size_of::<AVMotionVector>();
+        let vectors = data.as_ptr() as *const AVMotionVector;
+
+        (0..count)
+            .map(|i| unsafe { MotionVector::from(ptr::read_unaligned(vectors.add(i))) })
+            .collect()
+    }
+
+    pub fn set_motion_vectors(&mut self, vectors: &[MotionVector]) -> Result<(), Error> {
+        let size = mem::size_of::<AVMotionVector>();
+        let mut data = vec![0u8; vectors.len() * size];
+
+        for (i, vector) in vectors.iter().enumerate() {
+            unsafe {
+                ptr::write_unaligned(
+                    data[i * size..].as_mut_ptr() as *mut AVMotionVector,
+                    AVMotionVector::from(*vector),
+                );
+            }
+        }
+
+        self.add_side_data(Type::MotionVectors, &data)
+    }
+}
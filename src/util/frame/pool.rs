@@ -0,0 +1,110 @@
+use super::Video;
+use ffi::*;
+use libc::{c_int, ENOMEM};
+use util::{dimensions, format};
+use Error;
+
+// Line alignment, and padding past the last plane, for SIMD code.
+const ALIGN: c_int = 64;
+
+/// Video frames of a fixed format and size backed by an `AVBufferPool`.
+///
+/// Buffers go back to the pool when the last reference to them is dropped,
+/// including references taken by encoders or filters, so steady state
+/// processing stops hitting the allocator.
+pub struct Pool {
+    ptr: *mut AVBufferPool,
+
+    format: format::Pixel,
+    width: u32,
+    height: u32,
+}
+
+unsafe impl Send for Pool {}
+unsafe impl Sync for Pool {}
+
+impl Pool {
+    pub fn new(format: format::Pixel, width: u32, height: u32) -> Result<Self, Error> {
+        dimensions::check_size_for(width, height, format, None)?;
+
+        unsafe {
+            let size =
+                av_image_get_buffer_size(format.into(), width as c_int, height as c_int, ALIGN);
+
+            if size < 0 {
+                return Err(Error::from(size));
+            }
+
+            let ptr = av_buffer_pool_init((size + ALIGN) as _, None);
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            Ok(Pool {
+                ptr,
+
+                format,
+                width,
+                height,
+            })
+        }
+    }
+
+    pub fn format(&self) -> format::Pixel {
+        self.format
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Take a frame from the pool. Its contents are left over from previous
+    /// use, and its properties are reset.
+    pub fn get(&self) -> Result<Video, Error> {
+        unsafe {
+            let buffer = av_buffer_pool_get(self.ptr);
+
+            if buffer.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            let mut frame = Video::empty();
+            let ptr = frame.as_mut_ptr();
+
+            (*ptr).buf[0] = buffer;
+            (*ptr).format = AVPixelFormat::from(self.format) as c_int;
+            (*ptr).width = self.width as c_int;
+            (*ptr).height = self.height as c_int;
+
+            match av_image_fill_arrays(
+                (*ptr).data.as_mut_ptr(),
+                (*ptr).linesize.as_mut_ptr(),
+                (*buffer).data,
+                self.format.into(),
+                self.width as c_int,
+                self.height as c_int,
+                ALIGN,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => {
+                    (*ptr).extended_data = (*ptr).data.as_mut_ptr();
+                    Ok(frame)
+                }
+            }
+        }
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        // Frees the pool once the buffers still out are returned.
+        unsafe {
+            av_buffer_pool_uninit(&mut self.ptr);
+        }
+    }
+}
@@ -6,7 +6,8 @@ use std::str::from_utf8_unchecked;
 use super::Frame;
 use ffi::AVFrameSideDataType::*;
 use ffi::*;
-use DictionaryRef;
+use util::hdr::{ContentLightLevel, MasteringDisplay};
+use {DictionaryRef, Error};
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Type {
@@ -188,8 +189,49 @@ impl<'a> SideData<'a> {
         unsafe { slice::from_raw_parts((*self.as_ptr()).data, (*self.as_ptr()).size as usize) }
     }
 
+    #[inline]
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        unsafe {
+            slice::from_raw_parts_mut((*self.as_mut_ptr()).data, (*self.as_ptr()).size as usize)
+        }
+    }
+
     #[inline]
     pub fn metadata(&self) -> DictionaryRef {
         unsafe { DictionaryRef::wrap((*self.as_ptr()).metadata) }
     }
 }
+
+impl Frame {
+    pub fn mastering_display(&self) -> Option<MasteringDisplay> {
+        MasteringDisplay::from_bytes(self.side_data(Type::MasteringDisplayMetadata)?.data())
+    }
+
+    pub fn set_mastering_display(&mut self, value: &MasteringDisplay) -> Result<(), Error> {
+        self.add_side_data(Type::MasteringDisplayMetadata, &value.to_bytes())
+    }
+
+    pub fn content_light_level(&self) -> Option<ContentLightLevel> {
+        ContentLightLevel::from_bytes(self.side_data(Type::ContentLightLevel)?.data())
+    }
+
+    pub fn set_content_light_level(&mut self, value: &ContentLightLevel) -> Result<(), Error> {
+        self.add_side_data(Type::ContentLightLevel, &value.to_bytes())
+    }
+
+    /// Raw ATSC A/53 closed caption data, as a series of 3-byte `cc_data`
+    /// packets.
+    pub fn a53_captions(&self) -> Option<&[u8]> {
+        self.side_data(Type::A53CC).map(|data| unsafe {
+            slice::from_raw_parts((*data.as_ptr()).data, (*data.as_ptr()).size as usize)
+        })
+    }
+
+    pub fn set_a53_captions(&mut self, data: &[u8]) -> Result<(), Error> {
+        if data.len() % 3 != 0 {
+            return Err(Error::InvalidData);
+        }
+
+        self.add_side_data(Type::A53CC, data)
+    }
+}
@@ -1,4 +1,7 @@
+use std::marker::PhantomData;
 use std::mem;
+#[cfg(feature = "nonzero")]
+use std::num::NonZeroU32;
 use std::ops::{Deref, DerefMut};
 use std::slice;
 
@@ -8,8 +11,12 @@ use ffi::*;
 use libc::c_int;
 use picture;
 use util::chroma;
+use util::dimensions;
+use util::error::Limit;
 use util::format;
-use Rational;
+use {Error, Rational};
+
+pub use super::composite::{composite, Alpha};
 
 #[derive(PartialEq, Eq)]
 pub struct Video(Frame);
@@ -36,6 +43,9 @@ impl Video {
         unsafe { Video(Frame::empty()) }
     }
 
+    /// Allocate a frame, leaving it without data when the dimensions are
+    /// invalid or the allocation fails. Use `try_new`, or check the
+    /// dimensions with `dimensions::check_size_for` first, to find out why.
     #[inline]
     pub fn new(format: format::Pixel, width: u32, height: u32) -> Self {
         unsafe {
@@ -46,6 +56,66 @@ impl Video {
         }
     }
 
+    /// Like `new`, but report dimensions over a limit with `Error::Limit`,
+    /// and allocation failures, instead of returning a frame without data.
+    pub fn try_new(format: format::Pixel, width: u32, height: u32) -> Result<Self, Error> {
+        dimensions::check_size_for(width, height, format, None)?;
+
+        unsafe {
+            let mut frame = Video::empty();
+            frame.set_format(format);
+            frame.set_width(width);
+            frame.set_height(height);
+
+            match av_frame_get_buffer(frame.as_mut_ptr(), 32) {
+                0 => Ok(frame),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Like `try_new`, with every line and plane starting on an `align`
+    /// byte boundary, a power of two up to 64.
+    pub fn new_aligned(
+        format: format::Pixel,
+        width: u32,
+        height: u32,
+        align: usize,
+    ) -> Result<Self, Error> {
+        check_alignment(align)?;
+        dimensions::check_size_for(width, height, format, None)?;
+
+        unsafe {
+            let mut frame = Video::empty();
+            frame.set_format(format);
+            frame.set_width(width);
+            frame.set_height(height);
+
+            match av_frame_get_buffer(frame.as_mut_ptr(), align as c_int) {
+                0 => Ok(frame),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// The largest power of two, up to 64, that the data pointer and line
+    /// size of every plane are multiples of.
+    pub fn alignment(&self) -> usize {
+        let mut bits = 0;
+
+        for index in 0..self.planes() {
+            unsafe {
+                bits |= (*self.as_ptr()).data[index] as usize;
+                bits |= (*self.as_ptr()).linesize[index] as usize;
+            }
+        }
+
+        match bits.trailing_zeros() {
+            n if n >= 6 => 64,
+            n => 1 << n,
+        }
+    }
+
     #[inline]
     pub fn format(&self) -> format::Pixel {
         unsafe {
@@ -81,21 +151,58 @@ impl Video {
         unsafe { (*self.as_ptr()).interlaced_frame != 0 }
     }
 
+    #[inline]
+    pub fn set_interlaced(&mut self, value: bool) {
+        unsafe {
+            (*self.as_mut_ptr()).interlaced_frame = value as c_int;
+        }
+    }
+
     #[inline]
     pub fn is_top_first(&self) -> bool {
         unsafe { (*self.as_ptr()).top_field_first != 0 }
     }
 
+    #[inline]
+    pub fn set_top_first(&mut self, value: bool) {
+        unsafe {
+            (*self.as_mut_ptr()).top_field_first = value as c_int;
+        }
+    }
+
     #[inline]
     pub fn has_palette_changed(&self) -> bool {
         unsafe { (*self.as_ptr()).palette_has_changed != 0 }
     }
 
+    #[cfg(not(feature = "nonzero"))]
     #[inline]
     pub fn width(&self) -> u32 {
+        self.raw_width()
+    }
+
+    /// `None` until the frame is given a size.
+    #[cfg(feature = "nonzero")]
+    #[inline]
+    pub fn width(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_width())
+    }
+
+    // The fields behind `width` and `height`, whatever type the `nonzero`
+    // feature makes them return.
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn raw_width(&self) -> u32 {
         unsafe { (*self.as_ptr()).width as u32 }
     }
 
+    #[doc(hidden)]
+    #[inline]
+    pub fn raw_height(&self) -> u32 {
+        unsafe { (*self.as_ptr()).height as u32 }
+    }
+
     #[inline]
     pub fn set_width(&mut self, value: u32) {
         unsafe {
@@ -103,9 +210,17 @@ impl Video {
         }
     }
 
+    #[cfg(not(feature = "nonzero"))]
     #[inline]
     pub fn height(&self) -> u32 {
-        unsafe { (*self.as_ptr()).height as u32 }
+        self.raw_height()
+    }
+
+    /// `None` until the frame is given a size.
+    #[cfg(feature = "nonzero")]
+    #[inline]
+    pub fn height(&self) -> Option<NonZeroU32> {
+        NonZeroU32::new(self.raw_height())
     }
 
     #[inline]
@@ -115,6 +230,77 @@ impl Video {
         }
     }
 
+    /// Rows to drop from the top of the picture, as decoders report them for
+    /// padded output.
+    #[inline]
+    pub fn crop_top(&self) -> usize {
+        unsafe { (*self.as_ptr()).crop_top as usize }
+    }
+
+    #[inline]
+    pub fn set_crop_top(&mut self, value: usize) {
+        unsafe {
+            (*self.as_mut_ptr()).crop_top = value as _;
+        }
+    }
+
+    #[inline]
+    pub fn crop_bottom(&self) -> usize {
+        unsafe { (*self.as_ptr()).crop_bottom as usize }
+    }
+
+    #[inline]
+    pub fn set_crop_bottom(&mut self, value: usize) {
+        unsafe {
+            (*self.as_mut_ptr()).crop_bottom = value as _;
+        }
+    }
+
+    #[inline]
+    pub fn crop_left(&self) -> usize {
+        unsafe { (*self.as_ptr()).crop_left as usize }
+    }
+
+    #[inline]
+    pub fn set_crop_left(&mut self, value: usize) {
+        unsafe {
+            (*self.as_mut_ptr()).crop_left = value as _;
+        }
+    }
+
+    #[inline]
+    pub fn crop_right(&self) -> usize {
+        unsafe { (*self.as_ptr()).crop_right as usize }
+    }
+
+    #[inline]
+    pub fn set_crop_right(&mut self, value: usize) {
+        unsafe {
+            (*self.as_mut_ptr()).crop_right = value as _;
+        }
+    }
+
+    /// Apply the crop fields, moving the data pointers and shrinking the
+    /// dimensions instead of copying, then reset them to zero.
+    ///
+    /// Left cropping is rounded down to keep the data pointers aligned for
+    /// SIMD code unless `unaligned` is set, so the width may end up larger
+    /// than requested.
+    pub fn apply_cropping(&mut self, unaligned: bool) -> Result<(), Error> {
+        let flags = if unaligned {
+            AV_FRAME_CROP_UNALIGNED as c_int
+        } else {
+            0
+        };
+
+        unsafe {
+            match av_frame_apply_cropping(self.as_mut_ptr(), flags) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
     #[inline]
     pub fn color_space(&self) -> color::Space {
         unsafe { color::Space::from(av_frame_get_colorspace(self.as_ptr())) }
@@ -188,6 +374,28 @@ impl Video {
         unsafe { f64::from((*self.as_ptr()).repeat_pict) }
     }
 
+    /// Number of extra fields the frame is displayed for, as signaled by
+    /// soft telecine: 1 for a 3:2 pulldown frame repeating its first field,
+    /// 2 and 4 for progressive frame doubling and tripling.
+    #[inline]
+    pub fn repeated_fields(&self) -> u32 {
+        unsafe { (*self.as_ptr()).repeat_pict.max(0) as u32 }
+    }
+
+    #[inline]
+    pub fn set_repeated_fields(&mut self, value: u32) {
+        unsafe {
+            (*self.as_mut_ptr()).repeat_pict = value as c_int;
+        }
+    }
+
+    /// Number of fields the frame is displayed for, 2 for a frame without
+    /// repeats. Summing it over a stream gives its field-accurate duration.
+    #[inline]
+    pub fn fields(&self) -> u32 {
+        2 + self.repeated_fields()
+    }
+
     #[inline]
     pub fn stride(&self, index: usize) -> usize {
         if index >= self.planes() {
@@ -218,14 +426,14 @@ impl Video {
 
         // Logic taken from image_get_linesize().
         if index != 1 && index != 2 {
-            return self.width();
+            return self.raw_width();
         }
 
         if let Some(desc) = self.format().descriptor() {
             let s = desc.log2_chroma_w();
-            (self.width() + (1 << s) - 1) >> s
+            (self.raw_width() + (1 << s) - 1) >> s
         } else {
-            self.width()
+            self.raw_width()
         }
     }
 
@@ -237,14 +445,14 @@ impl Video {
 
         // Logic taken from av_image_fill_pointers().
         if index != 1 && index != 2 {
-            return self.height();
+            return self.raw_height();
         }
 
         if let Some(desc) = self.format().descriptor() {
             let s = desc.log2_chroma_h();
-            (self.height() + (1 << s) - 1) >> s
+            (self.raw_height() + (1 << s) - 1) >> s
         } else {
-            self.height()
+            self.raw_height()
         }
     }
 
@@ -284,6 +492,93 @@ impl Video {
         }
     }
 
+    /// Iterate over the rows of a plane as `T`, each holding the visible
+    /// pixels only, without the padding at the end of the line.
+    ///
+    /// Bottom-up frames with negative strides are walked top to bottom too.
+    pub fn rows<T: Component>(&self, index: usize) -> Rows<T> {
+        let (width, stride) = self.row_layout::<T>(index);
+
+        Rows {
+            ptr: unsafe { (*self.as_ptr()).data[index] as *const T },
+            stride,
+            width,
+            row: 0,
+            height: self.plane_height(index) as usize,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn rows_mut<T: Component>(&mut self, index: usize) -> RowsMut<T> {
+        let (width, stride) = self.row_layout::<T>(index);
+
+        RowsMut {
+            ptr: unsafe { (*self.as_mut_ptr()).data[index] as *mut T },
+            stride,
+            width,
+            row: 0,
+            height: self.plane_height(index) as usize,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Iterate over the rows of a plane as bytes, whatever the format, each
+    /// holding the bytes of the visible pixels only.
+    ///
+    /// Panics for hardware and bitstream formats, which have no rows.
+    pub fn row_bytes(&self, index: usize) -> Rows<u8> {
+        if index >= self.planes() {
+            panic!("out of bounds");
+        }
+
+        unsafe {
+            let bytes = av_image_get_linesize(
+                self.format().into(),
+                self.raw_width() as c_int,
+                index as c_int,
+            );
+
+            if bytes < 0 {
+                panic!("unsupported format");
+            }
+
+            Rows {
+                ptr: (*self.as_ptr()).data[index] as *const u8,
+                stride: (*self.as_ptr()).linesize[index] as isize,
+                width: bytes as usize,
+                row: 0,
+                height: self.plane_height(index) as usize,
+                _marker: PhantomData,
+            }
+        }
+    }
+
+    // Visible elements per row and stride in bytes.
+    fn row_layout<T: Component>(&self, index: usize) -> (usize, isize) {
+        if index >= self.planes() {
+            panic!("out of bounds");
+        }
+
+        if !<T as Component>::is_valid(self.format()) {
+            panic!("unsupported type");
+        }
+
+        unsafe {
+            let bytes = av_image_get_linesize(
+                self.format().into(),
+                self.raw_width() as c_int,
+                index as c_int,
+            );
+            let stride = (*self.as_ptr()).linesize[index] as isize;
+
+            if bytes < 0 || stride % mem::align_of::<T>() as isize != 0 {
+                panic!("unsupported type");
+            }
+
+            (bytes as usize / mem::size_of::<T>(), stride)
+        }
+    }
+
     #[inline]
     pub fn data(&self, index: usize) -> &[u8] {
         if index >= self.planes() {
@@ -332,7 +627,7 @@ impl DerefMut for Video {
 impl Clone for Video {
     #[inline]
     fn clone(&self) -> Self {
-        let mut cloned = Video::new(self.format(), self.width(), self.height());
+        let mut cloned = Video::new(self.format(), self.raw_width(), self.raw_height());
         cloned.clone_from(self);
 
         cloned
@@ -423,3 +718,162 @@ unsafe impl Component for (u8, u8, u8, u8) {
             || format == format::Pixel::ZBGR
     }
 }
+
+// Whether every component of the format fits in `bits` wide native endian
+// words, and at least one needs more than half of them.
+fn is_word(format: format::Pixel, bits: i32) -> bool {
+    let descriptor = match format.descriptor() {
+        Some(descriptor) => unsafe { &*descriptor.as_ptr() },
+        None => return false,
+    };
+
+    let excluded = (AV_PIX_FMT_FLAG_HWACCEL | AV_PIX_FMT_FLAG_BITSTREAM) as u64;
+    let big_endian = descriptor.flags & AV_PIX_FMT_FLAG_BE as u64 != 0;
+
+    if descriptor.flags & excluded != 0 || (bits > 8 && big_endian != cfg!(target_endian = "big")) {
+        return false;
+    }
+
+    let components = &descriptor.comp[..descriptor.nb_components as usize];
+
+    components
+        .iter()
+        .all(|c| c.depth + c.shift <= bits && c.step % (bits / 8) == 0)
+        && components.iter().any(|c| c.depth > bits / 2)
+}
+
+/// Samples of planar formats of up to 8 bits per component, and the bytes
+/// of packed ones.
+unsafe impl Component for u8 {
+    #[inline(always)]
+    fn is_valid(format: format::Pixel) -> bool {
+        is_word(format, 8)
+    }
+}
+
+/// Samples of formats of 9 to 16 bits per component in native endianness,
+/// such as `YUV420P10` or `RGB48`.
+unsafe impl Component for u16 {
+    #[inline(always)]
+    fn is_valid(format: format::Pixel) -> bool {
+        is_word(format, 16)
+    }
+}
+
+pub struct Rows<'a, T: 'a> {
+    ptr: *const T,
+    stride: isize,
+    width: usize,
+    row: usize,
+    height: usize,
+
+    _marker: PhantomData<&'a [T]>,
+}
+
+impl<'a, T: 'a> Iterator for Rows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.row == self.height {
+            return None;
+        }
+
+        // Only rows within the plane are addressed, strides being negative
+        // for bottom-up pictures.
+        unsafe {
+            let ptr = (self.ptr as *const u8).offset(self.row as isize * self.stride);
+            self.row += 1;
+
+            Some(slice::from_raw_parts(ptr as *const T, self.width))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.height - self.row;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Rows<'a, T> {}
+
+pub struct RowsMut<'a, T: 'a> {
+    ptr: *mut T,
+    stride: isize,
+    width: usize,
+    row: usize,
+    height: usize,
+
+    _marker: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T: 'a> Iterator for RowsMut<'a, T> {
+    type Item = &'a mut [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.row == self.height {
+            return None;
+        }
+
+        // Rows never overlap, so handing out each once is sound.
+        unsafe {
+            let ptr = (self.ptr as *mut u8).offset(self.row as isize * self.stride);
+            self.row += 1;
+
+            Some(slice::from_raw_parts_mut(ptr as *mut T, self.width))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.height - self.row;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for RowsMut<'a, T> {}
+
+/// Predict the line sizes `Video::new_aligned` allocates for `format` at
+/// `width`, one per plane.
+pub fn linesizes(format: format::Pixel, width: u32, align: usize) -> Result<Vec<usize>, Error> {
+    check_alignment(align)?;
+
+    if width > c_int::max_value() as u32 {
+        return Err(Error::Limit(Limit::Width));
+    }
+
+    let mut linesizes = [0; 4];
+
+    // Same as av_frame_get_buffer(): pad the width until the first line
+    // size is aligned, then align every line size.
+    let mut step = 1;
+
+    while step <= align {
+        let padded = (width as usize + step - 1) & !(step - 1);
+
+        unsafe {
+            match av_image_fill_linesizes(linesizes.as_mut_ptr(), format.into(), padded as c_int) {
+                e if e < 0 => return Err(Error::from(e)),
+                _ => (),
+            }
+        }
+
+        if linesizes[0] as usize & (align - 1) == 0 {
+            break;
+        }
+
+        step *= 2;
+    }
+
+    Ok(linesizes
+        .iter()
+        .take_while(|&&size| size != 0)
+        .map(|&size| (size as usize + align - 1) & !(align - 1))
+        .collect())
+}
+
+fn check_alignment(align: usize) -> Result<(), Error> {
+    if align == 0 || align > 64 || !align.is_power_of_two() {
+        return Err(Error::Limit(Limit::Alignment));
+    }
+
+    Ok(())
+}
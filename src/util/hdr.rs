@@ -0,0 +1,157 @@
+//! HDR metadata shared by packet and frame side data.
+
+use std::{mem, ptr, slice};
+
+use ffi::*;
+use libc::{c_int, c_uint};
+use Rational;
+
+// Mirror the structs of libavutil/mastering_display_metadata.h, which the
+// bindings do not cover.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RawMasteringDisplay {
+    display_primaries: [[AVRational; 2]; 3],
+    white_point: [AVRational; 2],
+    min_luminance: AVRational,
+    max_luminance: AVRational,
+    has_primaries: c_int,
+    has_luminance: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RawContentLightLevel {
+    max_cll: c_uint,
+    max_fall: c_uint,
+}
+
+/// CIE 1931 xy chromaticity coordinates.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Chromaticity {
+    pub x: Rational,
+    pub y: Rational,
+}
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Primaries {
+    pub red: Chromaticity,
+    pub green: Chromaticity,
+    pub blue: Chromaticity,
+    pub white: Chromaticity,
+}
+
+/// Color volume of the display an HDR stream was mastered on (SMPTE ST 2086).
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct MasteringDisplay {
+    pub primaries: Option<Primaries>,
+    /// Minimum and maximum luminance in cd/m².
+    pub luminance: Option<(Rational, Rational)>,
+}
+
+impl MasteringDisplay {
+    /// Read an `AVMasteringDisplayMetadata` side data payload.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let value = unsafe { read::<RawMasteringDisplay>(bytes)? };
+
+        let point = |xy: [AVRational; 2]| Chromaticity {
+            x: Rational::from(xy[0]),
+            y: Rational::from(xy[1]),
+        };
+
+        Some(MasteringDisplay {
+            primaries: if value.has_primaries != 0 {
+                Some(Primaries {
+                    red: point(value.display_primaries[0]),
+                    green: point(value.display_primaries[1]),
+                    blue: point(value.display_primaries[2]),
+                    white: point(value.white_point),
+                })
+            } else {
+                None
+            },
+
+            luminance: if value.has_luminance != 0 {
+                Some((
+                    Rational::from(value.min_luminance),
+                    Rational::from(value.max_luminance),
+                ))
+            } else {
+                None
+            },
+        })
+    }
+
+    /// Write an `AVMasteringDisplayMetadata` side data payload.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let zero = AVRational { num: 0, den: 1 };
+        let point = |c: Chromaticity| [c.x.into(), c.y.into()];
+
+        let (display_primaries, white_point) = match self.primaries {
+            Some(p) => (
+                [point(p.red), point(p.green), point(p.blue)],
+                point(p.white),
+            ),
+            None => ([[zero; 2]; 3], [zero; 2]),
+        };
+
+        let (min_luminance, max_luminance) = match self.luminance {
+            Some((min, max)) => (min.into(), max.into()),
+            None => (zero, zero),
+        };
+
+        unsafe {
+            bytes(&RawMasteringDisplay {
+                display_primaries,
+                white_point,
+                min_luminance,
+                max_luminance,
+                has_primaries: self.primaries.is_some() as c_int,
+                has_luminance: self.luminance.is_some() as c_int,
+            })
+        }
+    }
+}
+
+/// Content light level of an HDR stream (CTA-861.3), in cd/m².
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct ContentLightLevel {
+    /// Maximum content light level of any pixel.
+    pub max_cll: u32,
+    /// Maximum frame-average light level.
+    pub max_fall: u32,
+}
+
+impl ContentLightLevel {
+    /// Read an `AVContentLightMetadata` side data payload.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let value = unsafe { read::<RawContentLightLevel>(bytes)? };
+
+        Some(ContentLightLevel {
+            max_cll: value.max_cll,
+            max_fall: value.max_fall,
+        })
+    }
+
+    /// Write an `AVContentLightMetadata` side data payload.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        unsafe {
+            bytes(&RawContentLightLevel {
+                max_cll: self.max_cll,
+                max_fall: self.max_fall,
+            })
+        }
+    }
+}
+
+unsafe fn read<T: Copy>(bytes: &[u8]) -> Option<T> {
+    if bytes.len() < mem::size_of::<T>() {
+        return None;
+    }
+
+    Some(ptr::read_unaligned(bytes.as_ptr() as *const T))
+}
+
+unsafe fn bytes<T: Copy>(value: &T) -> Vec<u8> {
+    slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>()).to_vec()
+}
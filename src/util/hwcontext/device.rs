@@ -0,0 +1,81 @@
+use std::ffi::CString;
+use std::ptr;
+
+use super::Type;
+use ffi::*;
+use {Dictionary, Error};
+
+/// A reference to an `AVHWDeviceContext`, shareable between codecs.
+pub struct Device {
+    ptr: *mut AVBufferRef,
+}
+
+unsafe impl Send for Device {}
+unsafe impl Sync for Device {}
+
+impl Device {
+    pub unsafe fn wrap(ptr: *mut AVBufferRef) -> Self {
+        Device { ptr }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVBufferRef {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVBufferRef {
+        self.ptr
+    }
+}
+
+impl Device {
+    /// Open a device of the given type; `device` selects a specific one in a
+    /// type-dependent way, such as a DRM node path or a CUDA device index.
+    pub fn create(kind: Type, device: Option<&str>) -> Result<Self, Error> {
+        Device::create_with(kind, device, Dictionary::new())
+    }
+
+    pub fn create_with(
+        kind: Type,
+        device: Option<&str>,
+        options: Dictionary,
+    ) -> Result<Self, Error> {
+        unsafe {
+            let device = device.map(|d| CString::new(d).unwrap());
+            let mut ptr = ptr::null_mut();
+            let opts = options.disown();
+
+            let res = av_hwdevice_ctx_create(
+                &mut ptr,
+                kind.into(),
+                device.as_ref().map_or(ptr::null(), |d| d.as_ptr()),
+                opts,
+                0,
+            );
+
+            Dictionary::own(opts);
+
+            match res {
+                0 => Ok(Device::wrap(ptr)),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    pub fn kind(&self) -> Type {
+        unsafe { Type::from((*((*self.as_ptr()).data as *const AVHWDeviceContext)).type_) }
+    }
+}
+
+impl Clone for Device {
+    fn clone(&self) -> Self {
+        unsafe { Device::wrap(av_buffer_ref(self.ptr)) }
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        unsafe {
+            av_buffer_unref(&mut self.ptr);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use libc::{c_int, ENOMEM};
+
+use super::Device;
+use ffi::*;
+use util::format;
+use {frame, Error};
+
+/// A reference to an `AVHWFramesContext`, a pool of hardware surfaces.
+pub struct Frames {
+    ptr: *mut AVBufferRef,
+}
+
+unsafe impl Send for Frames {}
+unsafe impl Sync for Frames {}
+
+impl Frames {
+    pub unsafe fn wrap(ptr: *mut AVBufferRef) -> Self {
+        Frames { ptr }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVBufferRef {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVBufferRef {
+        self.ptr
+    }
+
+    unsafe fn context(&self) -> *const AVHWFramesContext {
+        (*self.as_ptr()).data as *const _
+    }
+}
+
+impl Frames {
+    /// Allocate a pool of `size` surfaces of `format` on `device`, holding
+    /// `sw_format` data, as used by hardware encoders and uploads.
+    pub fn new(
+        device: &Device,
+        format: format::Pixel,
+        sw_format: format::Pixel,
+        width: u32,
+        height: u32,
+        size: usize,
+    ) -> Result<Self, Error> {
+        unsafe {
+            let ptr = av_hwframe_ctx_alloc(device.as_ptr() as *mut _);
+
+            if ptr.is_null() {
+                return Err(Error::Other { errno: ENOMEM });
+            }
+
+            let mut frames = Frames::wrap(ptr);
+            let context = (*ptr).data as *mut AVHWFramesContext;
+
+            (*context).format = format.into();
+            (*context).sw_format = sw_format.into();
+            (*context).width = width as c_int;
+            (*context).height = height as c_int;
+            (*context).initial_pool_size = size as c_int;
+
+            match av_hwframe_ctx_init(frames.as_mut_ptr()) {
+                0 => Ok(frames),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    pub fn format(&self) -> format::Pixel {
+        unsafe { format::Pixel::from((*self.context()).format) }
+    }
+
+    pub fn sw_format(&self) -> format::Pixel {
+        unsafe { format::Pixel::from((*self.context()).sw_format) }
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe { (*self.context()).width as u32 }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe { (*self.context()).height as u32 }
+    }
+
+    /// Attach a surface from the pool to `frame`.
+    pub fn get_buffer(&self, frame: &mut frame::Video) -> Result<(), Error> {
+        unsafe {
+            match av_hwframe_get_buffer(self.ptr, frame.as_mut_ptr(), 0) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+}
+
+impl Clone for Frames {
+    fn clone(&self) -> Self {
+        unsafe { Frames::wrap(av_buffer_ref(self.ptr)) }
+    }
+}
+
+impl Drop for Frames {
+    fn drop(&mut self) {
+        unsafe {
+            av_buffer_unref(&mut self.ptr);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::ffi::{CStr, CString};
+use std::str::from_utf8_unchecked;
+
+use ffi::AVHWDeviceType::*;
+use ffi::*;
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Type {
+    None,
+    VDPAU,
+    CUDA,
+    VAAPI,
+    DXVA2,
+    QSV,
+    VideoToolbox,
+    D3D11VA,
+    #[cfg(feature = "ffmpeg_4_0")]
+    DRM,
+    #[cfg(feature = "ffmpeg_4_0")]
+    OpenCL,
+    #[cfg(feature = "ffmpeg_4_0")]
+    MediaCodec,
+    #[cfg(feature = "ffmpeg_4_3")]
+    Vulkan,
+}
+
+impl Type {
+    /// Look up a device type by its FFmpeg name, such as `"cuda"`.
+    pub fn find(name: &str) -> Option<Type> {
+        let name = CString::new(name).unwrap();
+
+        match unsafe { av_hwdevice_find_type_by_name(name.as_ptr()) } {
+            AV_HWDEVICE_TYPE_NONE => None,
+            kind => Some(Type::from(kind)),
+        }
+    }
+
+    pub fn name(&self) -> Option<&'static str> {
+        unsafe {
+            let ptr = av_hwdevice_get_type_name((*self).into());
+
+            if ptr.is_null() {
+                None
+            } else {
+                Some(from_utf8_unchecked(CStr::from_ptr(ptr).to_bytes()))
+            }
+        }
+    }
+}
+
+/// Iterate over the device types supported by the linked FFmpeg.
+pub fn types() -> TypeIter {
+    TypeIter::new()
+}
+
+pub struct TypeIter {
+    current: AVHWDeviceType,
+}
+
+impl TypeIter {
+    pub fn new() -> Self {
+        TypeIter {
+            current: AV_HWDEVICE_TYPE_NONE,
+        }
+    }
+}
+
+impl Default for TypeIter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for TypeIter {
+    type Item = Type;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            self.current = av_hwdevice_iterate_types(self.current);
+
+            match self.current {
+                AV_HWDEVICE_TYPE_NONE => None,
+                kind => Some(Type::from(kind)),
+            }
+        }
+    }
+}
+
+impl From<AVHWDeviceType> for Type {
+    fn from(value: AVHWDeviceType) -> Type {
+        match value {
+            AV_HWDEVICE_TYPE_NONE => Type::None,
+            AV_HWDEVICE_TYPE_VDPAU => Type::VDPAU,
+            AV_HWDEVICE_TYPE_CUDA => Type::CUDA,
+            AV_HWDEVICE_TYPE_VAAPI => Type::VAAPI,
+            AV_HWDEVICE_TYPE_DXVA2 => Type::DXVA2,
+            AV_HWDEVICE_TYPE_QSV => Type::QSV,
+            AV_HWDEVICE_TYPE_VIDEOTOOLBOX => Type::VideoToolbox,
+            AV_HWDEVICE_TYPE_D3D11VA => Type::D3D11VA,
+            #[cfg(feature = "ffmpeg_4_0")]
+            AV_HWDEVICE_TYPE_DRM => Type::DRM,
+            #[cfg(feature = "ffmpeg_4_0")]
+            AV_HWDEVICE_TYPE_OPENCL => Type::OpenCL,
+            #[cfg(feature = "ffmpeg_4_0")]
+            AV_HWDEVICE_TYPE_MEDIACODEC => Type::MediaCodec,
+            #[cfg(feature = "ffmpeg_4_3")]
+            AV_HWDEVICE_TYPE_VULKAN => Type::Vulkan,
+        }
+    }
+}
+
+impl From<Type> for AVHWDeviceType {
+    fn from(value: Type) -> AVHWDeviceType {
+        match value {
+            Type::None => AV_HWDEVICE_TYPE_NONE,
+            Type::VDPAU => AV_HWDEVICE_TYPE_VDPAU,
+            Type::CUDA => AV_HWDEVICE_TYPE_CUDA,
+            Type::VAAPI => AV_HWDEVICE_TYPE_VAAPI,
+            Type::DXVA2 => AV_HWDEVICE_TYPE_DXVA2,
+            Type::QSV => AV_HWDEVICE_TYPE_QSV,
+            Type::VideoToolbox => AV_HWDEVICE_TYPE_VIDEOTOOLBOX,
+            Type::D3D11VA => AV_HWDEVICE_TYPE_D3D11VA,
+            #[cfg(feature = "ffmpeg_4_0")]
+            Type::DRM => AV_HWDEVICE_TYPE_DRM,
+            #[cfg(feature = "ffmpeg_4_0")]
+            Type::OpenCL => AV_HWDEVICE_TYPE_OPENCL,
+            #[cfg(feature = "ffmpeg_4_0")]
+            Type::MediaCodec => AV_HWDEVICE_TYPE_MEDIACODEC,
+            #[cfg(feature = "ffmpeg_4_3")]
+            Type::Vulkan => AV_HWDEVICE_TYPE_VULKAN,
+        }
+    }
+}
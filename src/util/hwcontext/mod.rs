@@ -0,0 +1,13 @@
+pub mod kind;
+pub use self::kind::{types, Type};
+
+pub mod device;
+pub use self::device::Device;
+
+pub mod frames;
+pub use self::frames::Frames;
+
+#[cfg(feature = "software-scaling")]
+pub mod transfer;
+#[cfg(feature = "software-scaling")]
+pub use self::transfer::download_many;
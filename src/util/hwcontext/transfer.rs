@@ -0,0 +1,120 @@
+use std::ptr;
+
+use ffi::AVHWFrameTransferDirection::AV_HWFRAME_TRANSFER_DIRECTION_FROM;
+use ffi::*;
+use libc::c_int;
+use software::scaling;
+use util::format;
+use {frame, Error};
+
+/// Download hardware frames into system memory frames in `format`.
+///
+/// Transfer capabilities are queried once per frames context rather than per
+/// frame. Surfaces are downloaded straight into `format` when the device
+/// supports it; otherwise they are downloaded in their native software format
+/// and converted with a single scaler reused across the batch.
+pub fn download_many(
+    frames: &[frame::Video],
+    format: format::Pixel,
+) -> Result<Vec<frame::Video>, Error> {
+    let mut output = Vec::with_capacity(frames.len());
+    let mut context = ptr::null_mut();
+    let mut direct = false;
+    let mut scaler: Option<scaling::Context> = None;
+
+    for source in frames {
+        if !source.is_hardware() {
+            return Err(Error::InvalidData);
+        }
+
+        unsafe {
+            let frames_ctx = (*source.as_ptr()).hw_frames_ctx;
+
+            if (*frames_ctx).data != context {
+                context = (*frames_ctx).data;
+                direct = supports(frames_ctx, format)?;
+            }
+        }
+
+        let mut destination = frame::Video::empty();
+
+        if direct {
+            unsafe {
+                (*destination.as_mut_ptr()).format = AVPixelFormat::from(format) as c_int;
+            }
+
+            destination.transfer_data_from(source)?;
+            output.push(destination);
+
+            continue;
+        }
+
+        let mut native = frame::Video::empty();
+        native.transfer_data_from(source)?;
+
+        let reuse = match scaler {
+            Some(ref scaler) => {
+                scaler.input().format == native.format()
+                    && scaler.input().width == native.raw_width()
+                    && scaler.input().height == native.raw_height()
+            }
+            None => false,
+        };
+
+        if !reuse {
+            scaler = Some(scaling::Context::get(
+                native.format(),
+                native.raw_width(),
+                native.raw_height(),
+                format,
+                native.raw_width(),
+                native.raw_height(),
+                scaling::Flags::BILINEAR,
+            )?);
+        }
+
+        scaler.as_mut().unwrap().run(&native, &mut destination)?;
+
+        unsafe {
+            match av_frame_copy_props(destination.as_mut_ptr(), native.as_ptr()) {
+                0 => (),
+                e => return Err(Error::from(e)),
+            }
+        }
+
+        output.push(destination);
+    }
+
+    Ok(output)
+}
+
+unsafe fn supports(frames_ctx: *mut AVBufferRef, format: format::Pixel) -> Result<bool, Error> {
+    let mut formats = ptr::null_mut();
+
+    match av_hwframe_transfer_get_formats(
+        frames_ctx,
+        AV_HWFRAME_TRANSFER_DIRECTION_FROM,
+        &mut formats,
+        0,
+    ) {
+        0 => (),
+        e => return Err(Error::from(e)),
+    }
+
+    let wanted = AVPixelFormat::from(format);
+    let mut found = false;
+    let mut current = formats;
+
+    while *current != AVPixelFormat::AV_PIX_FMT_NONE {
+        if *current == wanted {
+            found = true;
+            break;
+        }
+
+        current = current.offset(1);
+    }
+
+    av_free(formats as *mut _);
+
+    Ok(found)
+}
@@ -18,6 +18,20 @@ where
     }
 }
 
+/// A callback calling `closure` in place, which must stay alive as long as
+/// the callback can be invoked.
+pub unsafe fn borrowed<F>(closure: &mut F) -> Interrupt
+where
+    F: FnMut() -> bool,
+{
+    Interrupt {
+        interrupt: AVIOInterruptCB {
+            callback: Some(callback::<F>),
+            opaque: closure as *mut F as *mut c_void,
+        },
+    }
+}
+
 pub fn new<F>(opaque: Box<F>) -> Interrupt
 where
     F: FnMut() -> bool,
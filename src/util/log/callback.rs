@@ -0,0 +1,215 @@
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use super::Level;
+use ffi::*;
+use libc::{c_char, c_int, c_void};
+
+/// A complete log line, as emitted by FFmpeg.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Message<'a> {
+    pub level: Level,
+
+    /// Name of the emitting component, such as `h264` or `mov,mp4,m4a`.
+    pub class: Option<&'a str>,
+
+    /// The text, without its trailing newline.
+    pub text: &'a str,
+}
+
+type Callback = Box<dyn Fn(&Message) + Send + Sync>;
+
+static CALLBACK: AtomicPtr<Callback> = AtomicPtr::new(ptr::null_mut());
+
+// `va_list` as bindgen passes it to the callback: the System V x86_64 one is
+// an array, which decays to a pointer to its only element.
+#[cfg(all(target_arch = "x86_64", not(windows)))]
+type VaList = *mut __va_list_tag;
+
+#[cfg(not(all(target_arch = "x86_64", not(windows))))]
+type VaList = va_list;
+
+thread_local! {
+    // FFmpeg often builds a line out of several calls.
+    static PENDING: RefCell<Vec<u8>> = RefCell::new(Vec::new());
+}
+
+/// Route FFmpeg log messages at or below the current level (see
+/// `set_level`) to `callback` instead of standard error.
+///
+/// The callback may run on any thread, including codec worker threads, and
+/// panics in it are swallowed. A replaced callback is leaked on purpose:
+/// other threads may still be running it, and there is no telling when they
+/// are done. Set a callback once rather than swapping them repeatedly.
+pub fn set_callback<F>(callback: F)
+where
+    F: Fn(&Message) + Send + Sync + 'static,
+{
+    let callback: Callback = Box::new(callback);
+
+    CALLBACK.store(Box::into_raw(Box::new(callback)), Ordering::Release);
+
+    unsafe {
+        av_log_set_callback(Some(trampoline));
+    }
+}
+
+/// Restore the default FFmpeg logging to standard error.
+pub fn reset_callback() {
+    unsafe {
+        av_log_set_callback(Some(av_log_default_callback));
+    }
+}
+
+unsafe extern "C" fn trampoline(
+    avcl: *mut c_void,
+    level: c_int,
+    format: *const c_char,
+    arguments: VaList,
+) {
+    if level > av_log_get_level() {
+        return;
+    }
+
+    let callback = CALLBACK.load(Ordering::Acquire);
+
+    if callback.is_null() {
+        return;
+    }
+
+    let mut line = [0 as c_char; 1024];
+    let mut prefix = 0;
+
+    av_log_format_line2(
+        avcl,
+        level,
+        format,
+        arguments,
+        line.as_mut_ptr(),
+        line.len() as c_int,
+        &mut prefix,
+    );
+
+    let bytes = CStr::from_ptr(line.as_ptr()).to_bytes();
+    let class = class(avcl);
+
+    let text = PENDING.try_with(|pending| {
+        let mut pending = pending.borrow_mut();
+        pending.extend_from_slice(bytes);
+
+        if pending.last() != Some(&b'\n') {
+            return None;
+        }
+
+        pending.pop();
+
+        let text = String::from_utf8_lossy(&pending).into_owned();
+        pending.clear();
+
+        Some(text)
+    });
+
+    if let Ok(Some(text)) = text {
+        let message = Message {
+            level: level_of(level),
+            class: class.as_ref().map(|class| &class[..]),
+            text: &text,
+        };
+
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| (*callback)(&message)));
+    }
+}
+
+unsafe fn class(avcl: *mut c_void) -> Option<String> {
+    if avcl.is_null() {
+        return None;
+    }
+
+    let class = *(avcl as *const *const AVClass);
+
+    if class.is_null() {
+        return None;
+    }
+
+    let name = match (*class).item_name {
+        Some(item_name) => item_name(avcl),
+        None => (*class).class_name,
+    };
+
+    if name.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+    }
+}
+
+// Levels in between the named ones belong to the next more severe one.
+fn level_of(value: c_int) -> Level {
+    match value {
+        v if v <= AV_LOG_PANIC => Level::Panic,
+        v if v <= AV_LOG_FATAL => Level::Fatal,
+        v if v <= AV_LOG_ERROR => Level::Error,
+        v if v <= AV_LOG_WARNING => Level::Warning,
+        v if v <= AV_LOG_INFO => Level::Info,
+        v if v <= AV_LOG_VERBOSE => Level::Verbose,
+        v if v <= AV_LOG_DEBUG => Level::Debug,
+        _ => Level::Trace,
+    }
+}
+
+/// Forward FFmpeg logs to the `log` crate, under the `ffmpeg` target
+/// followed by the component name, as in `ffmpeg::h264`.
+#[cfg(feature = "log")]
+pub fn forward_to_log() {
+    use rust_log::{logger, Level as LogLevel, Record};
+
+    set_callback(|message| {
+        let level = match message.level {
+            Level::Quiet => return,
+            Level::Panic | Level::Fatal | Level::Error => LogLevel::Error,
+            Level::Warning => LogLevel::Warn,
+            Level::Info => LogLevel::Info,
+            Level::Verbose | Level::Debug => LogLevel::Debug,
+            Level::Trace => LogLevel::Trace,
+        };
+
+        let target = match message.class {
+            Some(class) => format!("ffmpeg::{}", class),
+            None => "ffmpeg".to_owned(),
+        };
+
+        logger().log(
+            &Record::builder()
+                .level(level)
+                .target(&target)
+                .args(format_args!("{}", message.text))
+                .build(),
+        );
+    });
+}
+
+/// Forward FFmpeg logs to `tracing` events with the `ffmpeg` target, the
+/// component name being recorded in the `class` field.
+#[cfg(feature = "tracing")]
+pub fn forward_to_tracing() {
+    use tracing::{debug, error, info, trace, warn};
+
+    set_callback(|message| {
+        let class = message.class.unwrap_or("");
+        let text = message.text;
+
+        match message.level {
+            Level::Quiet => (),
+            Level::Panic | Level::Fatal | Level::Error => {
+                error!(target: "ffmpeg", class, "{}", text)
+            }
+            Level::Warning => warn!(target: "ffmpeg", class, "{}", text),
+            Level::Info => info!(target: "ffmpeg", class, "{}", text),
+            Level::Verbose | Level::Debug => debug!(target: "ffmpeg", class, "{}", text),
+            Level::Trace => trace!(target: "ffmpeg", class, "{}", text),
+        }
+    });
+}
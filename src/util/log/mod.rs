@@ -4,6 +4,14 @@ pub use self::level::Level;
 pub mod flag;
 pub use self::flag::Flags;
 
+pub mod callback;
+pub use self::callback::{reset_callback, set_callback, Message};
+
+#[cfg(feature = "log")]
+pub use self::callback::forward_to_log;
+#[cfg(feature = "tracing")]
+pub use self::callback::forward_to_tracing;
+
 use ffi::*;
 use std::convert::TryInto;
 
@@ -1,4 +1,5 @@
 use ffi::*;
+use libc::c_int;
 use {Rational, Rounding};
 
 pub const TIME_BASE: Rational = Rational(AV_TIME_BASE_Q.num, AV_TIME_BASE_Q.den);
@@ -45,3 +46,52 @@ impl<T: Into<i64> + Clone> Rescale for T {
         }
     }
 }
+
+/// Rescale the timestamps of consecutive audio frames or packets, carrying
+/// the rounding remainder over from one to the next with `av_rescale_delta`.
+///
+/// As long as timestamps follow on from each other, the rescaled ones do too
+/// and stay sample accurate, instead of each being rounded on its own.
+/// A discontinuity resynchronizes on the incoming timestamp.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Delta {
+    source: Rational,
+    destination: Rational,
+    samples: Rational,
+    last: i64,
+}
+
+impl Delta {
+    /// Rescale from `source` to `destination` for audio sampled at `rate`.
+    pub fn new<S, D>(source: S, destination: D, rate: u32) -> Self
+    where
+        S: Into<Rational>,
+        D: Into<Rational>,
+    {
+        Delta {
+            source: source.into(),
+            destination: destination.into(),
+            samples: Rational(1, rate as i32),
+            last: AV_NOPTS_VALUE,
+        }
+    }
+
+    /// Rescale the timestamp of a frame or packet holding `samples` samples.
+    pub fn rescale(&mut self, ts: i64, samples: usize) -> i64 {
+        unsafe {
+            av_rescale_delta(
+                self.source.into(),
+                ts,
+                self.samples.into(),
+                samples as c_int,
+                &mut self.last,
+                self.destination.into(),
+            )
+        }
+    }
+
+    /// Forget the carried remainder, as after a seek.
+    pub fn reset(&mut self) {
+        self.last = AV_NOPTS_VALUE;
+    }
+}
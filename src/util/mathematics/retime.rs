@@ -0,0 +1,168 @@
+use ffi::*;
+use {Frame, Rational, Rounding};
+
+#[cfg(feature = "software-resampling")]
+use software::resampling;
+#[cfg(feature = "software-resampling")]
+use {format, ChannelLayout, Error};
+
+/// How retimed audio is played back.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Pitch {
+    /// Stretch the audio in time only, with the `atempo` filter.
+    Preserve,
+    /// Resample as if the tape ran faster or slower, shifting the pitch.
+    Scale,
+}
+
+/// Map timestamps of a source timeline onto one played back at `speed`,
+/// `2/1` playing twice as fast.
+///
+/// Timestamps at or after `origin` land at `offset` plus their distance to
+/// `origin` divided by the speed, all in the same time base. Speed ramps are
+/// built by chaining mappings with `then`, which keeps the output continuous
+/// at the switch point.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Retime {
+    speed: Rational,
+    origin: i64,
+    offset: i64,
+}
+
+impl Retime {
+    pub fn new<R: Into<Rational>>(speed: R) -> Self {
+        Retime::with_origin(speed, 0, 0)
+    }
+
+    pub fn with_origin<R: Into<Rational>>(speed: R, origin: i64, offset: i64) -> Self {
+        let speed = speed.into();
+        assert!(
+            speed.numerator() > 0 && speed.denominator() > 0,
+            "speed must be positive"
+        );
+
+        Retime {
+            speed,
+            origin,
+            offset,
+        }
+    }
+
+    pub fn speed(&self) -> Rational {
+        self.speed
+    }
+
+    pub fn origin(&self) -> i64 {
+        self.origin
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    /// Continue at `speed` from the source timestamp `at`.
+    pub fn then<R: Into<Rational>>(&self, at: i64, speed: R) -> Retime {
+        Retime::with_origin(speed, at, self.map(at))
+    }
+
+    /// Map a timestamp, rounding to the nearest tick.
+    pub fn map(&self, ts: i64) -> i64 {
+        self.offset + self.duration(ts - self.origin)
+    }
+
+    /// Scale a duration, rounding to the nearest tick.
+    pub fn duration(&self, value: i64) -> i64 {
+        unsafe {
+            av_rescale_rnd(
+                value,
+                i64::from(self.speed.denominator()),
+                i64::from(self.speed.numerator()),
+                Rounding::NearInfinity.into(),
+            )
+        }
+    }
+
+    /// Retime the pts and duration of a frame, in the time base of its
+    /// timestamps.
+    ///
+    /// The samples of an audio frame are left untouched, see `filter` and
+    /// `resampler` to make them match the new duration.
+    pub fn frame(&self, frame: &mut Frame) {
+        let pts = frame.pts().map(|pts| self.map(pts));
+        frame.set_pts(pts);
+
+        unsafe {
+            let duration = self.duration(av_frame_get_pkt_duration(frame.as_ptr()));
+            av_frame_set_pkt_duration(frame.as_mut_ptr(), duration);
+        }
+    }
+
+    /// A filter chain that changes the tempo of audio sampled at `rate` to
+    /// match the speed, to be parsed into a filter graph.
+    pub fn filter(&self, pitch: Pitch, rate: u32) -> String {
+        match pitch {
+            Pitch::Preserve => {
+                let mut factor = f64::from(self.speed);
+                let mut chain = Vec::new();
+
+                // atempo accepts factors in [0.5, 2] on older versions.
+                while factor > 2.0 {
+                    chain.push("atempo=2".to_owned());
+                    factor /= 2.0;
+                }
+
+                while factor < 0.5 {
+                    chain.push("atempo=0.5".to_owned());
+                    factor /= 0.5;
+                }
+
+                chain.push(format!("atempo={}", factor));
+                chain.join(",")
+            }
+
+            Pitch::Scale => format!("asetrate={},aresample={}", self.scaled_rate(rate), rate),
+        }
+    }
+
+    /// A resampler shifting the pitch of audio sampled at `rate` along with
+    /// the speed, as `Pitch::Scale` does, while keeping its sample rate.
+    #[cfg(feature = "software-resampling")]
+    pub fn resampler(
+        &self,
+        format: format::Sample,
+        layout: ChannelLayout,
+        rate: u32,
+    ) -> Result<resampling::Context, Error> {
+        resampling::Context::get(format, layout, self.scaled_rate(rate), format, layout, rate)
+    }
+
+    fn scaled_rate(&self, rate: u32) -> u32 {
+        unsafe {
+            av_rescale_rnd(
+                i64::from(rate),
+                i64::from(self.speed.numerator()),
+                i64::from(self.speed.denominator()),
+                Rounding::NearInfinity.into(),
+            ) as u32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramp() {
+        let normal = Retime::new(Rational(1, 1));
+        let fast = normal.then(1000, Rational(2, 1));
+        let slow = fast.then(3000, Rational(1, 2));
+
+        assert_eq!(normal.map(500), 500);
+        assert_eq!(fast.map(1000), 1000);
+        assert_eq!(fast.map(3000), 2000);
+        assert_eq!(slow.map(3000), 2000);
+        assert_eq!(slow.map(3500), 3000);
+        assert_eq!(fast.duration(33), 17);
+    }
+}
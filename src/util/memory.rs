@@ -0,0 +1,12 @@
+use ffi::*;
+
+/// Cap the size of any single allocation made by FFmpeg, process-wide.
+///
+/// Larger allocations fail, which surfaces as `Error::Other { errno: ENOMEM }`
+/// from the call that needed them, not `Error::Limit`, since FFmpeg does not
+/// tell them apart from running out of memory. The default is `INT_MAX`.
+pub fn set_max_alloc(size: usize) {
+    unsafe {
+        av_max_alloc(size as _);
+    }
+}
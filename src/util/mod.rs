@@ -1,15 +1,20 @@
 #[macro_use]
 pub mod dictionary;
+pub mod cancel;
 pub mod channel_layout;
 pub mod chroma;
 pub mod color;
+pub mod dimensions;
 pub mod error;
 pub mod format;
 pub mod frame;
+pub mod hdr;
+pub mod hwcontext;
 pub mod interrupt;
 pub mod log;
 pub mod mathematics;
 pub mod media;
+pub mod memory;
 pub mod option;
 pub mod picture;
 pub mod range;
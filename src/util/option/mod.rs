@@ -1,8 +1,13 @@
 mod traits;
 pub use self::traits::{Gettable, Iterable, Settable, Target};
 
+use std::ffi::CStr;
+use std::ptr;
+
 use ffi::AVOptionType::*;
 use ffi::*;
+use libc::{c_char, c_void};
+use {Dictionary, Error};
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum Type {
@@ -81,3 +86,38 @@ impl From<Type> for AVOptionType {
         }
     }
 }
+
+/// The options of `obj`, an `AVClass` enabled struct, as strings, leaving out
+/// those still at their default unless `defaults` is set.
+///
+/// Options of child objects, such as the private options of a codec, are not
+/// included.
+pub unsafe fn serialize(obj: *const c_void, defaults: bool) -> Result<Dictionary<'static>, Error> {
+    let mut buffer = ptr::null_mut();
+    let flags = if defaults {
+        0
+    } else {
+        AV_OPT_SERIALIZE_SKIP_DEFAULTS
+    };
+
+    match av_opt_serialize(
+        obj as *mut _,
+        0,
+        flags as _,
+        &mut buffer,
+        b'=' as c_char,
+        b':' as c_char,
+    ) {
+        e if e < 0 => return Err(Error::from(e)),
+        _ => (),
+    }
+
+    if buffer.is_null() {
+        return Ok(Dictionary::new());
+    }
+
+    let result = Dictionary::parse(&CStr::from_ptr(buffer).to_string_lossy(), "=", ":");
+    av_free(buffer as *mut c_void);
+
+    result
+}
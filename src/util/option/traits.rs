@@ -1,12 +1,13 @@
 //! NOTE: this will be much better once specialization comes
 
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
 use std::mem;
+use std::ptr;
 
 use ffi::*;
 use libc::{c_int, c_void};
 use util::format;
-use {ChannelLayout, Error, Rational};
+use {ChannelLayout, Dictionary, Error, Rational};
 
 macro_rules! check {
     ($expr:expr) => {
@@ -144,6 +145,84 @@ pub trait Settable: Target {
     }
 }
 
-pub trait Gettable: Target {}
+pub trait Gettable: Target {
+    /// Every option as a string, or only those changed from their default.
+    fn options(&self, defaults: bool) -> Result<Dictionary<'static>, Error> {
+        unsafe { super::serialize(self.as_ptr(), defaults) }
+    }
+
+    fn get_str(&self, name: &str) -> Result<String, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = ptr::null_mut();
+
+            check!(av_opt_get(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))?;
+
+            if value.is_null() {
+                return Ok(String::new());
+            }
+
+            let result = CStr::from_ptr(value as *const _)
+                .to_string_lossy()
+                .into_owned();
+            av_free(value as *mut c_void);
+
+            Ok(result)
+        }
+    }
+
+    fn get_int(&self, name: &str) -> Result<i64, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = 0;
+
+            check!(av_opt_get_int(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))?;
+
+            Ok(value)
+        }
+    }
+
+    fn get_double(&self, name: &str) -> Result<f64, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = 0.0;
+
+            check!(av_opt_get_double(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))?;
+
+            Ok(value)
+        }
+    }
+
+    fn get_rational(&self, name: &str) -> Result<Rational, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let mut value = AVRational { num: 0, den: 1 };
+
+            check!(av_opt_get_q(
+                self.as_ptr() as *mut _,
+                name.as_ptr(),
+                AV_OPT_SEARCH_CHILDREN,
+                &mut value
+            ))?;
+
+            Ok(Rational::from(value))
+        }
+    }
+}
 
 pub trait Iterable: Target {}
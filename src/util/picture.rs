@@ -1,3 +1,5 @@
+use std::fmt;
+
 use ffi::AVPictureType::*;
 use ffi::*;
 
@@ -44,3 +46,27 @@ impl From<Type> for AVPictureType {
         }
     }
 }
+
+impl Type {
+    /// Whether the picture is decodable on its own.
+    #[inline]
+    pub fn is_intra(&self) -> bool {
+        match *self {
+            Type::I | Type::SI | Type::BI => true,
+            _ => false,
+        }
+    }
+
+    /// The single letter FFmpeg uses for the type in its logs, `?` for
+    /// `None`.
+    #[inline]
+    pub fn as_char(&self) -> char {
+        unsafe { av_get_picture_type_char((*self).into()) as u8 as char }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
@@ -1,9 +1,14 @@
 use std::cmp::Ordering;
 use std::fmt;
 use std::ops::{Add, Div, Mul, Sub};
+use std::str::FromStr;
+use std::time::Duration;
 
 use ffi::*;
-use libc::c_int;
+use libc::{c_int, EDOM, ERANGE};
+use {Error, Rescale};
+
+const NANOSECONDS: Rational = Rational(1, 1_000_000_000);
 
 #[derive(Copy, Clone)]
 pub struct Rational(pub i32, pub i32);
@@ -58,6 +63,141 @@ impl Rational {
     pub fn invert(&self) -> Rational {
         unsafe { Rational::from(av_inv_q((*self).into())) }
     }
+
+    /// Approximate `value` with a denominator of at most `max`.
+    #[inline]
+    pub fn from_f64_with_limit(value: f64, max: i32) -> Rational {
+        unsafe { Rational::from(av_d2q(value, max)) }
+    }
+
+    /// Parse like `FromStr`, approximating decimals with a denominator of at
+    /// most `max`. Fractions are taken as they are.
+    pub fn parse_with_limit(value: &str, max: i32) -> Result<Rational, Error> {
+        let value = value.trim();
+
+        if let Some(index) = value.find(|c| c == '/' || c == ':') {
+            let numerator = value[..index].trim().parse().map_err(|_| Error::InvalidData)?;
+            let denominator = value[index + 1..].trim().parse().map_err(|_| Error::InvalidData)?;
+
+            return Ok(Rational(numerator, denominator));
+        }
+
+        if let Ok(integer) = value.parse::<i32>() {
+            return Ok(Rational(integer, 1));
+        }
+
+        match value.parse::<f64>() {
+            Ok(float) if float.is_finite() => Ok(Rational::from_f64_with_limit(float, max)),
+            _ => Err(Error::InvalidData),
+        }
+    }
+
+    /// Add exactly, failing with `ERANGE` when the reduced result does not
+    /// fit in 32 bits instead of approximating it as `+` does.
+    pub fn checked_add(self, other: Rational) -> Result<Rational, Error> {
+        let (a, b) = (wide(self), wide(other));
+
+        exact(
+            a.0.checked_mul(b.1)
+                .and_then(|x| b.0.checked_mul(a.1).and_then(|y| x.checked_add(y))),
+            a.1.checked_mul(b.1),
+        )
+    }
+
+    pub fn checked_sub(self, other: Rational) -> Result<Rational, Error> {
+        self.checked_add(Rational(other.0.checked_neg().ok_or(RANGE)?, other.1))
+    }
+
+    pub fn checked_mul(self, other: Rational) -> Result<Rational, Error> {
+        let (a, b) = (wide(self), wide(other));
+
+        exact(a.0.checked_mul(b.0), a.1.checked_mul(b.1))
+    }
+
+    /// Divide exactly, failing with `EDOM` when `other` is zero.
+    pub fn checked_div(self, other: Rational) -> Result<Rational, Error> {
+        if other.0 == 0 {
+            return Err(Error::Other { errno: EDOM });
+        }
+
+        self.checked_mul(Rational(other.1, other.0))
+    }
+
+    /// Compare the distances of `a` and `b` to `self`, `Ordering::Less`
+    /// meaning `a` is nearer.
+    #[inline]
+    pub fn nearer(&self, a: Rational, b: Rational) -> Ordering {
+        nearer(*self, a, b).reverse()
+    }
+
+    /// Index of the entry of `list` nearest to `self`.
+    pub fn nearest(&self, list: &[Rational]) -> Option<usize> {
+        if list.is_empty() {
+            return None;
+        }
+
+        let mut terminated = list
+            .iter()
+            .map(|&q| AVRational::from(q))
+            .collect::<Vec<_>>();
+        terminated.push(AVRational { num: 0, den: 0 });
+
+        unsafe { Some(av_find_nearest_q_idx((*self).into(), terminated.as_ptr()) as usize) }
+    }
+
+    /// Convert a timestamp in this time base to a `Duration`, rounded to the
+    /// nearest nanosecond. Negative timestamps and `AV_NOPTS_VALUE` have
+    /// none.
+    pub fn duration(&self, ts: i64) -> Option<Duration> {
+        if ts < 0 || ts == AV_NOPTS_VALUE || self.0 <= 0 || self.1 <= 0 {
+            return None;
+        }
+
+        let nanoseconds = ts.rescale(*self, NANOSECONDS);
+
+        Some(Duration::new(
+            (nanoseconds / 1_000_000_000) as u64,
+            (nanoseconds % 1_000_000_000) as u32,
+        ))
+    }
+
+    /// Convert `duration` to a timestamp in this time base, rounded to the
+    /// nearest unit.
+    pub fn timestamp(&self, duration: Duration) -> i64 {
+        let seconds = (duration.as_secs() as i64).rescale((1, 1), *self);
+        let rest = i64::from(duration.subsec_nanos()).rescale(NANOSECONDS, *self);
+
+        seconds + rest
+    }
+}
+
+const RANGE: Error = Error::Other { errno: ERANGE };
+
+fn wide(q: Rational) -> (i64, i64) {
+    (i64::from(q.0), i64::from(q.1))
+}
+
+fn exact(numerator: Option<i64>, denominator: Option<i64>) -> Result<Rational, Error> {
+    let (numerator, denominator) = match (numerator, denominator) {
+        (Some(n), Some(d)) => (n, d),
+        _ => return Err(RANGE),
+    };
+
+    let mut num: c_int = 0;
+    let mut den: c_int = 0;
+
+    unsafe {
+        match av_reduce(
+            &mut num,
+            &mut den,
+            numerator,
+            denominator,
+            i64::from(i32::max_value()),
+        ) {
+            1 => Ok(Rational(num, den)),
+            _ => Err(RANGE),
+        }
+    }
 }
 
 impl From<AVRational> for Rational {
@@ -104,6 +244,16 @@ impl From<(i32, i32)> for Rational {
     }
 }
 
+/// Parse `num/den`, `num:den`, an integer or a decimal number such as
+/// `29.97`, the latter converted to the nearest fraction.
+impl FromStr for Rational {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Error> {
+        Rational::parse_with_limit(value, c_int::max_value())
+    }
+}
+
 impl PartialEq for Rational {
     fn eq(&self, other: &Rational) -> bool {
         if self.0 == other.0 && self.1 == other.1 {
@@ -200,3 +350,66 @@ pub fn nearer(q: Rational, q1: Rational, q2: Rational) -> Ordering {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(
+            Rational(1, 2).checked_add(Rational(1, 3)),
+            Ok(Rational(5, 6))
+        );
+        assert_eq!(
+            Rational(1, 2).checked_sub(Rational(1, 3)),
+            Ok(Rational(1, 6))
+        );
+        assert_eq!(
+            Rational(2, 3).checked_div(Rational(4, 9)),
+            Ok(Rational(3, 2))
+        );
+        assert!(Rational(1, i32::max_value())
+            .checked_mul(Rational(1, 3))
+            .is_err());
+        assert!(Rational(1, 2).checked_div(Rational(0, 1)).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("30000/1001".parse(), Ok(Rational(30000, 1001)));
+        assert_eq!("16:9".parse(), Ok(Rational(16, 9)));
+        assert_eq!(" 25 ".parse(), Ok(Rational(25, 1)));
+        assert_eq!("0.5".parse(), Ok(Rational(1, 2)));
+        assert_eq!(
+            Rational::parse_with_limit("0.333333", 10),
+            Ok(Rational(1, 3))
+        );
+        assert!("1/x".parse::<Rational>().is_err());
+        assert!("inf".parse::<Rational>().is_err());
+        assert_eq!(
+            Rational(30000, 1001).to_string().parse(),
+            Ok(Rational(30000, 1001))
+        );
+    }
+
+    #[test]
+    fn test_duration() {
+        let time_base = Rational(1, 90000);
+
+        assert_eq!(
+            time_base.duration(135000),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(time_base.duration(-1), None);
+        assert_eq!(time_base.timestamp(Duration::from_millis(1500)), 135000);
+    }
+
+    #[test]
+    fn test_nearest() {
+        let rates = [Rational(24, 1), Rational(25, 1), Rational(30, 1)];
+
+        assert_eq!(Rational(30000, 1001).nearest(&rates), Some(2));
+        assert_eq!(Rational(1, 1).nearest(&[]), None);
+    }
+}
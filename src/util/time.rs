@@ -1,5 +1,9 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::time::Duration;
+
 use ffi::*;
-use Error;
+use {Error, Rational, Rescale, Rounding};
 
 #[inline(always)]
 pub fn current() -> i64 {
@@ -25,3 +29,147 @@ pub fn sleep(usec: u32) -> Result<(), Error> {
         }
     }
 }
+
+/// A timestamp along with the time base it is expressed in, possibly
+/// `AV_NOPTS_VALUE`.
+///
+/// Timestamps compare across time bases, an unset one being equal to other
+/// unset ones and unordered against anything else.
+#[derive(Clone, Copy)]
+pub struct Timestamp {
+    value: i64,
+    time_base: Rational,
+}
+
+impl Timestamp {
+    #[inline]
+    pub fn new(value: i64, time_base: Rational) -> Self {
+        Timestamp { value, time_base }
+    }
+
+    #[inline]
+    pub fn none(time_base: Rational) -> Self {
+        Timestamp::new(AV_NOPTS_VALUE, time_base)
+    }
+
+    #[inline]
+    pub fn from_option(value: Option<i64>, time_base: Rational) -> Self {
+        Timestamp::new(value.unwrap_or(AV_NOPTS_VALUE), time_base)
+    }
+
+    #[inline]
+    pub fn value(&self) -> Option<i64> {
+        match self.value {
+            AV_NOPTS_VALUE => None,
+            value => Some(value),
+        }
+    }
+
+    #[inline]
+    pub fn time_base(&self) -> Rational {
+        self.time_base
+    }
+
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        self.value == AV_NOPTS_VALUE
+    }
+
+    #[inline]
+    pub fn is_some(&self) -> bool {
+        !self.is_none()
+    }
+
+    /// Express the timestamp in `time_base`, an unset one staying unset.
+    pub fn rescale_to<R: Into<Rational>>(&self, time_base: R, rounding: Rounding) -> Self {
+        let time_base = time_base.into();
+
+        match self.value() {
+            Some(value) => Timestamp::new(
+                value.rescale_with(self.time_base, time_base, rounding),
+                time_base,
+            ),
+            None => Timestamp::none(time_base),
+        }
+    }
+
+    #[inline]
+    pub fn seconds(&self) -> Option<f64> {
+        self.value()
+            .map(|value| value as f64 * f64::from(self.time_base))
+    }
+
+    /// The time elapsed since zero, when set and not negative.
+    #[inline]
+    pub fn duration(&self) -> Option<Duration> {
+        self.time_base.duration(self.value)
+    }
+}
+
+impl PartialEq for Timestamp {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.is_none(), other.is_none()) {
+            (true, true) => true,
+            (false, false) => self.partial_cmp(other) == Some(Ordering::Equal),
+            _ => false,
+        }
+    }
+}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.is_none() || other.is_none() {
+            return if self.is_none() && other.is_none() {
+                Some(Ordering::Equal)
+            } else {
+                None
+            };
+        }
+
+        unsafe {
+            match av_compare_ts(
+                self.value,
+                self.time_base.into(),
+                other.value,
+                other.time_base.into(),
+            ) {
+                -1 => Some(Ordering::Less),
+                0 => Some(Ordering::Equal),
+                _ => Some(Ordering::Greater),
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value() {
+            Some(value) => write!(f, "Timestamp({} @ {})", value, self.time_base),
+            None => write!(f, "Timestamp(NOPTS @ {})", self.time_base),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp() {
+        let a = Timestamp::new(90000, Rational(1, 90000));
+        let b = Timestamp::new(1000, Rational(1, 1000));
+
+        assert_eq!(a, b);
+        assert!(Timestamp::new(1001, Rational(1, 1000)) > a);
+        assert_eq!(
+            a.rescale_to((1, 48000), Rounding::NearInfinity).value(),
+            Some(48000)
+        );
+
+        let none = Timestamp::none(Rational(1, 90000));
+
+        assert_eq!(none, Timestamp::none(Rational(1, 1000)));
+        assert_eq!(none.partial_cmp(&a), None);
+        assert!(none.rescale_to((1, 1000), Rounding::Zero).is_none());
+    }
+}